@@ -1,7 +1,16 @@
+use super::completions::{complete_job, complete_task, Shell};
 use super::day_parser::parse_days_of_week;
-use crate::domain::models::{day, line_number::LineNumber};
+use super::period_parser::{parse_dates, parse_month, parse_week_range, WeekRange};
+use crate::domain::models::{
+    day,
+    hours::{Hours, HoursChange},
+    line_number::LineNumber,
+    period,
+};
 use clap::{Parser, Subcommand};
+use clap_complete::ArgValueCompleter;
 use color_print::cformat;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Parser, Debug)]
@@ -29,11 +38,11 @@ pub(crate) struct Week {
 #[derive(Parser, Debug)]
 pub(crate) struct Task {
     /// Name of the job
-    #[arg(long, short)]
+    #[arg(long, short, add = ArgValueCompleter::new(complete_job))]
     pub(crate) job: String,
 
     /// Name of the task
-    #[arg(long = "task", short = 't')]
+    #[arg(long = "task", short = 't', add = ArgValueCompleter::new(complete_task))]
     pub(crate) name: String,
 }
 
@@ -52,6 +61,73 @@ pub(crate) struct Days {
 
     #[command(flatten)]
     pub(crate) week: Week,
+
+    /// Date(s) instead of day(s) of a week, for example "2026-10-14", "yesterday" or "last fri"
+    ///
+    /// Also accepts a range of at most 31 dates, which may span several weeks, for example
+    /// "2026-10-12..2026-10-16" or "last mon..today". Saturdays and Sundays within the range are
+    /// skipped, unless the range starts or ends on them
+    #[arg(
+        long = "date",
+        value_parser = parse_dates,
+        conflicts_with_all = ["days", "number", "previous", "year"],
+    )]
+    pub(crate) dates: Option<period::Period>,
+}
+
+#[derive(Parser, Debug)]
+#[group(required = true, multiple = false)]
+pub(crate) struct SourceWeek {
+    /// Copy from the N:th week before the target week (defaults to 1 if N is omitted)
+    #[arg(
+        long = "from-previous-week",
+        value_name = "N",
+        default_missing_value = Some("1"),
+        num_args(0..=1),
+    )]
+    pub(crate) from_previous: Option<u8>,
+
+    /// Copy from this week number, in the same year as the target week
+    #[arg(long = "from-week", value_name = "WEEK")]
+    pub(crate) from_week: Option<u8>,
+}
+
+#[derive(Parser, Debug)]
+#[group(required = true, multiple = false)]
+pub(crate) struct Period {
+    /// Month, for example "2026-10"
+    #[arg(long, short, value_parser = parse_month)]
+    pub(crate) month: Option<period::Period>,
+
+    /// Range of week numbers, for example "40-44"
+    #[arg(long, short, value_parser = parse_week_range)]
+    pub(crate) weeks: Option<WeekRange>,
+}
+
+/// Where imported entries come from
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub(crate) enum ImportSource {
+    /// CSV with the columns date, job, task, hours and optionally comment
+    Csv,
+    /// JSON from `timew export`
+    Timewarrior,
+    /// A detailed report as CSV, or time entries from the API as JSON
+    Toggl,
+    /// A detailed report as CSV or JSON, or time entries from the API as JSON
+    Clockify,
+}
+
+impl ImportSource {
+    /// The section of the configuration with the rules that map entries to jobs and tasks, for
+    /// sources that don't name them
+    pub(crate) fn settings_section(self) -> Option<&'static str> {
+        match self {
+            ImportSource::Csv => None,
+            ImportSource::Timewarrior => Some("timewarrior"),
+            ImportSource::Toggl => Some("toggl"),
+            ImportSource::Clockify => Some("clockify"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, clap::ValueEnum)]
@@ -72,6 +148,26 @@ impl FromStr for Format {
     }
 }
 
+/// Formats of reports for handing over to clients
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// iCalendar, with an all-day event per day and line
+    Ics,
+    /// A printable page with the hours per job and per day
+    Html,
+}
+
+/// Output formats of a single time sheet
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub(crate) enum TimeSheetFormat {
+    Table,
+    Json,
+    Csv,
+    Tsv,
+    Markdown,
+    Yaml,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Line {
     /// Delete line based on line number (1-indexed)
@@ -80,6 +176,37 @@ pub enum Line {
 
         #[command(flatten)]
         week: Week,
+
+        /// Delete the line even if the week has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Delete the line without asking first. Nothing is asked when stdin isn't a terminal
+        #[arg(long)]
+        yes: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Set the comment of a line based on line number (1-indexed)
+    Comment {
+        line_number: LineNumber,
+
+        /// Comment to set. An empty comment removes the comment
+        comment: String,
+
+        #[command(flatten)]
+        week: Week,
+
+        /// Change the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
     },
 }
 
@@ -89,23 +216,185 @@ pub enum Command {
     Get {
         /// Output format
         #[arg(long, short, default_value = "table")]
-        format: Format,
+        format: TimeSheetFormat,
 
         #[command(flatten)]
         week: Week,
     },
 
+    /// Show the hours of several weeks or a month, per day and per job
+    ///
+    /// Days with fewer hours than the norm time are highlighted
+    Overview {
+        #[command(flatten)]
+        period: Period,
+
+        /// Year of `--weeks` (defaults to current year if omitted)
+        #[arg(long, short, requires = "weeks")]
+        year: Option<i32>,
+
+        /// Output format
+        #[arg(long, short, default_value = "table")]
+        format: Format,
+    },
+
+    /// Export the hours of several weeks or a month as a calendar or a printable report
+    ///
+    /// The report is printed to standard output, for example to redirect to a file
+    Export {
+        #[command(flatten)]
+        period: Period,
+
+        /// Year of `--weeks` (defaults to current year if omitted)
+        #[arg(long, short, requires = "weeks")]
+        year: Option<i32>,
+
+        /// Export format
+        #[arg(long, short)]
+        format: ExportFormat,
+    },
+
     /// Set number of hours on day(s) for a given job and task
     Set {
-        // TODO: change to string that allows "4:30" hours, etc.
         /// Number of hours to set
-        hours: f32,
+        ///
+        /// Accepts for example "8", "4:30", "4h30m", "4.5" and "90m"
+        ///
+        /// Prefix with "+" or "-" to add to or subtract from the current hours, for example "+1:30"
+        #[arg(allow_hyphen_values = true)]
+        hours: HoursChange,
 
         #[command(flatten)]
         task: Task,
 
         #[command(flatten)]
         days: Days,
+        /// Comment to set on the line
+        #[arg(long)]
+        comment: Option<String>,
+
+        /// Change the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Add hours to day(s) for a given job and task, on top of the current hours
+    Add {
+        /// Number of hours to add, for example "1:30"
+        hours: Hours,
+
+        #[command(flatten)]
+        task: Task,
+
+        #[command(flatten)]
+        days: Days,
+        /// Comment to set on the line
+        #[arg(long)]
+        comment: Option<String>,
+
+        /// Change the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Copy the lines of another week into a week
+    ///
+    /// Lines that the target week already has are skipped
+    Copy {
+        #[command(flatten)]
+        source: SourceWeek,
+
+        /// Also copy the hours of the lines
+        #[arg(long)]
+        with_hours: bool,
+
+        /// Target week
+        #[command(flatten)]
+        week: Week,
+
+        /// Change the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Fill in a week from a template in the configuration
+    ///
+    /// Templates are configured as `[templates.<name>]` with a list of job, task, days and hours
+    /// entries
+    Apply {
+        /// Name of the template
+        template: String,
+
+        #[command(flatten)]
+        week: Week,
+
+        /// Change the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format (defaults to table with `--dry-run`)
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Import hours from a CSV file with the columns date, job, task, hours and optionally comment
+    ///
+    /// The hours replace the hours that are already registered on the same day, job and task. Jobs
+    /// and tasks are matched the same way as `--job` and `--task`, and missing lines are added.
+    /// Nothing is imported if any row can't be imported.
+    ///
+    /// Exports of timewarrior, Toggl Track and Clockify can be imported with `--from`. Their
+    /// entries are mapped to a job and task by the rules in for example `[timewarrior]` of the
+    /// configuration, the same way as with `import-ics`
+    Import {
+        /// Path of the file, which needs a header row if it's CSV
+        file: PathBuf,
+
+        /// The tool that the file is exported from
+        #[arg(long, value_enum, default_value_t = ImportSource::Csv)]
+        from: ImportSource,
+
+        /// Import into weeks even if they have been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheets in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Import the events of a week from an iCalendar (.ics) file
+    ///
+    /// Events are mapped to a job and task by the rules in `[ics]` of the configuration, and the
+    /// first rule that applies is used. Durations are rounded to `ics.granularity` (15 minutes by
+    /// default) and summed per day, and they replace the hours that are already registered on
+    /// the same day, job and task. The changes are shown before they are saved
+    ImportIcs {
+        /// Path of the .ics file
+        file: PathBuf,
+
+        #[command(flatten)]
+        week: Week,
+
+        /// Import into the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
     },
 
     /// Remove hours on day(s) for a given job and task
@@ -115,17 +404,153 @@ pub enum Command {
 
         #[command(flatten)]
         days: Days,
+
+        /// Change the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Start a timer for a job and task, which books its time when it's stopped
+    ///
+    /// A timer that is already running is stopped first. The timer is kept in a file, so it keeps
+    /// running when the terminal is closed or the computer restarts
+    Start {
+        #[command(flatten)]
+        task: Task,
+
+        /// Book the time of a running timer even if today's week has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Print the time sheets that booking a running timer changes in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Stop the timer, and add its time to the hours of its job and task on the days it ran
+    ///
+    /// The time of each day is rounded to `timer.granularity` of the configuration (15 minutes by
+    /// default)
+    Stop {
+        /// Book the time even if its week has been submitted or approved
+        #[arg(long)]
+        force: bool,
+
+        /// Stop the timer without booking its time, for example when booking it fails
+        #[arg(long, conflicts_with = "force")]
+        discard: bool,
+
+        /// Print the time sheets that booking the timer changes in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// Show the running timer
+    Status,
+
+    /// Edit the time sheet for a week interactively
+    Edit {
+        #[command(flatten)]
+        week: Week,
+
+        /// Edit the week even if it has been submitted or approved
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List jobs, optionally filtered by a search query
+    Jobs {
+        /// Only list jobs whose number, name or customer contains this text
+        query: Option<String>,
+
+        /// Output format
+        #[arg(long, short, default_value = "table")]
+        format: Format,
+    },
+
+    /// List the tasks of a job
+    Tasks {
+        /// Name of the job
+        #[arg(long, short, add = ArgValueCompleter::new(complete_job))]
+        job: String,
+
+        /// Output format
+        #[arg(long, short, default_value = "table")]
+        format: Format,
+    },
+
+    /// Check the time sheet for week against the checks in the configuration
+    ///
+    /// Exits with an error if any check fails
+    Check {
+        #[command(flatten)]
+        week: Week,
     },
 
     /// Submit time sheet for week
+    ///
+    /// Refuses if any of the checks in the configuration fails. Warns about days with less regular
+    /// time than expected, and about the checks that fail when it's forced
     Submit {
         #[command(flatten)]
         week: Week,
+
+        /// Submit without asking first. Nothing is asked when stdin isn't a terminal
+        #[arg(long)]
+        yes: bool,
+
+        /// Submit even if some of the checks fail
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheet in this format
+        #[arg(long, short)]
+        format: Option<Format>,
     },
 
+    /// Undo the latest changes made with maconomy
+    ///
+    /// Changes are undone one command at a time, from the newest. Deleted lines are added again
+    /// at the end of the time sheet, and submitting can't be undone
+    Undo {
+        /// Number of commands to undo
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+
+        /// Undo changes to weeks that have been submitted or approved, and hours that have been
+        /// changed since
+        #[arg(long)]
+        force: bool,
+
+        /// Print the resulting time sheets in this format
+        #[arg(long, short)]
+        format: Option<Format>,
+    },
+
+    /// List the latest changes made with maconomy, which `undo` can undo
+    History {
+        /// Number of commands to list
+        #[arg(long, short = 'n', default_value_t = 10)]
+        limit: usize,
+    },
+
+    /// Print the JSON Schema of the time sheets printed with `--format json`
+    Schema,
+
     /// Log out
     Logout,
 
+    /// Print a shell completion script
+    ///
+    /// For example, add `source <(maconomy completions bash)` to ~/.bashrc. Job and task names are
+    /// completed from the jobs, tasks and lines that maconomy has seen, and are refreshed by
+    /// running `maconomy get`, `maconomy jobs` and `maconomy tasks`
+    Completions { shell: Shell },
+
     /// Operate on entire lines in the time sheet
     #[command(subcommand)]
     Line(Line),
@@ -139,11 +564,36 @@ pub enum Command {
     arg_required_else_help = true,
     after_help = cformat!("<bold,underline>Examples:</bold,underline>\
     \n  maconomy get \
+    \n  maconomy get --format csv --week 42 > week-42.csv \
+    \n  maconomy set 8 --job '<<job name>>' --task '<<task name>>' --format json \
+    \n  maconomy overview --month 2026-10 \
+    \n  maconomy overview --weeks 40-44 \
+    \n  maconomy export --weeks 40-43 --format html > report.html \
     \n  maconomy set 8 --job '<<job name>>' --task '<<task name>>' \
+    \n  maconomy set 4:30 --job '<<job name>>' --task '<<task name>>' \
+    \n  maconomy set -0:30 --job '<<job name>>' --task '<<task name>>' \
+    \n  maconomy add 1:30 --job '<<job name>>' --task '<<task name>>' \
     \n  maconomy set 8 --job '<<job name>>' --task '<<task name>>' --day 'mon-wed, fri' --week 46 \
     \n  maconomy set 8 --job '<<job name>>' --task '<<task name>>' --day mo --previous-week 2 \
+    \n  maconomy set 8 --job '<<job name>>' --task '<<task name>>' --date yesterday \
+    \n  maconomy set 8 --job '<<job name>>' --task '<<task name>>' --date 2026-10-12..2026-10-16 \
     \n  maconomy clear --job '<<job name>>' --task '<<task name>>' --day tuesday \
+    \n  maconomy set 0 --job '<<job name>>' --task '<<task name>>' --day mon-fri --dry-run \
+    \n  maconomy start --job '<<job name>>' --task '<<task name>>' \
+    \n  maconomy stop \
     \n  maconomy line delete 2 \
+    \n  maconomy line comment 2 '<<comment>>' \
+    \n  maconomy check --previous-week \
+    \n  maconomy history \
+    \n  maconomy undo --steps 2 \
+    \n  maconomy edit --previous-week \
+    \n  maconomy copy --from-previous-week --with-hours \
+    \n  maconomy apply '<<template name>>' --dry-run \
+    \n  maconomy import entries.csv --dry-run \
+    \n  maconomy import-ics calendar.ics --previous-week \
+    \n  timew export :week | maconomy import - --from timewarrior --dry-run \
+    \n  maconomy jobs '<<search query>>' \
+    \n  maconomy tasks --job '<<job name>>' \
     ")
 )]
 pub struct Args {
@@ -151,6 +601,58 @@ pub struct Args {
     #[arg(short, long, value_name = "configuration")]
     pub config: Option<String>,
 
+    /// Show what `set`, `add`, `clear`, `copy`, `apply`, `import`, `import-ics`, `line comment`,
+    /// `line delete` or `submit` would change, without saving anything
+    ///
+    /// Jobs, tasks and lines are still looked up in Maconomy
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
     #[command(subcommand)]
     pub command: Command,
 }
+
+impl Command {
+    /// Whether the command can be run with `--dry-run`, which commands that don't change anything
+    /// can trivially
+    pub(crate) fn supports_dry_run(&self) -> bool {
+        match self {
+            Command::Start { .. }
+            | Command::Stop { .. }
+            | Command::Edit { .. }
+            | Command::Logout
+            | Command::Undo { .. } => false,
+            Command::Get { .. }
+            | Command::Overview { .. }
+            | Command::Export { .. }
+            | Command::Set { .. }
+            | Command::Add { .. }
+            | Command::Copy { .. }
+            | Command::Apply { .. }
+            | Command::Import { .. }
+            | Command::ImportIcs { .. }
+            | Command::Clear { .. }
+            | Command::Status
+            | Command::Jobs { .. }
+            | Command::Tasks { .. }
+            | Command::Check { .. }
+            | Command::Submit { .. }
+            | Command::History { .. }
+            | Command::Schema
+            | Command::Completions { .. }
+            | Command::Line(Line::Comment { .. })
+            | Command::Line(Line::Delete { .. }) => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn has_valid_arguments() {
+        Args::command().debug_assert();
+    }
+}
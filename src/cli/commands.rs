@@ -1,18 +1,44 @@
-use super::arguments::Format;
+use super::arguments::{ExportFormat, Format, ImportSource, TimeSheetFormat};
+use super::day_parser::parse_days_of_week;
+use super::editor::Editor;
+use super::export;
+use super::formats;
+use super::json::TimeSheetJson;
+use super::period_parser::WeekRange;
+use super::rendering::{diff_table, jobs_table, overview_tables, tasks_table};
+use crate::domain::models::check::Checks;
 use crate::domain::models::day::Days;
+use crate::domain::models::hours::{Hours, HoursChange};
+use crate::domain::models::import::{group_by_week, ImportError, ImportedEntry};
+use crate::domain::models::import_rule::{to_entries, Activities, ImportSettings};
+use crate::domain::models::job::JobTask;
+use crate::domain::models::journal::{by_command, Change, LineKey};
 use crate::domain::models::line_number::LineNumber;
+use crate::domain::models::overview::Overview;
+use crate::domain::models::period::Period;
+use crate::domain::models::report::Report;
+use crate::domain::models::template::Template;
+use crate::domain::models::time_sheet::{Status, TimeSheet};
+use crate::domain::models::timer::Timer;
 use crate::domain::models::week::WeekNumber;
 use crate::{
-    domain::time_sheet_service::{SetTimeError, TimeSheetService},
+    domain::time_sheet_service::{Preview, SetTimeError, TimeSheetService},
     infrastructure::{
-        auth_service::AuthService, repositories::time_sheet_repository::TimeSheetRepository,
+        auth_service::AuthService,
+        completion_cache::CompletionCache,
+        importers,
+        journal_store::JournalStore,
+        repositories::time_sheet_repository::{resolve_line_number, TimeSheetRepository},
+        timer_store::TimerStore,
     },
     utils::errors::error_stack_fmt,
 };
 use anyhow::Context;
-use chrono::Datelike;
-use log::info;
+use chrono::{Datelike, Utc};
+use log::{info, warn};
 use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::path::Path;
 use std::rc::Rc;
 use tokio::sync::Mutex;
 
@@ -28,6 +54,9 @@ pub struct CommandClient<'a> {
     pub repository: Rc<Mutex<TimeSheetRepository<'a>>>,
     pub time_sheet_service: Rc<Mutex<TimeSheetService<'a>>>,
     pub auth_service: &'a AuthService,
+    completion_cache_path: String,
+    /// Whether to show what commands would change instead of saving anything
+    dry_run: bool,
 }
 
 impl<'a> CommandClient<'a> {
@@ -35,59 +64,312 @@ impl<'a> CommandClient<'a> {
         repository: Rc<Mutex<TimeSheetRepository<'a>>>,
         time_sheet_service: Rc<Mutex<TimeSheetService<'a>>>,
         auth_service: &'a AuthService,
+        completion_cache_path: String,
     ) -> CommandClient<'a> {
         CommandClient {
             repository,
             time_sheet_service,
             auth_service,
+            completion_cache_path,
+            dry_run: false,
         }
     }
 
+    pub(crate) fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
     pub(crate) async fn get_table(&self, week: &WeekNumber) -> anyhow::Result<()> {
-        let time_sheet = self.repository.lock().await.get_time_sheet(week).await?;
+        let time_sheet = self.get_time_sheet(week).await?;
 
         println!("{time_sheet}");
         Ok(())
     }
 
     async fn get_json(&self, week: &WeekNumber) -> anyhow::Result<()> {
-        let time_sheet = self.repository.lock().await.get_time_sheet(week).await?;
-        let json =
-            serde_json::to_string(&time_sheet).context("Failed to deserialize time sheet")?;
+        let time_sheet = self.get_time_sheet(week).await?;
+        let json = serde_json::to_string(&TimeSheetJson::new(&time_sheet, week))
+            .context("Failed to serialize time sheet")?;
 
         println!("{json}");
         Ok(())
     }
 
-    pub(crate) async fn get(&self, week: super::arguments::Week, format: Format) {
+    async fn get_formatted(
+        &self,
+        week: &WeekNumber,
+        format: fn(&TimeSheet, &WeekNumber) -> String,
+    ) -> anyhow::Result<()> {
+        let time_sheet = self.get_time_sheet(week).await?;
+
+        print!("{}", format(&time_sheet, week));
+        Ok(())
+    }
+
+    async fn get_time_sheet(&self, week: &WeekNumber) -> anyhow::Result<TimeSheet> {
+        let time_sheet = self.repository.lock().await.get_time_sheet(week).await?;
+
+        if *week == WeekNumber::default() {
+            self.update_completion_cache(|cache| cache.set_lines(&time_sheet.lines));
+        }
+
+        Ok(time_sheet)
+    }
+
+    pub(crate) async fn get(&self, week: super::arguments::Week, format: TimeSheetFormat) {
         let week = get_week_number(&week.number, &week.previous, &week.year);
 
         match format {
-            Format::Json => self.get_json(&week).await.context("JSON"),
-            Format::Table => self.get_table(&week).await.context("table"),
+            TimeSheetFormat::Json => self.get_json(&week).await.context("JSON"),
+            TimeSheetFormat::Table => self.get_table(&week).await.context("table"),
+            TimeSheetFormat::Csv => self.get_formatted(&week, formats::csv).await.context("CSV"),
+            TimeSheetFormat::Tsv => self.get_formatted(&week, formats::tsv).await.context("TSV"),
+            TimeSheetFormat::Markdown => self
+                .get_formatted(&week, formats::markdown)
+                .await
+                .context("Markdown"),
+            TimeSheetFormat::Yaml => self
+                .get_formatted(&week, formats::yaml)
+                .await
+                .context("YAML"),
         }
         .unwrap_or_else(|err| {
             exit_with_error!("Failed to get time sheet as {}", error_stack_fmt(&err));
         })
     }
 
+    /// Prints the time sheet of `week` after it has been changed, unless `format` is `None`
+    async fn print_result(&self, week: &WeekNumber, format: Option<Format>) {
+        let result = match format {
+            None => return,
+            Some(Format::Json) => self.get_json(week).await.context("JSON"),
+            Some(Format::Table) => self.get_table(week).await.context("table"),
+        };
+
+        result.unwrap_or_else(|err| {
+            exit_with_error!(
+                "Failed to get the resulting time sheet as {}",
+                error_stack_fmt(&err)
+            );
+        });
+    }
+
+    pub(crate) async fn overview(
+        &self,
+        period: &super::arguments::Period,
+        year: Option<i32>,
+        format: Format,
+    ) {
+        let (period, time_sheets) = self.get_time_sheets_of_period(period, year).await;
+        let overview = Overview::new(&period, &time_sheets);
+
+        match format {
+            Format::Json => print_json(&overview),
+            Format::Table => println!("{}", overview_tables(&overview)),
+        }
+    }
+
+    pub(crate) async fn export(
+        &self,
+        period: &super::arguments::Period,
+        year: Option<i32>,
+        format: ExportFormat,
+    ) {
+        let (period, time_sheets) = self.get_time_sheets_of_period(period, year).await;
+        let report = Report::new(&period, &time_sheets);
+
+        match format {
+            ExportFormat::Ics => print!("{}", export::ics(&report, Utc::now())),
+            ExportFormat::Html => print!("{}", export::html(&report)),
+        }
+    }
+
+    async fn get_time_sheets_of_period(
+        &self,
+        period: &super::arguments::Period,
+        year: Option<i32>,
+    ) -> (Period, Vec<(WeekNumber, TimeSheet)>) {
+        // NOTE: `month` and `weeks` are assumed to be mutually exclusive (handled by Clap)
+        let period = match (&period.month, period.weeks) {
+            (Some(month), _) => Ok(month.clone()),
+            (None, Some(weeks)) => get_period_of_weeks(weeks, year),
+            (None, None) => unreachable!("Either `--month` or `--weeks` is required"),
+        }
+        .unwrap_or_else(|err| exit_with_error!("{err}"));
+
+        let mut time_sheets = Vec::new();
+        for week in period.week_numbers() {
+            let time_sheet = self.get_time_sheet(&week).await.unwrap_or_else(|err| {
+                exit_with_error!("Failed to get time sheet: {}", error_stack_fmt(&err));
+            });
+            time_sheets.push((week, time_sheet));
+        }
+
+        (period, time_sheets)
+    }
+
     pub(crate) async fn set(
         &mut self,
-        hours: f32,
+        change: HoursChange,
         days: &super::arguments::Days,
         task: &super::arguments::Task,
+        comment: Option<String>,
+        force: bool,
+        format: Option<Format>,
     ) {
-        if days.days.as_ref().is_some_and(|days| days.is_empty()) {
-            exit_with_error!("`--day` is set but no day was provided");
+        let weeks = get_days_per_week(days);
+        for (week, _) in &weeks {
+            self.ensure_editable(week, force).await;
         }
 
-        let day = get_days(days.days.clone());
-        let week = get_week_number(&days.week.number, &days.week.previous, &days.week.year);
+        if self.dry_run {
+            let mut previews = Vec::new();
+            for (week, days) in &weeks {
+                let mut preview = self
+                    .time_sheet_service
+                    .lock()
+                    .await
+                    .preview_set_time(change, days, week, &task.job, &task.name)
+                    .await
+                    .unwrap_or_else(|err| {
+                        if let SetTimeError::Unknown(err) = err {
+                            exit_with_error!("{}", error_stack_fmt(&err));
+                        } else {
+                            exit_with_error!("{err}");
+                        }
+                    });
+                if let Some(comment) = &comment {
+                    if let Ok(Some(line_number)) = preview.after.find_line_nr(&task.job, &task.name)
+                    {
+                        preview.after.lines[line_number as usize].comment = comment.clone();
+                    }
+                }
+                previews.push((week, preview));
+            }
+            print_previews(&previews, format);
+            return;
+        }
 
-        self.time_sheet_service
+        // Dates may span several weeks, which are changed one at a time
+        let mut changed = Vec::new();
+        for (week, days) in &weeks {
+            self.time_sheet_service
+                .lock()
+                .await
+                .set_time(change, days, week, &task.job, &task.name)
+                .await
+                .unwrap_or_else(|err| {
+                    report_changed_weeks(&changed);
+                    if let SetTimeError::Unknown(err) = err {
+                        exit_with_error!("{}", error_stack_fmt(&err));
+                    } else {
+                        exit_with_error!("{err}");
+                    }
+                });
+            changed.push(week);
+
+            // The line exists now, since setting the hours creates it if needed
+            if let Some(comment) = &comment {
+                self.repository
+                    .lock()
+                    .await
+                    .set_comment(week, &task.job, &task.name, comment)
+                    .await
+                    .unwrap_or_else(|err| {
+                        report_changed_weeks(&changed);
+                        exit_with_error!("Failed to set comment: {err}");
+                    });
+            }
+        }
+
+        // One line of JSON per week, if the dates span several weeks
+        for (week, _) in &weeks {
+            self.print_result(week, format.clone()).await;
+        }
+    }
+
+    pub(crate) async fn clear(
+        &mut self,
+        task: &super::arguments::Task,
+        days: &super::arguments::Days,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        if self.dry_run {
+            let change = HoursChange::Set(Hours::ZERO);
+            return self.set(change, days, task, None, force, format).await;
+        }
+
+        let weeks = get_days_per_week(days);
+        for (week, _) in &weeks {
+            self.ensure_editable(week, force).await;
+        }
+
+        let mut changed = Vec::new();
+        for (week, days) in &weeks {
+            self.time_sheet_service
+                .lock()
+                .await
+                .clear(&task.job, &task.name, days, week)
+                .await
+                .unwrap_or_else(|err| {
+                    report_changed_weeks(&changed);
+                    if let SetTimeError::Unknown(err) = err {
+                        exit_with_error!("{}", error_stack_fmt(&err));
+                    } else {
+                        exit_with_error!("{err}");
+                    }
+                });
+            changed.push(week);
+        }
+
+        for (week, _) in &weeks {
+            self.print_result(week, format.clone()).await;
+        }
+    }
+
+    pub(crate) async fn copy(
+        &mut self,
+        source: &super::arguments::SourceWeek,
+        with_hours: bool,
+        week: super::arguments::Week,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        let target = get_week_number(&week.number, &week.previous, &week.year);
+        // NOTE: `from_week` and `from_previous` are assumed to be mutually exclusive (handled by
+        // Clap)
+        let source = match source.from_week {
+            Some(number) => WeekNumber::new(number, target.year),
+            None => target.weeks_before(source.from_previous.unwrap_or(1)),
+        }
+        .unwrap_or_else(|err| exit_with_error!("{err}"));
+        self.ensure_editable(&target, force).await;
+
+        if self.dry_run {
+            let preview = self
+                .time_sheet_service
+                .lock()
+                .await
+                .preview_copy_lines(&source, &target, with_hours)
+                .await
+                .unwrap_or_else(|err| {
+                    if let SetTimeError::Unknown(err) = err {
+                        exit_with_error!("{}", error_stack_fmt(&err));
+                    } else {
+                        exit_with_error!("{err}");
+                    }
+                });
+            print_previews(&[(&target, preview)], format);
+            return;
+        }
+
+        let result = self
+            .time_sheet_service
             .lock()
             .await
-            .set_time(hours, &day, &week, &task.job, &task.name)
+            .copy_lines(&source, &target, with_hours)
             .await
             .unwrap_or_else(|err| {
                 if let SetTimeError::Unknown(err) = err {
@@ -96,22 +378,207 @@ impl<'a> CommandClient<'a> {
                     exit_with_error!("{err}");
                 }
             });
+
+        let report = |message: String| report(&message, &format);
+        for line in &result.copied {
+            report(format!("Copied '{}', '{}'", line.job, line.task));
+        }
+        for line in &result.merged {
+            let hours = if with_hours { ", with its hours," } else { "" };
+            report(format!(
+                "Copied another line for '{}', '{}' in week {}{hours} as part of the same line",
+                line.job, line.task, source.number
+            ));
+        }
+        for line in &result.skipped {
+            report(format!(
+                "Skipped '{}', '{}' (already in week {})",
+                line.job, line.task, target.number
+            ));
+        }
+        if result.copied.is_empty() && result.skipped.is_empty() {
+            report(format!("Week {} has no lines to copy", source.number));
+        }
+
+        self.print_result(&target, format).await;
     }
 
-    pub(crate) async fn clear(
+    pub(crate) async fn apply(
         &mut self,
-        task: &super::arguments::Task,
-        days: &super::arguments::Days,
+        name: &str,
+        template: &Template,
+        week: super::arguments::Week,
+        force: bool,
+        format: Option<Format>,
     ) {
-        if days.days.as_ref().is_some_and(|days| days.is_empty()) {
-            exit_with_error!("`--day` is set but no day was provided");
+        let week = get_week_number(&week.number, &week.previous, &week.year);
+
+        // Parse all entries up front so that a broken template doesn't get half applied
+        let entries: Vec<_> = template
+            .entries
+            .iter()
+            .map(|entry| {
+                let days = parse_days_of_week(&entry.days)?;
+                let hours: Hours = entry.hours.parse()?;
+                anyhow::Ok((entry, days, HoursChange::Set(hours)))
+            })
+            .collect::<anyhow::Result<_>>()
+            .unwrap_or_else(|err| {
+                exit_with_error!("Invalid entry in template '{name}': {err}");
+            });
+        self.ensure_editable(&week, force).await;
+
+        if self.dry_run {
+            let mut time_sheet = self.get_time_sheet(&week).await.unwrap_or_else(|err| {
+                exit_with_error!("Failed to get time sheet: {}", error_stack_fmt(&err));
+            });
+            for (entry, days, change) in &entries {
+                time_sheet
+                    .apply(*change, days, &entry.job, &entry.task)
+                    .unwrap_or_else(|err| exit_with_error!("{err}"));
+            }
+
+            eprintln!("Dry run: nothing was saved. The time sheet would look like this:");
+            match format.unwrap_or(Format::Table) {
+                Format::Json => print_json(&TimeSheetJson::new(&time_sheet, &week)),
+                Format::Table => println!("{time_sheet}"),
+            }
+            return;
+        }
+
+        for (entry, days, change) in entries {
+            self.time_sheet_service
+                .lock()
+                .await
+                .set_time(change, &days, &week, &entry.job, &entry.task)
+                .await
+                .unwrap_or_else(|err| {
+                    if let SetTimeError::Unknown(err) = err {
+                        exit_with_error!("{}", error_stack_fmt(&err));
+                    } else {
+                        exit_with_error!("{err}");
+                    }
+                });
+        }
+
+        self.print_result(&week, format).await;
+    }
+
+    pub(crate) async fn import(
+        &mut self,
+        path: &Path,
+        source: ImportSource,
+        settings: Option<&ImportSettings>,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        let export = read_file(path);
+        let entries = match (source, settings) {
+            (ImportSource::Csv, _) => importers::csv::read_entries(export.as_bytes())
+                .unwrap_or_else(|errors| exit_with_import_errors(&errors)),
+            (source, Some(settings)) => {
+                let activities = match source {
+                    ImportSource::Timewarrior => importers::timewarrior::read_activities(&export),
+                    ImportSource::Toggl => {
+                        importers::toggl::read_activities(&export, settings.date_order)
+                    }
+                    ImportSource::Clockify => {
+                        importers::clockify::read_activities(&export, settings.date_order)
+                    }
+                    ImportSource::Csv => unreachable!("CSV files are read above"),
+                }
+                .unwrap_or_else(|errors| exit_with_import_errors(&errors));
+                map_activities(activities, settings)
+            }
+            (_, None) => exit_with_error!("Rules for importing are missing from the configuration"),
+        };
+        if entries.is_empty() {
+            eprintln!("Nothing to import");
+            return;
+        }
+
+        self.import_entries(&entries, self.dry_run, force, false, format)
+            .await;
+    }
+
+    pub(crate) async fn import_ics(
+        &mut self,
+        path: &Path,
+        settings: &ImportSettings,
+        week: super::arguments::Week,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        let week = get_week_number(&week.number, &week.previous, &week.year);
+        let calendar = read_file(path);
+
+        let activities = importers::ics::read_activities(&calendar, &week)
+            .unwrap_or_else(|errors| exit_with_import_errors(&errors));
+        let entries = map_activities(activities, settings);
+        if entries.is_empty() {
+            eprintln!("No events in {week} to import");
+            return;
+        }
+
+        self.import_entries(&entries, self.dry_run, force, true, format)
+            .await;
+    }
+
+    /// Imports entries into the weeks they belong to. Nothing is saved if any entry can't be
+    /// imported. The changes to each week are shown with `dry_run`, without saving them, and with
+    /// `preview`, before saving them
+    async fn import_entries(
+        &mut self,
+        entries: &[ImportedEntry],
+        dry_run: bool,
+        force: bool,
+        preview: bool,
+        format: Option<Format>,
+    ) {
+        let weeks =
+            group_by_week(entries).unwrap_or_else(|errors| exit_with_import_errors(&errors));
+
+        let plan = self
+            .time_sheet_service
+            .lock()
+            .await
+            .plan_import(&weeks, force)
+            .await
+            .unwrap_or_else(|err| {
+                if let SetTimeError::Unknown(err) = err {
+                    exit_with_error!("{}", error_stack_fmt(&err));
+                } else {
+                    exit_with_error!("{err}");
+                }
+            });
+
+        if dry_run || preview {
+            if dry_run {
+                eprintln!("Dry run: nothing was saved. The time sheets would look like this:");
+            } else {
+                eprintln!("Saving these changes:");
+            }
+            for week in &plan.weeks {
+                match format {
+                    Some(Format::Json) if dry_run => {
+                        print_json(&TimeSheetJson::new(&week.after, &week.week))
+                    }
+                    _ if dry_run => println!("{}", diff_table(&week.before, &week.after)),
+                    _ => report(&diff_table(&week.before, &week.after), &format),
+                }
+            }
+        }
+        if !plan.errors.is_empty() {
+            exit_with_import_errors(&plan.errors);
+        }
+        if dry_run {
+            return;
         }
 
-        let week = get_week_number(&days.week.number, &days.week.previous, &days.week.year);
         self.time_sheet_service
             .lock()
             .await
-            .clear(&task.job, &task.name, &get_days(days.days.clone()), &week)
+            .import(&plan)
             .await
             .unwrap_or_else(|err| {
                 if let SetTimeError::Unknown(err) = err {
@@ -120,6 +587,73 @@ impl<'a> CommandClient<'a> {
                     exit_with_error!("{err}");
                 }
             });
+
+        for week in &plan.weeks {
+            let message = format!("Imported {} line(s) into {}", week.lines.len(), week.week);
+            report(&message, &format);
+        }
+        // One line of JSON per week, if the entries span several weeks
+        for week in &plan.weeks {
+            self.print_result(&week.week, format.clone()).await;
+        }
+    }
+
+    pub(crate) async fn jobs(&self, query: Option<String>, format: Format) {
+        let jobs = self
+            .repository
+            .lock()
+            .await
+            .search_jobs(query.as_deref())
+            .await
+            .unwrap_or_else(|err| {
+                exit_with_error!("Failed to get jobs: {}", error_stack_fmt(&err));
+            });
+        self.update_completion_cache(|cache| cache.add_jobs(&jobs));
+
+        match format {
+            Format::Json => print_json(&jobs),
+            Format::Table => println!("{}", jobs_table(&jobs)),
+        }
+    }
+
+    pub(crate) async fn tasks(&self, job: &str, format: Format) {
+        let (found_job, tasks) = self
+            .repository
+            .lock()
+            .await
+            .get_tasks_for_job(job)
+            .await
+            .unwrap_or_else(|err| {
+                exit_with_error!("Failed to get tasks: {}", error_stack_fmt(&err));
+            });
+        self.update_completion_cache(|cache| cache.set_tasks(&found_job, &tasks));
+
+        let tasks: Vec<_> = tasks
+            .into_iter()
+            .map(|task| JobTask {
+                job: found_job.clone(),
+                task,
+            })
+            .collect();
+
+        match format {
+            Format::Json => print_json(&tasks),
+            Format::Table => println!("{}", tasks_table(&tasks)),
+        }
+    }
+
+    pub(crate) async fn edit(&self, week: super::arguments::Week, force: bool) {
+        let week = get_week_number(&week.number, &week.previous, &week.year);
+        self.ensure_editable(&week, force).await;
+        let editor = Editor::new(
+            self.repository.clone(),
+            self.time_sheet_service.clone(),
+            week,
+        );
+
+        editor.run().await.unwrap_or_else(|err| {
+            exit_with_error!("Failed to edit time sheet: {}", error_stack_fmt(&err));
+        });
     }
 
     pub(crate) async fn logout(&self) {
@@ -128,8 +662,40 @@ impl<'a> CommandClient<'a> {
         });
     }
 
-    pub(crate) async fn delete(&mut self, line_number: &LineNumber, week: super::arguments::Week) {
+    pub(crate) async fn delete(
+        &mut self,
+        line_number: &LineNumber,
+        week: super::arguments::Week,
+        force: bool,
+        yes: bool,
+        format: Option<Format>,
+    ) {
         let week = get_week_number(&week.number, &week.previous, &week.year);
+        self.ensure_editable(&week, force).await;
+
+        if self.dry_run {
+            let preview = self.preview_delete(line_number, &week).await;
+            print_previews(&[(&week, preview)], format);
+            return;
+        }
+
+        if !yes && is_interactive() {
+            let Preview { before, after } = self.preview_delete(line_number, &week).await;
+            let row = resolve_line_number(line_number, &before).unwrap_or_else(|err| {
+                exit_with_error!("Failed to delete line {line_number:?}: {err}");
+            });
+            let line = &before.lines[row as usize];
+            eprintln!("{}", diff_table(&before, &after));
+            let question = format!(
+                "Delete line {} {} with {}?",
+                row + 1,
+                LineKey::from(line),
+                line.week.total()
+            );
+            if !confirm(&question) {
+                exit_with_error!("Nothing was deleted");
+            }
+        }
 
         self.repository
             .lock()
@@ -140,10 +706,128 @@ impl<'a> CommandClient<'a> {
                 let source = error_stack_fmt(&err);
                 exit_with_error!("Failed to delete line {line_number:?}: {source}");
             });
+
+        self.print_result(&week, format).await;
+    }
+
+    async fn preview_delete(&self, line_number: &LineNumber, week: &WeekNumber) -> Preview {
+        self.time_sheet_service
+            .lock()
+            .await
+            .preview_delete_line(line_number, week)
+            .await
+            .unwrap_or_else(|err| {
+                let source = error_stack_fmt(&err);
+                exit_with_error!("Failed to delete line {line_number:?}: {source}");
+            })
+    }
+
+    pub(crate) async fn comment(
+        &mut self,
+        line_number: &LineNumber,
+        comment: &str,
+        week: super::arguments::Week,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        let week = get_week_number(&week.number, &week.previous, &week.year);
+        self.ensure_editable(&week, force).await;
+
+        if self.dry_run {
+            let preview = self
+                .time_sheet_service
+                .lock()
+                .await
+                .preview_set_comment(line_number, comment, &week)
+                .await
+                .unwrap_or_else(|err| {
+                    let source = error_stack_fmt(&err);
+                    exit_with_error!("Failed to set comment on line {line_number:?}: {source}");
+                });
+            print_previews(&[(&week, preview)], format);
+            return;
+        }
+
+        self.repository
+            .lock()
+            .await
+            .set_line_comment(&week, line_number, comment)
+            .await
+            .unwrap_or_else(|err| {
+                let source = error_stack_fmt(&err);
+                exit_with_error!("Failed to set comment on line {line_number:?}: {source}");
+            });
+
+        self.print_result(&week, format).await;
+    }
+
+    pub(crate) async fn check(&self, week: super::arguments::Week, checks: &Checks) {
+        let week = get_week_number(&week.number, &week.previous, &week.year);
+        if checks.is_empty() {
+            exit_with_error!(
+                "No checks are configured. Add them to `[checks]` in the configuration"
+            );
+        }
+        let time_sheet = self.get_time_sheet(&week).await.unwrap_or_else(|err| {
+            exit_with_error!("Failed to get time sheet: {}", error_stack_fmt(&err));
+        });
+
+        let violations = checks.evaluate(&time_sheet);
+        if violations.is_empty() {
+            println!("{week} passes every check");
+            return;
+        }
+        println!("{week} fails {} check(s):", violations.len());
+        for violation in &violations {
+            println!("  - {violation}");
+        }
+        std::process::exit(1);
     }
 
-    pub(crate) async fn submit(&mut self, week: super::arguments::Week) {
+    pub(crate) async fn submit(
+        &mut self,
+        week: super::arguments::Week,
+        yes: bool,
+        force: bool,
+        checks: &Checks,
+        format: Option<Format>,
+    ) {
         let week = get_week_number(&week.number, &week.previous, &week.year);
+        let time_sheet = self.get_time_sheet(&week).await.unwrap_or_else(|err| {
+            exit_with_error!("Failed to get time sheet: {}", error_stack_fmt(&err));
+        });
+
+        let violations = checks.evaluate(&time_sheet);
+        if !violations.is_empty() && !force {
+            for violation in &violations {
+                eprintln!("  - {violation}");
+            }
+            exit_with_error!(
+                "{week} fails {} check(s), so it wasn't submitted. Use `--force` to submit it anyway",
+                violations.len()
+            );
+        }
+        let violations = violations.iter().map(|violation| violation.to_string());
+        for warning in violations.chain(submit_warnings(&time_sheet)) {
+            eprintln!("Warning: {warning}");
+        }
+
+        if self.dry_run {
+            let after = time_sheet.clone().with_status(Status::Submitted);
+            let preview = Preview {
+                before: time_sheet,
+                after,
+            };
+            print_previews(&[(&week, preview)], format);
+            return;
+        }
+
+        if !yes && is_interactive() {
+            eprintln!("{time_sheet}");
+            if !confirm(&format!("Submit week {} of {}?", week.number, week.year)) {
+                exit_with_error!("Nothing was submitted");
+            }
+        }
 
         self.repository
             .lock()
@@ -153,7 +837,401 @@ impl<'a> CommandClient<'a> {
             .unwrap_or_else(|err| {
                 exit_with_error!("Failed to submit: {}", error_stack_fmt(&err));
             });
+
+        self.print_result(&week, format).await;
+    }
+
+    /// Undoes the changes of the latest `steps` commands in the journal, from the newest. Each
+    /// change is removed from the journal once it has been undone
+    pub(crate) async fn undo(
+        &mut self,
+        journal: &JournalStore,
+        steps: usize,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        let entries = journal.load().unwrap_or_else(|err| {
+            exit_with_error!("{}", error_stack_fmt(&err));
+        });
+        let commands = by_command(&entries);
+        if commands.is_empty() {
+            exit_with_error!("There are no changes to undo");
+        }
+
+        // Undoing shouldn't be recorded as changes of its own
+        self.repository.lock().await.stop_journaling();
+
+        let mut remaining = entries.len();
+        let mut changed: Vec<&WeekNumber> = Vec::new();
+        for command in commands.iter().rev().take(steps) {
+            for entry in command.iter().rev() {
+                if entry.change == Change::Submit {
+                    if !force {
+                        exit_with_error!(
+                            "{} was submitted, which can't be undone. Use `--force` to undo the \
+                            changes before it anyway",
+                            entry.week
+                        );
+                    }
+                    eprintln!("{} stays submitted, since that can't be undone", entry.week);
+                } else {
+                    self.ensure_editable(&entry.week, force).await;
+                    self.time_sheet_service
+                        .lock()
+                        .await
+                        .undo(entry, force)
+                        .await
+                        .unwrap_or_else(|err| {
+                            let source = error_stack_fmt(&err);
+                            exit_with_error!("Failed to undo '{}': {source}", entry.change);
+                        });
+                    report(&format!("Undid {}: {}", entry.week, entry.change), &format);
+                    if !changed.contains(&&entry.week) {
+                        changed.push(&entry.week);
+                    }
+                }
+
+                remaining -= 1;
+                journal.truncate(remaining).unwrap_or_else(|err| {
+                    exit_with_error!("{}", error_stack_fmt(&err));
+                });
+            }
+        }
+
+        for week in changed {
+            self.print_result(week, format.clone()).await;
+        }
+    }
+
+    pub(crate) async fn start(
+        &mut self,
+        task: &super::arguments::Task,
+        store: &TimerStore,
+        granularity: Hours,
+        force: bool,
+        format: Option<Format>,
+    ) {
+        let mut booked = Vec::new();
+        if let Some(previous) = load_timer(store) {
+            booked = self
+                .book_timer(&previous, store, granularity, force, &format)
+                .await;
+            clear_timer(store);
+        }
+
+        let timer = Timer::new(task.job.clone(), task.name.clone(), Utc::now());
+        store.save(&timer).unwrap_or_else(|err| {
+            exit_with_error!("{}", error_stack_fmt(&err));
+        });
+        let started = timer.started_local().format("%H:%M");
+        let message = format!(
+            "Started timer for '{}', '{}' at {started}",
+            timer.job, timer.task
+        );
+        report(&message, &format);
+
+        for week in &booked {
+            self.print_result(week, format.clone()).await;
+        }
+    }
+
+    pub(crate) async fn stop(
+        &mut self,
+        store: &TimerStore,
+        granularity: Hours,
+        force: bool,
+        discard: bool,
+        format: Option<Format>,
+    ) {
+        let Some(timer) = load_timer(store) else {
+            exit_with_error!("No timer is running");
+        };
+
+        if discard {
+            clear_timer(store);
+            let message = format!(
+                "Discarded timer for '{}', '{}' after {}, without booking anything",
+                timer.job,
+                timer.task,
+                timer.elapsed(Utc::now())
+            );
+            report(&message, &format);
+            return;
+        }
+
+        // The timer is only removed once its time is booked, so that no time is lost if booking
+        // fails
+        let booked = self
+            .book_timer(&timer, store, granularity, force, &format)
+            .await;
+        clear_timer(store);
+
+        for week in &booked {
+            self.print_result(week, format.clone()).await;
+        }
     }
+
+    pub(crate) fn status(&self, store: &TimerStore, granularity: Hours) {
+        let Some(timer) = load_timer(store) else {
+            println!("No timer is running");
+            return;
+        };
+
+        let now = Utc::now();
+        let elapsed = timer.elapsed(now);
+        let started = timer.started_local().format("%a %H:%M");
+        let hours: Hours = timer
+            .elapsed_per_day(now, &chrono::Local)
+            .iter()
+            .map(|part| part.elapsed.round_to(granularity))
+            .sum();
+        println!(
+            "Timer for '{}', '{}' running since {started}: {elapsed} (books {hours})",
+            timer.job, timer.task,
+        );
+    }
+
+    /// Adds the time of a timer to the hours of its line on the days that it ran, and returns the
+    /// weeks that were changed. The time of each day is rounded to `granularity`
+    async fn book_timer(
+        &mut self,
+        timer: &Timer,
+        store: &TimerStore,
+        granularity: Hours,
+        force: bool,
+        format: &Option<Format>,
+    ) -> Vec<WeekNumber> {
+        let now = Utc::now();
+        let elapsed = timer.elapsed(now);
+        let line = format!("'{}', '{}'", timer.job, timer.task);
+        let parts: Vec<_> = timer
+            .elapsed_per_day(now, &chrono::Local)
+            .into_iter()
+            .map(|part| (part.elapsed.round_to(granularity), part))
+            .filter(|(hours, _)| !hours.is_zero())
+            .collect();
+        if parts.is_empty() {
+            let message = format!(
+                "Stopped timer for {line} after {elapsed}, which rounds to nothing to book"
+            );
+            report(&message, format);
+            return Vec::new();
+        }
+
+        let mut weeks: Vec<_> = parts
+            .iter()
+            .map(|(_, part)| WeekNumber::of(part.date))
+            .collect();
+        weeks.dedup();
+        for week in &weeks {
+            self.ensure_editable(week, force).await;
+        }
+
+        let today = chrono::Local::now().date_naive();
+        let mut booked = Vec::new();
+        for (i, (hours, part)) in parts.iter().enumerate() {
+            let days = HashSet::from([part.date.weekday().into()]);
+            self.time_sheet_service
+                .lock()
+                .await
+                .set_time(
+                    HoursChange::Add(*hours),
+                    &days,
+                    &WeekNumber::of(part.date),
+                    &timer.job,
+                    &timer.task,
+                )
+                .await
+                .unwrap_or_else(|err| {
+                    let err = if let SetTimeError::Unknown(err) = err {
+                        error_stack_fmt(&err).to_string()
+                    } else {
+                        err.to_string()
+                    };
+                    exit_with_error!(
+                        "Failed to book the timer, which is still running: {err}. Use `maconomy \
+                         stop --discard` to stop it without booking the rest of its time"
+                    );
+                });
+
+            let day = match part.date == today {
+                true => "today".to_string(),
+                false => part.date.format("%a %Y-%m-%d").to_string(),
+            };
+            booked.push(format!("{hours} to {day}"));
+
+            // Only the time that isn't booked yet is left on the timer, so that booking it again
+            // after a later day fails doesn't book this day twice
+            if i + 1 < parts.len() {
+                let rest = Timer::new(timer.job.clone(), timer.task.clone(), part.end);
+                store.save(&rest).unwrap_or_else(|err| {
+                    exit_with_error!("{}", error_stack_fmt(&err));
+                });
+            }
+        }
+
+        let message = format!(
+            "Stopped timer for {line} after {elapsed}, and added {}",
+            booked.join(", ")
+        );
+        report(&message, format);
+
+        weeks
+    }
+
+    /// Exits with an error if the week has been submitted or approved, unless `force` is set.
+    /// Maconomy's own error for changing such a week doesn't say what's wrong
+    async fn ensure_editable(&self, week: &WeekNumber, force: bool) {
+        if force {
+            return;
+        }
+
+        let time_sheet = self.get_time_sheet(week).await.unwrap_or_else(|err| {
+            exit_with_error!("Failed to get time sheet: {}", error_stack_fmt(&err));
+        });
+        if time_sheet.status.is_locked() {
+            let status = time_sheet.status.to_string().to_lowercase();
+            exit_with_error!("{week} has been {status}. Use `--force` to change it anyway");
+        }
+    }
+
+    /// Updates the names used for shell completions. Failing to do so isn't worth failing the
+    /// command over
+    fn update_completion_cache(&self, update: impl FnOnce(&mut CompletionCache)) {
+        let mut cache = CompletionCache::load(&self.completion_cache_path);
+        update(&mut cache);
+
+        if let Err(err) = cache.save(&self.completion_cache_path) {
+            warn!(
+                "Failed to update completion cache: {}",
+                error_stack_fmt(&err)
+            );
+        }
+    }
+}
+
+/// Lists the changes of the latest `limit` commands in the journal, from the newest
+pub(crate) fn history(journal: &JournalStore, limit: usize) {
+    let entries = journal.load().unwrap_or_else(|err| {
+        exit_with_error!("{}", error_stack_fmt(&err));
+    });
+    let commands = by_command(&entries);
+    if commands.is_empty() {
+        println!("No changes have been made with maconomy yet");
+        return;
+    }
+
+    for (i, command) in (1..).zip(commands.iter().rev().take(limit)) {
+        let started = command[0]
+            .command_started_local()
+            .format("%a %Y-%m-%d %H:%M");
+        println!("{i}. {started}");
+        for entry in *command {
+            println!("   {}: {}", entry.week, entry.change);
+        }
+    }
+}
+
+/// Whether there's someone to ask for confirmation, i.e. stdin is a terminal
+fn is_interactive() -> bool {
+    std::io::stdin().is_terminal()
+}
+
+/// Asks a yes/no question on the terminal, where no is the default
+fn confirm(question: &str) -> bool {
+    eprint!("{question} [y/N] ");
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Days that are worth a second look before submitting. Days with more hours than the maximum
+/// are found by the `max_hours_per_day` check
+fn submit_warnings(time_sheet: &TimeSheet) -> Vec<String> {
+    let totals = &time_sheet.totals;
+    totals
+        .underfilled_days()
+        .into_iter()
+        .map(|day| {
+            format!(
+                "{day} has {} of regular time, less than the expected {}",
+                totals.regular.get(day),
+                totals.norm.get(day)
+            )
+        })
+        .collect()
+}
+
+/// Prints what changes would do to weeks, for `--dry-run`. Tables show what changed, and JSON
+/// the resulting time sheets
+fn print_previews(previews: &[(&WeekNumber, Preview)], format: Option<Format>) {
+    eprintln!("Dry run: nothing was saved. The time sheet would look like this:");
+    for (week, preview) in previews {
+        match format.clone().unwrap_or(Format::Table) {
+            Format::Json => print_json(&TimeSheetJson::new(&preview.after, week)),
+            Format::Table => println!("{}", diff_table(&preview.before, &preview.after)),
+        }
+    }
+}
+
+/// Prints a message about what a command did, which goes to stderr when the resulting time sheet
+/// is printed, to keep the output parsable
+fn report(message: &str, format: &Option<Format>) {
+    match format {
+        Some(_) => eprintln!("{message}"),
+        None => println!("{message}"),
+    }
+}
+
+fn print_json(value: &impl serde::Serialize) {
+    let json = serde_json::to_string(value).unwrap_or_else(|err| {
+        exit_with_error!("Failed to serialize to JSON: {err}");
+    });
+
+    println!("{json}");
+}
+
+fn load_timer(store: &TimerStore) -> Option<Timer> {
+    store.load().unwrap_or_else(|err| {
+        exit_with_error!("{}", error_stack_fmt(&err));
+    })
+}
+
+fn clear_timer(store: &TimerStore) {
+    store.clear().unwrap_or_else(|err| {
+        exit_with_error!("{}", error_stack_fmt(&err));
+    });
+}
+
+/// Reads a file, or standard input if the path is "-"
+fn read_file(path: &Path) -> String {
+    let result = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(path)
+    };
+
+    result.unwrap_or_else(|err| exit_with_error!("Failed to read '{}': {err}", path.display()))
+}
+
+/// Maps activities to entries with the configured rules, and lists the activities that are
+/// skipped
+fn map_activities(activities: Activities, settings: &ImportSettings) -> Vec<ImportedEntry> {
+    let mapped =
+        to_entries(activities, settings).unwrap_or_else(|errors| exit_with_import_errors(&errors));
+    for skipped in &mapped.skipped {
+        eprintln!("Skipped {skipped}");
+    }
+
+    mapped.entries
+}
+
+fn exit_with_import_errors(errors: &[ImportError]) -> ! {
+    let errors: String = errors.iter().map(|err| format!("\n  {err}")).collect();
+    exit_with_error!("Nothing was imported. These entries can't be imported:{errors}");
 }
 
 fn get_week_number(
@@ -173,6 +1251,36 @@ fn get_week_number(
     }
 }
 
+fn get_period_of_weeks(weeks: WeekRange, year: Option<i32>) -> anyhow::Result<Period> {
+    let first = WeekNumber::new_with_year_fallback(weeks.first, year)?;
+    let last = WeekNumber::new_with_year_fallback(weeks.last, year)?;
+
+    Period::weeks(&first, &last)
+}
+
+/// The days to change, per week, from either `--date` or `--day` and the week. Weekends within a
+/// range of dates are skipped
+fn get_days_per_week(days: &super::arguments::Days) -> Vec<(WeekNumber, Days)> {
+    if let Some(dates) = &days.dates {
+        return dates.workdays_per_week();
+    }
+
+    if days.days.as_ref().is_some_and(|days| days.is_empty()) {
+        exit_with_error!("`--day` is set but no day was provided");
+    }
+
+    let week = get_week_number(&days.week.number, &days.week.previous, &days.week.year);
+    vec![(week, get_days(days.days.clone()))]
+}
+
+/// Tells which weeks were changed before changing a later week failed, since they aren't changed
+/// back
+fn report_changed_weeks(changed: &[&WeekNumber]) {
+    for week in changed {
+        eprintln!("{week} was changed before the failure, and keeps its changes");
+    }
+}
+
 fn get_days(days: Option<Days>) -> Days {
     days.unwrap_or_else(|| {
         // Fall back to today's weekday
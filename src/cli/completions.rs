@@ -0,0 +1,255 @@
+use super::arguments::Args;
+use crate::config::Configuration;
+use crate::domain::matching::MatchKind;
+use crate::infrastructure::completion_cache::CompletionCache;
+use clap::CommandFactory;
+use clap_complete::{
+    env::{Bash, EnvCompleter, Fish, Shells, Zsh},
+    CompletionCandidate,
+};
+use std::ffi::{OsStr, OsString};
+
+const BIN_NAME: &str = "maconomy";
+
+/// Environment variable that makes the binary output completions instead of running a command
+const COMPLETE_VAR: &str = "COMPLETE";
+
+#[derive(Debug, Clone, clap::ValueEnum)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+}
+
+/// Outputs completions if the shell asked for them (through `COMPLETE=<shell>`), and exits
+pub(crate) fn complete_if_requested() {
+    clap_complete::CompleteEnv::with_factory(Args::command)
+        .var(COMPLETE_VAR)
+        .shells(Shells(&[&Bash, &Zsh, &Fish, &Nushell]))
+        .complete();
+}
+
+/// Writes the completion script for `shell`, which calls back into `maconomy` to complete
+/// arguments, including job and task names
+pub(crate) fn write_completions(shell: Shell, buf: &mut dyn std::io::Write) -> std::io::Result<()> {
+    let registration: &dyn EnvCompleter = match shell {
+        Shell::Bash => &Bash,
+        Shell::Zsh => &Zsh,
+        Shell::Fish => &Fish,
+        Shell::Nushell => &Nushell,
+    };
+
+    registration.write_registration(COMPLETE_VAR, BIN_NAME, BIN_NAME, BIN_NAME, buf)
+}
+
+/// Nushell, which clap only has static completions for. Registers an external completer for
+/// `maconomy`, which hands other commands to the external completer that was set before it
+struct Nushell;
+
+impl EnvCompleter for Nushell {
+    fn name(&self) -> &'static str {
+        "nushell"
+    }
+
+    fn is(&self, name: &str) -> bool {
+        name == "nushell" || name == "nu"
+    }
+
+    fn write_registration(
+        &self,
+        var: &str,
+        name: &str,
+        bin: &str,
+        completer: &str,
+        buf: &mut dyn std::io::Write,
+    ) -> Result<(), std::io::Error> {
+        let variable = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+        writeln!(
+            buf,
+            r#"let {variable}_previous_completer = $env.config.completions.external.completer?
+$env.config.completions.external.enable = true
+$env.config.completions.external.completer = {{|spans|
+    if ($spans.0 == "{bin}") {{
+        with-env {{ {var}: "nushell" }} {{ ^"{completer}" -- ...$spans }}
+        | lines
+        | each {{|line|
+            let parts = $line | split row "\t"
+            {{ value: $parts.0, description: $parts.1? }}
+        }}
+    }} else if ${variable}_previous_completer != null {{
+        do ${variable}_previous_completer $spans
+    }}
+}}"#
+        )
+    }
+
+    fn write_complete(
+        &self,
+        cmd: &mut clap::Command,
+        args: Vec<OsString>,
+        current_dir: Option<&std::path::Path>,
+        buf: &mut dyn std::io::Write,
+    ) -> Result<(), std::io::Error> {
+        // The last span is the argument being completed, which is empty after a space
+        let index = args.len() - 1;
+        let completions = clap_complete::engine::complete(cmd, args, index, current_dir)?;
+
+        for candidate in completions {
+            write!(buf, "{}", candidate.get_value().to_string_lossy())?;
+            if let Some(help) = candidate.get_help() {
+                let help = help.to_string();
+                write!(buf, "\t{}", help.lines().next().unwrap_or_default())?;
+            }
+            writeln!(buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Completes `--job` from the local cache
+pub(crate) fn complete_job(current: &OsStr) -> Vec<CompletionCandidate> {
+    let cache = load_cache(std::env::args_os());
+    candidates(current, cache.job_names())
+}
+
+/// Completes `--task` from the local cache, limited to the tasks of the job on the command line
+pub(crate) fn complete_task(current: &OsStr) -> Vec<CompletionCandidate> {
+    let cache = load_cache(std::env::args_os());
+    let job = find_argument(std::env::args_os(), "--job", "-j");
+
+    let mut tasks = cache.task_names(job.as_deref());
+    if tasks.is_empty() {
+        // We may not know the tasks of the job yet, so fall back to all known tasks
+        tasks = cache.task_names(None);
+    }
+
+    candidates(current, tasks)
+}
+
+/// Loads the cache from where the configuration of the command being completed has it. Nothing
+/// is suggested if the configuration can't be read, since completing shouldn't print errors
+fn load_cache(args: impl IntoIterator<Item = OsString>) -> CompletionCache {
+    let path = Configuration::try_new(find_argument(args, "--config", "-c"))
+        .and_then(|config| CompletionCache::path(&config));
+    match path {
+        Ok(path) => CompletionCache::load(&path),
+        Err(_) => CompletionCache::default(),
+    }
+}
+
+fn candidates(current: &OsStr, names: Vec<&str>) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+
+    names
+        .into_iter()
+        .filter(|name| {
+            current.is_empty()
+                || matches!(
+                    MatchKind::of(&current, name),
+                    Some(MatchKind::Exact | MatchKind::Prefix)
+                )
+        })
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Finds the value of an option, such as `--job`/`-j`, in the arguments of the command being
+/// completed
+fn find_argument(
+    args: impl IntoIterator<Item = OsString>,
+    long: &str,
+    short: &str,
+) -> Option<String> {
+    let args: Vec<_> = args
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if let Some(value) = arg.strip_prefix(&format!("{long}=")) {
+            Some(value.to_string())
+        } else if arg == long || arg == short {
+            args.get(i + 1).cloned()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<OsString> {
+        args.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn finds_job_argument() {
+        let find = |arguments: &[&str]| find_argument(args(arguments), "--job", "-j");
+
+        assert_eq!(
+            find(&["maconomy", "--", "maconomy", "set", "8", "-j", "Acme", "-t"]),
+            Some("Acme".to_string())
+        );
+        assert_eq!(
+            find(&["maconomy", "set", "--job=Acme Corp", "--task"]),
+            Some("Acme Corp".to_string())
+        );
+        assert_eq!(find(&["maconomy", "set", "--task"]), None);
+    }
+
+    #[test]
+    fn loads_empty_cache_for_malformed_config() {
+        let arguments = args(&["maconomy", "--", "maconomy", "-c", "[jobs", "set", "-j"]);
+
+        assert!(load_cache(arguments).job_names().is_empty());
+    }
+
+    #[test]
+    fn finds_config_argument() {
+        let arguments = args(&["maconomy", "--", "maconomy", "-c", "<config>", "set", "-j"]);
+
+        assert_eq!(
+            find_argument(arguments, "--config", "-c"),
+            Some("<config>".to_string())
+        );
+    }
+
+    #[test]
+    fn completes_for_nushell() {
+        let mut output = Vec::new();
+
+        Nushell
+            .write_complete(
+                &mut Args::command(),
+                args(&["maconomy", "subm"]),
+                None,
+                &mut output,
+            )
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.starts_with("submit\tSubmit time sheet for week\n"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn completes_by_prefix() {
+        let names = vec!["Acme", "Acme Labs", "Globex"];
+        let values = |current: &str| {
+            candidates(OsStr::new(current), names.clone())
+                .into_iter()
+                .map(|candidate| candidate.get_value().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(values("acm"), vec!["Acme", "Acme Labs"]);
+        assert_eq!(values(""), vec!["Acme", "Acme Labs", "Globex"]);
+        assert!(values("labs").is_empty());
+    }
+}
@@ -4,7 +4,7 @@ use nom::{
     branch::alt,
     bytes::complete::{tag, take_while_m_n},
     character::complete::{space0, space1},
-    combinator::{map, map_res},
+    combinator::{all_consuming, map, map_res},
     multi::separated_list0,
     sequence::{delimited, separated_pair},
     Finish, IResult, Parser,
@@ -50,6 +50,17 @@ pub(crate) fn parse_days_of_week(input: &str) -> anyhow::Result<Days> {
     Ok(days)
 }
 
+/// Parses a single day, which like in `parse_days_of_week` may be shortened to a prefix
+pub(crate) fn parse_day(input: &str) -> anyhow::Result<Day> {
+    let (_, day) = all_consuming(day_prefix)
+        .parse(input)
+        .map_err(|err| err.to_owned())
+        .finish()
+        .with_context(|| format!("Invalid day '{input}'"))?;
+
+    Ok(day)
+}
+
 fn day_prefix(input: &str) -> IResult<&str, Day> {
     map_res(take_while_m_n(2, 9, char::is_alphabetic), |prefix: &str| {
         let week = [
@@ -194,6 +205,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parses_single_day() {
+        assert_eq!(parse_day("fri").unwrap(), Day::Friday);
+        assert!(parse_day("fri, sat").is_err());
+        assert!(parse_day("f").is_err());
+    }
+
     #[test]
     fn gets_days_in_range() {
         let range = (Day::Tuesday, Day::Friday);
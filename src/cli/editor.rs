@@ -0,0 +1,872 @@
+use super::rendering::{Row, FIRST_DAY_COLUMN};
+use crate::domain::models::{
+    day::Day,
+    hours::Hours,
+    job::{Job, Task},
+    line_number::LineNumber,
+    time_sheet::{Line, TimeSheet, Week},
+    week::WeekNumber,
+};
+use crate::domain::time_sheet_service::TimeSheetService;
+use crate::infrastructure::repositories::time_sheet_repository::TimeSheetRepository;
+use crate::utils::errors::error_stack_fmt;
+use anyhow::{Context, Result};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Text,
+    widgets::{Block, BorderType, Cell, Clear, List, ListState, Paragraph, Table, TableState},
+    DefaultTerminal, Frame,
+};
+use std::{collections::HashMap, rc::Rc};
+use tabled::Tabled;
+use tokio::sync::Mutex;
+
+const HELP: &str = "←↑↓→ move · 0-9 type hours · ⌫ clear · a add line · d delete line · s save · \
+                    q quit";
+
+enum LineKind {
+    /// A line that exists in Maconomy, with its (0-indexed) line number and its hours before editing
+    Existing { line_number: u8, original: Week },
+    /// A line that gets added to Maconomy when saving
+    New { job: Job, task: Task },
+}
+
+struct EditorLine {
+    line: Line,
+    kind: LineKind,
+    deleted: bool,
+}
+
+impl EditorLine {
+    fn is_changed(&self, day: Day) -> bool {
+        match &self.kind {
+            LineKind::Existing { original, .. } => original.get(day) != self.line.week.get(day),
+            LineKind::New { .. } => !self.line.week.get(day).is_zero(),
+        }
+    }
+
+    fn changed_hours(&self) -> HashMap<Day, Hours> {
+        Day::ALL
+            .into_iter()
+            .filter(|&day| self.is_changed(day))
+            .map(|day| (day, self.line.week.get(day)))
+            .collect()
+    }
+}
+
+/// Everything that needs to be sent to Maconomy to save the edited time sheet
+#[derive(Debug, Default, PartialEq)]
+struct Changes {
+    /// Changed hours on existing lines, by 0-indexed line number
+    hours: Vec<(u8, HashMap<Day, Hours>)>,
+    /// 0-indexed line numbers of deleted lines, in descending order so that deleting one line
+    /// doesn't shift the others
+    deleted: Vec<u8>,
+    added: Vec<(Job, Task, HashMap<Day, Hours>)>,
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        self.hours.is_empty() && self.deleted.is_empty() && self.added.is_empty()
+    }
+}
+
+enum Mode {
+    Normal,
+    EditingCell(String),
+    SearchingJob(String),
+    PickingJob {
+        jobs: Vec<Job>,
+        selected: usize,
+    },
+    PickingTask {
+        job: Job,
+        tasks: Vec<Task>,
+        selected: usize,
+    },
+    ConfirmingQuit,
+}
+
+/// Something that the editor needs to do outside of its own state
+#[derive(Debug, PartialEq)]
+enum Action {
+    None,
+    Quit,
+    Save,
+    SearchJobs(String),
+    GetTasks(Job),
+}
+
+struct EditorState {
+    week_number: u8,
+    lines: Vec<EditorLine>,
+    row: usize,
+    day: usize,
+    mode: Mode,
+    status: Option<String>,
+}
+
+impl EditorState {
+    fn new(time_sheet: TimeSheet) -> Self {
+        let lines = time_sheet
+            .lines
+            .into_iter()
+            .enumerate()
+            .map(|(line_number, line)| EditorLine {
+                kind: LineKind::Existing {
+                    line_number: line_number as u8,
+                    original: line.week.clone(),
+                },
+                line,
+                deleted: false,
+            })
+            .collect();
+
+        Self {
+            week_number: time_sheet.week_number,
+            lines,
+            row: 0,
+            day: 0,
+            mode: Mode::Normal,
+            status: None,
+        }
+    }
+
+    fn changes(&self) -> Changes {
+        let mut changes = Changes::default();
+
+        for line in &self.lines {
+            match (&line.kind, line.deleted) {
+                (LineKind::Existing { line_number, .. }, true) => {
+                    changes.deleted.push(*line_number)
+                }
+                (LineKind::Existing { line_number, .. }, false) => {
+                    let hours = line.changed_hours();
+                    if !hours.is_empty() {
+                        changes.hours.push((*line_number, hours));
+                    }
+                }
+                (LineKind::New { job, task }, _) => {
+                    changes
+                        .added
+                        .push((job.clone(), task.clone(), line.changed_hours()))
+                }
+            }
+        }
+
+        changes.deleted.sort_unstable_by(|a, b| b.cmp(a));
+        changes
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        self.status = None;
+
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::EditingCell(input) => self.handle_editing_key(key, input),
+            Mode::SearchingJob(mut query) => match key.code {
+                KeyCode::Enter => Action::SearchJobs(query),
+                KeyCode::Esc => Action::None,
+                code => {
+                    match code {
+                        KeyCode::Char(c) => query.push(c),
+                        KeyCode::Backspace => {
+                            query.pop();
+                        }
+                        _ => (),
+                    }
+                    self.mode = Mode::SearchingJob(query);
+                    Action::None
+                }
+            },
+            Mode::PickingJob { jobs, selected } => match key.code {
+                KeyCode::Enter => match jobs.get(selected) {
+                    Some(job) => Action::GetTasks(job.clone()),
+                    None => Action::None,
+                },
+                KeyCode::Esc => Action::None,
+                code => {
+                    let selected = move_selection(code, selected, jobs.len());
+                    self.mode = Mode::PickingJob { jobs, selected };
+                    Action::None
+                }
+            },
+            Mode::PickingTask {
+                job,
+                tasks,
+                selected,
+            } => match key.code {
+                KeyCode::Enter => {
+                    if let Some(task) = tasks.get(selected) {
+                        self.add_line(job, task.clone());
+                    }
+                    Action::None
+                }
+                KeyCode::Esc => Action::None,
+                code => {
+                    let selected = move_selection(code, selected, tasks.len());
+                    self.mode = Mode::PickingTask {
+                        job,
+                        tasks,
+                        selected,
+                    };
+                    Action::None
+                }
+            },
+            Mode::ConfirmingQuit => match key.code {
+                KeyCode::Char('y') => Action::Quit,
+                _ => Action::None,
+            },
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
+                self.move_cursor(key.code)
+            }
+            KeyCode::Tab => self.move_cursor(KeyCode::Right),
+            KeyCode::BackTab => self.move_cursor(KeyCode::Left),
+            // Letters are reserved for commands, so "4h" can only be typed after the first digit
+            KeyCode::Char(c)
+                if is_hours_char(c) && !c.is_alphabetic() && self.selected_line().is_some() =>
+            {
+                self.mode = Mode::EditingCell(c.to_string());
+            }
+            KeyCode::Backspace | KeyCode::Delete => self.set_selected_hours(Hours::ZERO),
+            KeyCode::Char('a') => self.mode = Mode::SearchingJob(String::new()),
+            KeyCode::Char('d') => self.toggle_delete(),
+            KeyCode::Char('s') => return Action::Save,
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if self.changes().is_empty() {
+                    return Action::Quit;
+                }
+                self.mode = Mode::ConfirmingQuit;
+            }
+            _ => (),
+        }
+
+        Action::None
+    }
+
+    fn handle_editing_key(&mut self, key: KeyEvent, mut input: String) -> Action {
+        match key.code {
+            KeyCode::Char(c) if is_hours_char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => return Action::None,
+            KeyCode::Enter
+            | KeyCode::Tab
+            | KeyCode::BackTab
+            | KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Left
+            | KeyCode::Right => match input.parse() {
+                Ok(hours) => {
+                    self.set_selected_hours(hours);
+                    let direction = match key.code {
+                        KeyCode::Tab => KeyCode::Right,
+                        KeyCode::BackTab => KeyCode::Left,
+                        code => code,
+                    };
+                    self.move_cursor(direction);
+                    return Action::None;
+                }
+                Err(err) => self.status = Some(format!("Invalid hours '{input}': {err}")),
+            },
+            _ => (),
+        }
+
+        self.mode = Mode::EditingCell(input);
+        Action::None
+    }
+
+    fn move_cursor(&mut self, direction: KeyCode) {
+        match direction {
+            KeyCode::Up => self.row = self.row.saturating_sub(1),
+            KeyCode::Down => self.row = (self.row + 1).min(self.lines.len().saturating_sub(1)),
+            KeyCode::Left => self.day = self.day.saturating_sub(1),
+            KeyCode::Right => self.day = (self.day + 1).min(Day::ALL.len() - 1),
+            _ => (),
+        }
+    }
+
+    fn selected_line(&mut self) -> Option<&mut EditorLine> {
+        self.lines.get_mut(self.row).filter(|line| !line.deleted)
+    }
+
+    fn set_selected_hours(&mut self, hours: Hours) {
+        let day = Day::ALL[self.day];
+        if let Some(line) = self.selected_line() {
+            line.line.week.set(day, hours);
+        }
+    }
+
+    fn toggle_delete(&mut self) {
+        let Some(line) = self.lines.get_mut(self.row) else {
+            return;
+        };
+
+        match line.kind {
+            // New lines don't exist in Maconomy yet, so we can just forget about them
+            LineKind::New { .. } => {
+                // The cursor stays on the row, which is now the line after the removed one
+                self.lines.remove(self.row);
+                self.row = self.row.min(self.lines.len().saturating_sub(1));
+            }
+            LineKind::Existing { .. } => line.deleted = !line.deleted,
+        }
+    }
+
+    fn add_line(&mut self, job: Job, task: Task) {
+        let existing = self.lines.iter().position(|line| {
+            line.line.job.to_lowercase() == job.name.to_lowercase()
+                && line.line.task.to_lowercase() == task.description.to_lowercase()
+        });
+
+        if let Some(row) = existing {
+            self.status = Some("That line is already in the time sheet".to_string());
+            self.row = row;
+            return;
+        }
+
+        let line = Line::new(job.name.clone(), task.description.clone(), Week::default())
+            .with_job_number(job.number.clone())
+            .with_task_short_name(task.short_name.clone());
+
+        self.lines.push(EditorLine {
+            line,
+            kind: LineKind::New { job, task },
+            deleted: false,
+        });
+        self.row = self.lines.len() - 1;
+    }
+
+    fn show_jobs(&mut self, jobs: Vec<Job>) {
+        if jobs.is_empty() {
+            self.status = Some("Found no matching jobs".to_string());
+        } else {
+            self.mode = Mode::PickingJob { jobs, selected: 0 };
+        }
+    }
+
+    fn show_tasks(&mut self, job: Job, tasks: Vec<Task>) {
+        if tasks.is_empty() {
+            self.status = Some(format!("Found no tasks for job '{}'", job.name));
+        } else {
+            self.mode = Mode::PickingTask {
+                job,
+                tasks,
+                selected: 0,
+            };
+        }
+    }
+}
+
+fn move_selection(code: KeyCode, selected: usize, len: usize) -> usize {
+    match code {
+        KeyCode::Up => selected.saturating_sub(1),
+        KeyCode::Down => (selected + 1).min(len.saturating_sub(1)),
+        _ => selected,
+    }
+}
+
+fn is_hours_char(c: char) -> bool {
+    c.is_ascii_digit() || matches!(c, ':' | '.' | ',' | 'h' | 'm')
+}
+
+pub(crate) struct Editor<'a> {
+    repository: Rc<Mutex<TimeSheetRepository<'a>>>,
+    time_sheet_service: Rc<Mutex<TimeSheetService<'a>>>,
+    week: WeekNumber,
+}
+
+impl<'a> Editor<'a> {
+    pub(crate) fn new(
+        repository: Rc<Mutex<TimeSheetRepository<'a>>>,
+        time_sheet_service: Rc<Mutex<TimeSheetService<'a>>>,
+        week: WeekNumber,
+    ) -> Self {
+        Self {
+            repository,
+            time_sheet_service,
+            week,
+        }
+    }
+
+    pub(crate) async fn run(&self) -> Result<()> {
+        // Fetch the time sheet before taking over the terminal, in case we need to log in
+        let time_sheet = self.get_time_sheet().await?;
+
+        let mut terminal = ratatui::try_init().context("Failed to initialize terminal")?;
+        let result = self.run_event_loop(&mut terminal, time_sheet).await;
+        ratatui::try_restore().context("Failed to restore terminal")?;
+
+        result
+    }
+
+    async fn get_time_sheet(&self) -> Result<TimeSheet> {
+        self.repository
+            .lock()
+            .await
+            .get_time_sheet(&self.week)
+            .await
+            .context("Failed to get time sheet")
+    }
+
+    async fn run_event_loop(
+        &self,
+        terminal: &mut DefaultTerminal,
+        time_sheet: TimeSheet,
+    ) -> Result<()> {
+        let mut state = EditorState::new(time_sheet);
+
+        loop {
+            terminal
+                .draw(|frame| draw(frame, &state))
+                .context("Failed to draw editor")?;
+
+            let Event::Key(key) = event::read().context("Failed to read terminal event")? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match state.handle_key(key) {
+                Action::None => (),
+                Action::Quit => return Ok(()),
+                Action::Save => {
+                    state = self.save(state).await?;
+                    // Messages from the save may have been printed on top of the editor
+                    terminal.clear().context("Failed to clear terminal")?;
+                }
+                Action::SearchJobs(query) => {
+                    let query = Some(query.as_str()).filter(|query| !query.is_empty());
+                    match self.repository.lock().await.search_jobs(query).await {
+                        Ok(jobs) => state.show_jobs(jobs),
+                        Err(err) => state.status = Some(error_stack_fmt(&err).to_string()),
+                    }
+                }
+                Action::GetTasks(job) => {
+                    let tasks = self
+                        .repository
+                        .lock()
+                        .await
+                        .get_tasks_for_job_number(&job.number)
+                        .await;
+                    match tasks {
+                        Ok(tasks) => state.show_tasks(job, tasks),
+                        Err(err) => state.status = Some(error_stack_fmt(&err).to_string()),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Saves the changes and returns a fresh state from the time sheet in Maconomy. The state is
+    /// refreshed even if saving fails, since the changes that were saved before the failure shift
+    /// the line numbers of the rest
+    async fn save(&self, mut state: EditorState) -> Result<EditorState> {
+        let changes = state.changes();
+        if changes.is_empty() {
+            state.status = Some("No changes to save".to_string());
+            return Ok(state);
+        }
+
+        let status = match self.send_changes(changes).await {
+            Ok(()) => "Saved".to_string(),
+            Err(err) => format!(
+                "Failed to save, so the time sheet was reloaded: {}",
+                error_stack_fmt(&err)
+            ),
+        };
+        let time_sheet = self.get_time_sheet().await?;
+
+        let (row, day) = (state.row, state.day);
+        let mut state = EditorState::new(time_sheet);
+        state.row = row.min(state.lines.len().saturating_sub(1));
+        state.day = day;
+        state.status = Some(status);
+
+        Ok(state)
+    }
+
+    async fn send_changes(&self, changes: Changes) -> Result<()> {
+        let week = &self.week;
+
+        {
+            let mut repository = self.repository.lock().await;
+
+            // Hours are set before deleting lines, since deletions shift the line numbers
+            for (line_number, hours) in &changes.hours {
+                repository
+                    .set_line_hours(week, *line_number, hours)
+                    .await
+                    .with_context(|| format!("Failed to set hours on line {}", line_number + 1))?;
+            }
+
+            for line_number in &changes.deleted {
+                let line_number = LineNumber::Number(line_number + 1);
+                repository
+                    .delete_line(&line_number, week)
+                    .await
+                    .with_context(|| format!("Failed to delete line {line_number:?}"))?;
+            }
+        }
+
+        for (job, task, hours) in &changes.added {
+            let line_number = self
+                .time_sheet_service
+                .lock()
+                .await
+                .add_line(week, job, task)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to add line for '{}', '{}'",
+                        job.name, task.description
+                    )
+                })?;
+
+            if !hours.is_empty() {
+                self.repository
+                    .lock()
+                    .await
+                    .set_line_hours(week, line_number, hours)
+                    .await
+                    .context("Failed to set hours on new line")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn draw(frame: &mut Frame, state: &EditorState) {
+    let [table_area, footer_area] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(3)]).areas(frame.area());
+
+    draw_table(frame, table_area, state);
+
+    let status = state.status.as_deref().unwrap_or(match state.mode {
+        Mode::ConfirmingQuit => "You have unsaved changes. Quit anyway? (y/n)",
+        Mode::EditingCell(_) => "Enter to confirm · Esc to cancel",
+        _ => HELP,
+    });
+    let footer = Paragraph::new(status)
+        .style(Style::new().fg(Color::DarkGray))
+        .block(Block::bordered().border_type(BorderType::Rounded));
+    frame.render_widget(footer, footer_area);
+
+    match &state.mode {
+        Mode::SearchingJob(query) => {
+            let area = popup_area(frame.area(), 3);
+            let prompt = Paragraph::new(format!("{query}▏")).block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title(" Search for job (empty for all jobs) "),
+            );
+            frame.render_widget(Clear, area);
+            frame.render_widget(prompt, area);
+        }
+        Mode::PickingJob { jobs, selected } => {
+            let items = jobs
+                .iter()
+                .map(|job| format!("{}  {}  ({})", job.number, job.name, job.customer));
+            draw_picker(frame, " Pick a job ", items, *selected);
+        }
+        Mode::PickingTask {
+            job,
+            tasks,
+            selected,
+        } => {
+            let title = format!(" Pick a task for '{}' ", job.name);
+            let items = tasks.iter().map(|task| task.description.clone());
+            draw_picker(frame, &title, items, *selected);
+        }
+        _ => (),
+    }
+}
+
+fn draw_table(frame: &mut Frame, area: Rect, state: &EditorState) {
+    let header = ratatui::widgets::Row::new(Row::headers().into_iter().map(String::from))
+        .style(Style::new().add_modifier(Modifier::BOLD));
+
+    let rows = state.lines.iter().enumerate().map(|(row, line)| {
+        let fields: Vec<String> = Row::from(&line.line)
+            .fields()
+            .into_iter()
+            .map(|field| field.into_owned())
+            .collect();
+        let cells = fields.into_iter().enumerate().map(|(column, field)| {
+            let day = column
+                .checked_sub(FIRST_DAY_COLUMN)
+                .and_then(|day| Day::ALL.get(day).copied());
+            let is_selected = row == state.row && day == Some(Day::ALL[state.day]);
+
+            let text = match (&state.mode, is_selected) {
+                (Mode::EditingCell(input), true) => format!("{input}▏"),
+                _ => field,
+            };
+
+            let mut style = Style::new();
+            if day.is_some_and(|day| line.is_changed(day)) {
+                style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD);
+            }
+            if is_selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Cell::new(text).style(style)
+        });
+
+        let style = match (&line.kind, line.deleted) {
+            (_, true) => Style::new()
+                .fg(Color::Red)
+                .add_modifier(Modifier::CROSSED_OUT),
+            (LineKind::New { .. }, _) => Style::new().fg(Color::Green),
+            _ => Style::new(),
+        };
+        ratatui::widgets::Row::new(cells).style(style)
+    });
+
+    let name_width = |name: fn(&Line) -> &str| {
+        state
+            .lines
+            .iter()
+            .map(|line| name(&line.line).chars().count())
+            .max()
+            .unwrap_or_default()
+            .max(9) as u16
+    };
+    let widths = [
+        Constraint::Length(name_width(|line| &line.job)),
+        Constraint::Length(name_width(|line| &line.task)),
+    ]
+    .into_iter()
+    .chain(Day::ALL.map(|_| Constraint::Length(6)))
+    // Total
+    .chain([Constraint::Length(6)])
+    // Comment
+    .chain([Constraint::Fill(1)]);
+
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(2)
+        .block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(format!(" Week {} ", state.week_number)),
+        );
+
+    let mut table_state = TableState::default().with_selected(Some(state.row));
+    frame.render_stateful_widget(table, area, &mut table_state);
+}
+
+fn draw_picker(
+    frame: &mut Frame,
+    title: &str,
+    items: impl Iterator<Item = String>,
+    selected: usize,
+) {
+    let items: Vec<_> = items.map(Text::from).collect();
+    let area = popup_area(frame.area(), items.len() as u16 + 2);
+
+    let list = List::new(items)
+        .block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(title.to_string()),
+        )
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut ListState::default().with_selected(Some(selected)),
+    );
+}
+
+fn popup_area(area: Rect, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Percentage(70)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height.min(area.height))])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_keys(state: &mut EditorState, input: &str) {
+        for c in input.chars() {
+            state.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    fn create_time_sheet() -> TimeSheet {
+        let week = |monday: u32| Week {
+            monday: Hours::from_minutes(monday * 60),
+            tuesday: Hours::ZERO,
+            wednesday: Hours::ZERO,
+            thursday: Hours::ZERO,
+            friday: Hours::ZERO,
+            saturday: Hours::ZERO,
+            sunday: Hours::ZERO,
+        };
+        let lines = vec![
+            Line::new("Job one".to_string(), "Task one".to_string(), week(8)),
+            Line::new("Job two".to_string(), "Task two".to_string(), week(0)),
+            Line::new("Job three".to_string(), "Task three".to_string(), week(0)),
+        ];
+        TimeSheet::new(lines, 42)
+    }
+
+    fn create_job_and_task() -> (Job, Task) {
+        let job = Job {
+            number: "1234".to_string(),
+            name: "Job four".to_string(),
+            customer: "Customer".to_string(),
+        };
+        let task = Task {
+            short_name: "400".to_string(),
+            description: "Task four".to_string(),
+        };
+        (job, task)
+    }
+
+    #[test]
+    fn has_no_changes_initially() {
+        let state = EditorState::new(create_time_sheet());
+
+        assert!(state.changes().is_empty());
+    }
+
+    #[test]
+    fn only_includes_changed_cells() {
+        let mut state = EditorState::new(create_time_sheet());
+
+        // Set Tuesday on line 2, and set Monday on line 1 to the hours it already has
+        type_keys(&mut state, "8");
+        state.handle_key(key(KeyCode::Enter));
+        state.handle_key(key(KeyCode::Down));
+        state.handle_key(key(KeyCode::Right));
+        type_keys(&mut state, "4:30");
+        state.handle_key(key(KeyCode::Enter));
+
+        let expected = Changes {
+            hours: vec![(1, HashMap::from([(Day::Tuesday, Hours::from_minutes(270))]))],
+            ..Default::default()
+        };
+        assert_eq!(state.changes(), expected);
+    }
+
+    #[test]
+    fn clears_cell() {
+        let mut state = EditorState::new(create_time_sheet());
+
+        state.handle_key(key(KeyCode::Backspace));
+
+        let expected = Changes {
+            hours: vec![(0, HashMap::from([(Day::Monday, Hours::ZERO)]))],
+            ..Default::default()
+        };
+        assert_eq!(state.changes(), expected);
+    }
+
+    #[test]
+    fn keeps_editing_invalid_hours() {
+        let mut state = EditorState::new(create_time_sheet());
+
+        type_keys(&mut state, "4:75");
+        state.handle_key(key(KeyCode::Enter));
+
+        assert!(matches!(&state.mode, Mode::EditingCell(input) if input == "4:75"));
+        assert!(state.status.is_some());
+        assert!(state.changes().is_empty());
+    }
+
+    #[test]
+    fn deletes_lines_in_descending_order() {
+        let mut state = EditorState::new(create_time_sheet());
+
+        state.handle_key(key(KeyCode::Char('d')));
+        state.handle_key(key(KeyCode::Down));
+        state.handle_key(key(KeyCode::Down));
+        state.handle_key(key(KeyCode::Char('d')));
+
+        assert_eq!(state.changes().deleted, vec![2, 0]);
+    }
+
+    #[test]
+    fn adds_line_with_hours() {
+        let mut state = EditorState::new(create_time_sheet());
+        let (job, task) = create_job_and_task();
+
+        state.add_line(job.clone(), task.clone());
+        state.handle_key(key(KeyCode::Right));
+        type_keys(&mut state, "2");
+        state.handle_key(key(KeyCode::Enter));
+
+        let hours = HashMap::from([(Day::Tuesday, Hours::from_minutes(120))]);
+        let expected = Changes {
+            added: vec![(job, task, hours)],
+            ..Default::default()
+        };
+        assert_eq!(state.changes(), expected);
+    }
+
+    #[test]
+    fn forgets_deleted_new_lines() {
+        let mut state = EditorState::new(create_time_sheet());
+        let (job, task) = create_job_and_task();
+
+        state.add_line(job, task);
+        state.handle_key(key(KeyCode::Char('d')));
+
+        assert_eq!(state.lines.len(), 3);
+        assert!(state.changes().is_empty());
+    }
+
+    #[test]
+    fn stays_on_row_after_forgetting_new_line() {
+        let mut state = EditorState::new(create_time_sheet());
+        let (job, task) = create_job_and_task();
+        let other_task = |number: &str, name: &str| Task {
+            short_name: number.to_string(),
+            description: name.to_string(),
+        };
+
+        state.add_line(job.clone(), task);
+        state.add_line(job.clone(), other_task("500", "Task five"));
+        state.add_line(job, other_task("600", "Task six"));
+        state.handle_key(key(KeyCode::Up));
+        state.handle_key(key(KeyCode::Up));
+        state.handle_key(key(KeyCode::Char('d')));
+
+        assert_eq!(state.row, 3);
+        assert_eq!(state.lines[state.row].line.task, "Task five");
+    }
+
+    #[test]
+    fn asks_before_quitting_with_unsaved_changes() {
+        let mut state = EditorState::new(create_time_sheet());
+        assert_eq!(state.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+
+        state.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(state.handle_key(key(KeyCode::Char('q'))), Action::None);
+        assert_eq!(state.handle_key(key(KeyCode::Char('y'))), Action::Quit);
+    }
+}
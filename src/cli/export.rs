@@ -0,0 +1,256 @@
+//! Reports of what was registered, for handing over to clients. Everything is rendered locally,
+//! so the files don't depend on any other service
+
+use crate::domain::models::report::{Report, ReportEntry};
+use chrono::{DateTime, Days, Utc};
+use std::fmt::Write;
+
+const PRODUCT_ID: &str = "-//maconomy-cli//Time sheet export//EN";
+
+/// Lines longer than this many bytes are folded, as iCalendar requires
+const MAX_ICS_LINE_LENGTH: usize = 75;
+
+/// One all-day event per day and line, so that calendars show what was worked on each day.
+/// `generated` is the time stamp that iCalendar requires on every event
+pub(crate) fn ics(report: &Report, generated: DateTime<Utc>) -> String {
+    let stamp = generated.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+    if !report.employee.is_empty() {
+        let name = format!("Time sheet of {}", report.employee);
+        lines.push(format!("X-WR-CALNAME:{}", escape_ics(&name)));
+    }
+
+    for (i, entry) in report.entries.iter().enumerate() {
+        let end = entry.date + Days::new(1);
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}", uid(entry, i)),
+            format!("DTSTAMP:{stamp}"),
+            format!("DTSTART;VALUE=DATE:{}", entry.date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            format!("SUMMARY:{}", escape_ics(&summary(entry))),
+            format!("DESCRIPTION:{}", escape_ics(&description(entry))),
+            format!("CATEGORIES:{}", escape_ics(&entry.job)),
+            // The hours don't take up any particular time of the day
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_ics(line) + "\r\n").collect()
+}
+
+fn summary(entry: &ReportEntry) -> String {
+    format!("{} {} – {}", entry.hours, entry.job, entry.task)
+}
+
+fn description(entry: &ReportEntry) -> String {
+    let mut description = format!(
+        "Job: {} {}\nTask: {} {}\nHours: {} ({})",
+        entry.job_number,
+        entry.job,
+        entry.task_short_name,
+        entry.task,
+        entry.hours,
+        entry.hours.as_decimal()
+    );
+    if !entry.comment.is_empty() {
+        description.push_str(&format!("\nComment: {}", entry.comment));
+    }
+
+    description
+}
+
+/// Unique within the export, since a line can appear on several days and a job and task on several
+/// lines
+fn uid(entry: &ReportEntry, index: usize) -> String {
+    let job: String = entry
+        .job_number
+        .chars()
+        .chain(['-'])
+        .chain(entry.task_short_name.chars())
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+
+    format!("{}-{job}-{index}@maconomy-cli", entry.date.format("%Y%m%d"))
+}
+
+fn escape_ics(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits a line into lines of at most `MAX_ICS_LINE_LENGTH` bytes, where the lines after the
+/// first start with a space
+fn fold_ics(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_ICS_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+/// A self-contained page with the hours per job and per day, meant for printing or saving as PDF
+pub(crate) fn html(report: &Report) -> String {
+    let title = if report.employee.is_empty() {
+        format!("Time report {}", report.period)
+    } else {
+        format!("Time report {} {}", report.employee, report.period)
+    };
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>{}</title>", escape_html(&title));
+    html.push_str(STYLE);
+    html.push_str("</head>\n<body>\n<h1>Time report</h1>\n<dl>\n");
+    if !report.employee.is_empty() {
+        let _ = writeln!(
+            html,
+            "<dt>Employee</dt><dd>{}</dd>",
+            escape_html(&report.employee)
+        );
+    }
+    let _ = writeln!(html, "<dt>Period</dt><dd>{}</dd>", report.period);
+    html.push_str("</dl>\n");
+
+    html.push_str("<h2>Hours per job</h2>\n<table>\n");
+    html.push_str("<thead><tr><th>Job number</th><th>Job</th><th class=\"hours\">Hours</th></tr></thead>\n<tbody>\n");
+    for job in &report.jobs {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"hours\">{}</td></tr>",
+            escape_html(&job.job_number),
+            escape_html(&job.job),
+            job.hours
+        );
+    }
+    let _ = writeln!(
+        html,
+        "</tbody>\n<tfoot><tr><th colspan=\"2\">Total</th><th class=\"hours\">{}</th></tr></tfoot>\n</table>",
+        report.total
+    );
+
+    html.push_str("<h2>Hours per day</h2>\n<table>\n");
+    html.push_str("<thead><tr><th>Date</th><th>Job</th><th>Task</th><th class=\"hours\">Hours</th><th>Comment</th></tr></thead>\n<tbody>\n");
+    for entry in &report.entries {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"hours\">{}</td><td>{}</td></tr>",
+            entry.date.format("%a %Y-%m-%d"),
+            escape_html(&entry.job),
+            escape_html(&entry.task),
+            entry.hours,
+            escape_html(&entry.comment)
+        );
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+
+    html
+}
+
+const STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2em; }
+th, td { border-bottom: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; }
+tfoot th { border-top: 2px solid #222; }
+.hours { text-align: right; font-variant-numeric: tabular-nums; }
+dt { font-weight: bold; float: left; clear: left; width: 6em; }
+dd { margin-left: 6em; }
+@media print { body { margin: 0; } tr { break-inside: avoid; } }
+</style>
+";
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{hours::Hours, period::Period, report::JobSum};
+
+    fn report() -> Report {
+        let date = |date: &str| date.parse().unwrap();
+        let entry = |day: &str, job: &str, task: &str, minutes: u32, comment: &str| ReportEntry {
+            date: date(day),
+            job_number: format!("{}01", &job[..1]),
+            job: job.to_string(),
+            task_short_name: task.to_uppercase(),
+            task: task.to_string(),
+            comment: comment.to_string(),
+            hours: Hours::from_minutes(minutes),
+        };
+
+        Report {
+            employee: "John Smith".to_string(),
+            period: Period::new(date("2024-10-21"), date("2024-10-27")).unwrap(),
+            entries: vec![
+                entry(
+                    "2024-10-21",
+                    "Acme",
+                    "Development",
+                    270,
+                    "Fixed bugs, and <more>",
+                ),
+                entry("2024-10-21", "Globex & Co", "Meetings", 60, ""),
+                entry("2024-10-22", "Acme", "Development", 480, ""),
+            ],
+            jobs: vec![
+                JobSum {
+                    job_number: "A01".to_string(),
+                    job: "Acme".to_string(),
+                    hours: Hours::from_minutes(750),
+                },
+                JobSum {
+                    job_number: "G01".to_string(),
+                    job: "Globex & Co".to_string(),
+                    hours: Hours::from_minutes(60),
+                },
+            ],
+            total: Hours::from_minutes(810),
+        }
+    }
+
+    #[test]
+    fn renders_ics() {
+        let generated = "2024-10-28T12:00:00Z".parse().unwrap();
+
+        insta::assert_snapshot!(ics(&report(), generated));
+    }
+
+    #[test]
+    fn renders_html() {
+        insta::assert_snapshot!(html(&report()));
+    }
+
+    #[test]
+    fn folds_long_ics_lines() {
+        let line = format!("SUMMARY:{}", "æ".repeat(60));
+
+        let folded = fold_ics(&line);
+
+        let lines: Vec<_> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= MAX_ICS_LINE_LENGTH));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
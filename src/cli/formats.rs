@@ -0,0 +1,251 @@
+//! Plain text formats of time sheets, for spreadsheets, notes and scripts. The table in
+//! `rendering` is meant for reading in the terminal instead.
+
+use super::rendering::Row;
+use crate::domain::models::{day::Day, hours::Hours, time_sheet::TimeSheet, week::WeekNumber};
+use chrono::NaiveDate;
+use std::fmt::Write;
+
+const CSV_SEPARATOR: char = ',';
+const TSV_SEPARATOR: char = '\t';
+
+fn dates(week: &WeekNumber) -> [NaiveDate; 7] {
+    Day::ALL.map(|day| {
+        week.date(day)
+            .expect("Week numbers are validated when they are created")
+    })
+}
+
+pub(crate) fn csv(time_sheet: &TimeSheet, week: &WeekNumber) -> String {
+    separated_values(time_sheet, week, CSV_SEPARATOR)
+}
+
+pub(crate) fn tsv(time_sheet: &TimeSheet, week: &WeekNumber) -> String {
+    separated_values(time_sheet, week, TSV_SEPARATOR)
+}
+
+/// One row per line with decimal hours, so that spreadsheets can sum them
+fn separated_values(time_sheet: &TimeSheet, week: &WeekNumber, separator: char) -> String {
+    let header = ["Job number", "Job name", "Task short name", "Task name"]
+        .map(String::from)
+        .into_iter()
+        .chain(dates(week).map(|date| date.to_string()))
+        .chain(["Total", "Comment"].map(String::from));
+
+    let rows = time_sheet.lines.iter().map(Row::from).map(|row| {
+        [
+            row.job_number,
+            row.job_name,
+            row.task_short_name,
+            row.task_name,
+        ]
+        .map(String::from)
+        .into_iter()
+        .chain(row.days().map(|hours| hours.as_decimal().to_string()))
+        .chain([row.total.as_decimal().to_string(), row.comment.to_string()])
+        .collect::<Vec<_>>()
+    });
+
+    std::iter::once(header.collect::<Vec<_>>())
+        .chain(rows)
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|field| escape_separated_value(field, separator))
+                .collect::<Vec<_>>()
+                .join(&separator.to_string())
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Quotes fields that contain the separator, quotes or line breaks, and doubles their quotes
+fn escape_separated_value(field: &str, separator: char) -> String {
+    if field.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// A Markdown table with the same rows as the table in the terminal
+pub(crate) fn markdown(time_sheet: &TimeSheet, week: &WeekNumber) -> String {
+    let header = ["Job number", "Job name", "Task short name", "Task name"]
+        .map(String::from)
+        .into_iter()
+        .chain(Day::ALL.into_iter().zip(dates(week)).map(|(day, date)| {
+            let day: String = day.to_string().chars().take(3).collect();
+            format!("{day} {date}")
+        }))
+        .chain(["Total", "Comment"].map(String::from))
+        .collect::<Vec<_>>();
+
+    let totals = [
+        Row::summary("**Total**", &time_sheet.totals.registered),
+        Row::summary("**Expected**", &time_sheet.totals.norm),
+    ];
+    let rows = time_sheet
+        .lines
+        .iter()
+        .map(Row::from)
+        .chain(totals)
+        .map(|row| {
+            [
+                row.job_number,
+                row.job_name,
+                row.task_short_name,
+                row.task_name,
+            ]
+            .map(String::from)
+            .into_iter()
+            .chain(row.days().map(|hours| display_markdown_hours(&hours)))
+            .chain([display_markdown_hours(&row.total), row.comment.to_string()])
+            .collect::<Vec<_>>()
+        });
+
+    let separator = vec!["---".to_string(); header.len()];
+    std::iter::once(header)
+        .chain(std::iter::once(separator))
+        .chain(rows)
+        .map(|cells| {
+            let cells: Vec<_> = cells.iter().map(|cell| escape_markdown(cell)).collect();
+            format!("| {} |\n", cells.join(" | "))
+        })
+        .collect()
+}
+
+fn display_markdown_hours(hours: &Hours) -> String {
+    if hours.is_zero() {
+        return String::new();
+    }
+
+    hours.to_string()
+}
+
+/// Keeps cells from breaking the table
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// YAML with one entry per line, and its hours by date
+pub(crate) fn yaml(time_sheet: &TimeSheet, week: &WeekNumber) -> String {
+    let dates = dates(week);
+    let lines = if time_sheet.lines.is_empty() {
+        " []"
+    } else {
+        ""
+    };
+    let mut yaml = format!(
+        "week: {}\nyear: {}\nlines:{lines}\n",
+        week.number, week.year
+    );
+
+    for row in time_sheet.lines.iter().map(Row::from) {
+        let fields = [
+            ("job_number", row.job_number),
+            ("job_name", row.job_name),
+            ("task_short_name", row.task_short_name),
+            ("task_name", row.task_name),
+            ("comment", row.comment),
+        ];
+        for (i, (key, value)) in fields.into_iter().enumerate() {
+            let indent = if i == 0 { "  - " } else { "    " };
+            writeln!(yaml, "{indent}{key}: {}", quote_yaml(value)).expect("Writing to a string");
+        }
+
+        writeln!(yaml, "    total: {}", row.total.as_decimal()).expect("Writing to a string");
+        yaml.push_str("    hours:\n");
+        for (date, hours) in dates.iter().zip(row.days()) {
+            writeln!(yaml, "      \"{date}\": {}", hours.as_decimal())
+                .expect("Writing to a string");
+        }
+    }
+
+    yaml
+}
+
+/// JSON strings are valid double quoted YAML scalars, with the same escapes
+fn quote_yaml(value: &str) -> String {
+    serde_json::to_string(value).expect("Strings can always be serialized")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::time_sheet::{Line, Totals, Week};
+
+    fn create_time_sheet() -> TimeSheet {
+        let week = Week {
+            monday: Hours::from_minutes(8 * 60),
+            tuesday: Hours::from_minutes(4 * 60 + 30),
+            ..Default::default()
+        };
+        let norm = Week {
+            monday: Hours::from_minutes(8 * 60),
+            tuesday: Hours::from_minutes(8 * 60),
+            ..Default::default()
+        };
+        let totals = Totals {
+            registered: week.clone(),
+            norm,
+            regular: week.clone(),
+        };
+        let lines = vec![
+            Line::new("Acme, Inc.".to_string(), "Development".to_string(), week)
+                .with_job_number("1234".to_string())
+                .with_task_short_name("DEV".to_string())
+                .with_comment("Fixed \"that\" bug | again".to_string()),
+            Line::new(
+                "Globex".to_string(),
+                "Meetings".to_string(),
+                Week::default(),
+            )
+            .with_job_number("5678".to_string())
+            .with_task_short_name("MEET".to_string()),
+        ];
+
+        TimeSheet::new(lines, 43).with_totals(totals)
+    }
+
+    fn week() -> WeekNumber {
+        WeekNumber::new(43, 2024).unwrap()
+    }
+
+    #[test]
+    fn formats_csv() {
+        insta::assert_snapshot!(csv(&create_time_sheet(), &week()));
+    }
+
+    #[test]
+    fn formats_tsv() {
+        let tsv = tsv(&create_time_sheet(), &week());
+
+        let first_line = tsv.lines().nth(1).unwrap();
+        assert_eq!(
+            first_line,
+            "1234\tAcme, Inc.\tDEV\tDevelopment\t8\t4.5\t0\t0\t0\t0\t0\t12.5\t\
+             \"Fixed \"\"that\"\" bug | again\""
+        );
+    }
+
+    #[test]
+    fn formats_markdown() {
+        insta::assert_snapshot!(markdown(&create_time_sheet(), &week()));
+    }
+
+    #[test]
+    fn formats_yaml() {
+        insta::assert_snapshot!(yaml(&create_time_sheet(), &week()));
+    }
+
+    #[test]
+    fn escapes_separated_values() {
+        assert_eq!(escape_separated_value("plain", ','), "plain");
+        assert_eq!(escape_separated_value("a,b", ','), "\"a,b\"");
+        assert_eq!(escape_separated_value("a,b", '\t'), "a,b");
+        assert_eq!(
+            escape_separated_value("say \"hi\"", ','),
+            "\"say \"\"hi\"\"\""
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! The JSON output of time sheets. Unlike the domain models, which change with the needs of the
+//! code, this is a documented format that scripts can rely on. Changes that may break scripts,
+//! like renaming or removing a field, require bumping `SCHEMA_VERSION`
+
+use crate::domain::models::{
+    day::Day,
+    time_sheet::{self, TimeSheet},
+    week::WeekNumber,
+};
+use chrono::NaiveDate;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// The time sheet of a week
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename = "TimeSheet")]
+pub(crate) struct TimeSheetJson {
+    /// Version of this format. Only changes when fields are renamed, removed or change meaning
+    schema_version: u32,
+    /// ISO week-numbering year
+    year: i32,
+    /// ISO week number
+    week: u8,
+    status: Status,
+    lines: Vec<LineJson>,
+    /// Registered and expected hours of each day of the week, from Monday to Sunday
+    days: Vec<DayTotalJson>,
+    /// Registered hours of the week
+    registered: f64,
+    /// Expected hours of the week
+    norm: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Open,
+    Submitted,
+    Approved,
+    Rejected,
+}
+
+/// A job and task, with its hours of the week
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename = "Line")]
+struct LineJson {
+    /// 1-indexed, the same as in `maconomy line delete`
+    line_number: usize,
+    job_number: String,
+    job_name: String,
+    task_short_name: String,
+    task_name: String,
+    comment: String,
+    /// Hours of each day of the week, from Monday to Sunday
+    days: Vec<DayJson>,
+    /// Hours of the week
+    total: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename = "Day")]
+struct DayJson {
+    date: NaiveDate,
+    /// Decimal hours, for example 7.5 for 7:30
+    hours: f64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename = "DayTotal")]
+struct DayTotalJson {
+    date: NaiveDate,
+    /// Decimal hours registered on the day
+    registered: f64,
+    /// Decimal hours expected on the day, according to the norm time
+    norm: f64,
+}
+
+impl TimeSheetJson {
+    pub(crate) fn new(time_sheet: &TimeSheet, week: &WeekNumber) -> Self {
+        let dates = Day::ALL.map(|day| {
+            let date = week
+                .date(day)
+                .expect("Week numbers are validated when they are created");
+            (day, date)
+        });
+
+        let lines = time_sheet
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| LineJson {
+                line_number: i + 1,
+                job_number: line.job_number.clone(),
+                job_name: line.job.clone(),
+                task_short_name: line.task_short_name.clone(),
+                task_name: line.task.clone(),
+                comment: line.comment.clone(),
+                days: dates
+                    .iter()
+                    .map(|&(day, date)| DayJson {
+                        date,
+                        hours: line.week.get(day).as_decimal(),
+                    })
+                    .collect(),
+                total: line.week.total().as_decimal(),
+            })
+            .collect();
+
+        let totals = &time_sheet.totals;
+        let days = dates
+            .iter()
+            .map(|&(day, date)| DayTotalJson {
+                date,
+                registered: totals.registered.get(day).as_decimal(),
+                norm: totals.norm.get(day).as_decimal(),
+            })
+            .collect();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            year: week.year,
+            week: week.number,
+            status: Status::from(time_sheet.status),
+            lines,
+            days,
+            registered: totals.registered.total().as_decimal(),
+            norm: totals.norm.total().as_decimal(),
+        }
+    }
+}
+
+impl From<time_sheet::Status> for Status {
+    fn from(status: time_sheet::Status) -> Self {
+        match status {
+            time_sheet::Status::Open => Status::Open,
+            time_sheet::Status::Submitted => Status::Submitted,
+            time_sheet::Status::Approved => Status::Approved,
+            time_sheet::Status::Rejected => Status::Rejected,
+        }
+    }
+}
+
+/// The JSON Schema of `TimeSheetJson`
+pub(crate) fn schema() -> schemars::Schema {
+    schemars::schema_for!(TimeSheetJson)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{
+        hours::Hours,
+        time_sheet::{Line, Totals, Week},
+    };
+
+    fn hours(hours: u32) -> Hours {
+        Hours::from_minutes(hours * 60)
+    }
+
+    #[test]
+    fn serializes_time_sheet() {
+        let week = Week {
+            monday: hours(8),
+            tuesday: Hours::from_minutes(4 * 60 + 30),
+            ..Default::default()
+        };
+        let norm = Week {
+            monday: hours(8),
+            tuesday: hours(8),
+            ..Default::default()
+        };
+        let totals = Totals {
+            registered: week.clone(),
+            norm,
+            regular: week.clone(),
+        };
+        let line = Line::new("Acme".to_string(), "Development".to_string(), week)
+            .with_job_number("1234".to_string())
+            .with_task_short_name("DEV".to_string());
+        let time_sheet = TimeSheet::new(vec![line], 43)
+            .with_totals(totals)
+            .with_status(time_sheet::Status::Submitted);
+
+        let json = TimeSheetJson::new(&time_sheet, &WeekNumber::new(43, 2024).unwrap());
+
+        insta::assert_json_snapshot!(json);
+    }
+
+    #[test]
+    fn prints_schema() {
+        insta::assert_json_snapshot!(schema());
+    }
+}
@@ -1,4 +1,10 @@
 pub(crate) mod arguments;
 pub(crate) mod commands;
+pub(crate) mod completions;
 pub(crate) mod day_parser;
+pub(crate) mod editor;
+pub(crate) mod export;
+pub(crate) mod formats;
+pub(crate) mod json;
+pub(crate) mod period_parser;
 pub(crate) mod rendering;
@@ -0,0 +1,212 @@
+use super::day_parser::parse_day;
+use crate::domain::models::period::Period;
+use anyhow::{anyhow, bail, Context};
+use chrono::{Datelike, NaiveDate};
+
+/// The most days that a range of dates may span, so that a typo in a year doesn't change hundreds
+/// of weeks
+const MAX_DAYS_IN_RANGE: u64 = 31;
+
+/// Range of week numbers within a year, for example "40-44"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WeekRange {
+    pub(crate) first: u8,
+    pub(crate) last: u8,
+}
+
+/// Parses a month written as "2026-10"
+pub(crate) fn parse_month(input: &str) -> anyhow::Result<Period> {
+    let (year, month) = input
+        .trim()
+        .split_once('-')
+        .ok_or_else(|| anyhow!("Invalid month '{input}', expected for example \"2026-10\""))?;
+
+    let year = year
+        .parse()
+        .with_context(|| format!("Invalid year '{year}'"))?;
+    let month = month
+        .parse()
+        .with_context(|| format!("Invalid month '{month}'"))?;
+
+    Period::month(year, month)
+}
+
+/// Parses a date or a range of dates, for example "2026-10-14", "yesterday", "last fri" or
+/// "2026-10-12..2026-10-16". A range may span at most 31 days
+pub(crate) fn parse_dates(input: &str) -> anyhow::Result<Period> {
+    parse_dates_relative_to(input, chrono::Local::now().date_naive())
+}
+
+fn parse_dates_relative_to(input: &str, today: NaiveDate) -> anyhow::Result<Period> {
+    match input.split_once("..") {
+        Some((start, end)) => {
+            let period = Period::new(parse_date(start, today)?, parse_date(end, today)?)?;
+            let days = period.dates().count() as u64;
+            if days > MAX_DAYS_IN_RANGE {
+                bail!(
+                    "The range '{input}' spans {days} days, more than the maximum of \
+                     {MAX_DAYS_IN_RANGE}"
+                );
+            }
+            Ok(period)
+        }
+        None => {
+            let date = parse_date(input, today)?;
+            Period::new(date, date)
+        }
+    }
+}
+
+fn parse_date(input: &str, today: NaiveDate) -> anyhow::Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+
+    match input.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return today.pred_opt().context("Yesterday is out of range"),
+        _ => (),
+    }
+
+    if let Some(day) = input.strip_prefix("last ") {
+        let day = parse_day(day.trim())?;
+        // The most recent one before today, so "last fri" on a Friday is a week ago
+        let days_back = match today.weekday().days_since(day.into()) {
+            0 => 7,
+            days => days,
+        };
+        return Ok(today - chrono::Days::new(days_back.into()));
+    }
+
+    NaiveDate::parse_from_str(&input, "%Y-%m-%d").with_context(|| {
+        format!("Invalid date '{input}', expected for example \"2026-10-14\" or \"yesterday\"")
+    })
+}
+
+/// Parses a range of weeks written as "40-44", or a single week like "40"
+pub(crate) fn parse_week_range(input: &str) -> anyhow::Result<WeekRange> {
+    let parse_week = |week: &str| {
+        week.trim()
+            .parse::<u8>()
+            .with_context(|| format!("Invalid week '{week}'"))
+    };
+
+    let range = match input.split_once('-') {
+        Some((first, last)) => WeekRange {
+            first: parse_week(first)?,
+            last: parse_week(last)?,
+        },
+        None => {
+            let week = parse_week(input)?;
+            WeekRange {
+                first: week,
+                last: week,
+            }
+        }
+    };
+
+    if range.first > range.last {
+        bail!("Invalid range of weeks '{input}'");
+    }
+
+    Ok(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_month() {
+        let period = parse_month("2026-10").unwrap();
+
+        assert_eq!(period, Period::month(2026, 10).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_months() {
+        for input in ["2026", "2026-13", "october", "2026-1o"] {
+            assert!(
+                parse_month(input).is_err(),
+                "Parsed invalid input '{input}'"
+            );
+        }
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_dates() {
+        // A Wednesday
+        let today = date("2026-10-14");
+        let inputs = [
+            ("2026-10-12", "2026-10-12"),
+            ("today", "2026-10-14"),
+            ("yesterday", "2026-10-13"),
+            ("last fri", "2026-10-09"),
+            ("Last Monday", "2026-10-12"),
+            ("last wed", "2026-10-07"),
+        ];
+
+        for (input, expected) in inputs {
+            let period = parse_dates_relative_to(input, today).unwrap();
+            assert_eq!(period.start, date(expected), "Failed on input '{input}'");
+            assert_eq!(period.end, date(expected), "Failed on input '{input}'");
+        }
+    }
+
+    #[test]
+    fn parses_date_ranges() {
+        let today = date("2026-10-14");
+
+        let period = parse_dates_relative_to("2026-10-09..2026-10-13", today).unwrap();
+        assert_eq!(
+            period,
+            Period::new(date("2026-10-09"), date("2026-10-13")).unwrap()
+        );
+
+        let period = parse_dates_relative_to("last mon..today", today).unwrap();
+        assert_eq!(period, Period::new(date("2026-10-12"), today).unwrap());
+
+        let period = parse_dates_relative_to("2026-10-01..2026-10-31", today).unwrap();
+        assert_eq!(period, Period::month(2026, 10).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        let today = date("2026-10-14");
+
+        for input in [
+            "2026-10-32",
+            "tomorrow",
+            "last",
+            "2026-10-16..2026-10-12",
+            "2026-01-01..2026-12-31",
+            "14/10",
+        ] {
+            assert!(
+                parse_dates_relative_to(input, today).is_err(),
+                "Parsed invalid input '{input}'"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_week_ranges() {
+        let inputs = [("40-44", (40, 44)), ("40 - 44", (40, 44)), ("40", (40, 40))];
+
+        for (input, (first, last)) in inputs {
+            assert_eq!(parse_week_range(input).unwrap(), WeekRange { first, last });
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_week_ranges() {
+        for input in ["44-40", "40-", "-44", "week 40"] {
+            assert!(
+                parse_week_range(input).is_err(),
+                "Parsed invalid input '{input}'"
+            );
+        }
+    }
+}
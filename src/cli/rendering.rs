@@ -1,62 +1,117 @@
-use crate::domain::models::time_sheet::{Line, TimeSheet};
+use crate::domain::models::{
+    day::Day,
+    hours::Hours,
+    job::{Job, JobTask},
+    overview::{DayTotal, Overview},
+    time_sheet::{Line, Status, TimeSheet, Week},
+};
+use chrono::Datelike;
 use owo_colors::OwoColorize;
 use std::fmt::Display;
 use tabled::settings::{
-    object::Rows, style::BorderColor, themes::Colorization, Color, Panel, Style, Theme,
+    format::Format,
+    object::{Cell, Rows},
+    style::BorderColor,
+    themes::Colorization,
+    Color, Panel, Style, Theme,
 };
 
+/// Index of the first day column in `Row`, after the job and task names
+pub(crate) const FIRST_DAY_COLUMN: usize = 2;
+
 #[derive(tabled::Tabled, Default)]
 pub(crate) struct Row<'a> {
+    #[tabled(skip)]
+    pub(crate) job_number: &'a str,
     #[tabled(rename = "Job name")]
     pub(crate) job_name: &'a str,
+    #[tabled(skip)]
+    pub(crate) task_short_name: &'a str,
     #[tabled(rename = "Task name")]
     pub(crate) task_name: &'a str,
     #[tabled(rename = "Mon")]
     #[tabled(display = "display_hours")]
-    pub(crate) monday: f32,
+    pub(crate) monday: Hours,
     #[tabled(rename = "Tue")]
     #[tabled(display = "display_hours")]
-    pub(crate) tuesday: f32,
+    pub(crate) tuesday: Hours,
     #[tabled(rename = "Wed")]
     #[tabled(display = "display_hours")]
-    pub(crate) wednesday: f32,
+    pub(crate) wednesday: Hours,
     #[tabled(rename = "Thu")]
     #[tabled(display = "display_hours")]
-    pub(crate) thursday: f32,
+    pub(crate) thursday: Hours,
     #[tabled(rename = "Fri")]
     #[tabled(display = "display_hours")]
-    pub(crate) friday: f32,
+    pub(crate) friday: Hours,
     #[tabled(rename = "Sat")]
     #[tabled(display = "display_hours")]
-    pub(crate) saturday: f32,
+    pub(crate) saturday: Hours,
     #[tabled(rename = "Sun")]
     #[tabled(display = "display_hours")]
-    pub(crate) sunday: f32,
+    pub(crate) sunday: Hours,
+    #[tabled(rename = "Total")]
+    #[tabled(display = "display_hours")]
+    pub(crate) total: Hours,
+    #[tabled(rename = "Comment")]
+    pub(crate) comment: &'a str,
 }
 
-fn display_hours(hours: &f32) -> String {
-    if let 0.0 = hours {
+fn display_hours(hours: &Hours) -> String {
+    if hours.is_zero() {
         return "".to_string();
     }
 
-    let whole_hours = hours.trunc() as u32;
-    let minutes = ((*hours - whole_hours as f32) * 60.0).floor() as u32;
+    hours.to_string()
+}
+
+impl<'a> Row<'a> {
+    /// Hours of the days of the week, from Monday to Sunday
+    pub(crate) fn days(&self) -> [Hours; 7] {
+        [
+            self.monday,
+            self.tuesday,
+            self.wednesday,
+            self.thursday,
+            self.friday,
+            self.saturday,
+            self.sunday,
+        ]
+    }
 
-    format!("{whole_hours}:{minutes:02}")
+    /// A row with hours that don't belong to a line, such as the totals of the time sheet
+    pub(crate) fn summary(name: &'a str, week: &Week) -> Self {
+        Row {
+            job_name: name,
+            monday: week.monday,
+            tuesday: week.tuesday,
+            wednesday: week.wednesday,
+            thursday: week.thursday,
+            friday: week.friday,
+            saturday: week.saturday,
+            sunday: week.sunday,
+            total: week.total(),
+            ..Default::default()
+        }
+    }
 }
 
 impl<'a> From<&'a Line> for Row<'a> {
     fn from(line: &'a Line) -> Self {
         Row {
+            job_number: &line.job_number,
             job_name: &line.job,
+            task_short_name: &line.task_short_name,
             task_name: &line.task,
-            monday: line.week.monday.0,
-            tuesday: line.week.tuesday.0,
-            wednesday: line.week.wednesday.0,
-            thursday: line.week.thursday.0,
-            friday: line.week.friday.0,
-            saturday: line.week.saturday.0,
-            sunday: line.week.sunday.0,
+            monday: line.week.monday,
+            tuesday: line.week.tuesday,
+            wednesday: line.week.wednesday,
+            thursday: line.week.thursday,
+            friday: line.week.friday,
+            saturday: line.week.saturday,
+            sunday: line.week.sunday,
+            total: line.week.total(),
+            comment: &line.comment,
         }
     }
 }
@@ -77,21 +132,41 @@ fn gray_borders() -> BorderColor {
         .corner_top_right(gray().clone())
 }
 
+fn create_table<T: tabled::Tabled>(rows: impl IntoIterator<Item = T>) -> tabled::Table {
+    let mut theme = Theme::from_style(Style::modern_rounded());
+    theme.remove_vertical_lines();
+
+    let mut table = tabled::Table::new(rows);
+    table.with(theme).with(Colorization::exact(
+        [tabled::settings::Color::BOLD],
+        Rows::first(),
+    ));
+    table
+}
+
 impl Display for TimeSheet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let rows = self.lines.iter().map(Row::from);
+        let totals = [
+            Row::summary("Total", &self.totals.registered),
+            Row::summary("Expected", &self.totals.norm),
+        ];
+        let mut table = create_table(self.lines.iter().map(Row::from).chain(totals));
 
-        let mut theme = Theme::from_style(Style::modern_rounded());
-        theme.remove_vertical_lines();
+        // Highlight the days with fewer hours than expected, including the ones without any hours
+        // at all. The header comes before the lines
+        let total_row = self.lines.len() + 1;
+        for (column, day) in Day::ALL.into_iter().enumerate() {
+            if self.totals.is_below_norm(day) {
+                let cell = Cell::new(total_row, FIRST_DAY_COLUMN + column);
+                let hours = self.totals.registered.get(day).to_string();
+                table
+                    .modify(cell, Format::content(|_| hours.clone()))
+                    .modify(cell, Color::FG_RED);
+            }
+        }
 
-        let mut table = tabled::Table::new(rows);
         let table = table
-            .with(theme)
-            .with(Colorization::exact(
-                [tabled::settings::Color::BOLD],
-                Rows::first(),
-            ))
-            .with(Panel::footer(format!("Week {}", self.week_number)))
+            .with(Panel::footer(footer(self)))
             .with(Colorization::exact([gray()], Rows::last()))
             .with(gray_borders());
 
@@ -99,32 +174,281 @@ impl Display for TimeSheet {
     }
 }
 
+/// The time sheet after a change, with the cells that differ from the time sheet before it
+/// highlighted. Lines are only ever added at the end or removed, so the lines that both have are in
+/// the same order. Removed lines are shown in red
+pub(crate) fn diff_table(before: &TimeSheet, after: &TimeSheet) -> String {
+    let lines = pair_lines(before, after);
+    let rows = lines.iter().map(|&(previous, line)| match line {
+        Some(line) => Row::from(line),
+        None => Row::from(previous.expect("a line is either before or after a change")),
+    });
+    let total = Row::summary("Total", &after.totals.registered);
+    let mut table = create_table(rows.chain([total]));
+    let comment_column = FIRST_DAY_COLUMN + Day::ALL.len() + 1;
+
+    let mut changes = 0;
+    let mut removed = 0;
+    // The header comes before the lines
+    for (row, &(previous, line)) in (1..).zip(&lines) {
+        let Some(line) = line else {
+            table.modify(Rows::new(row..=row), Color::FG_RED);
+            removed += 1;
+            continue;
+        };
+        if previous.is_none() {
+            table.modify(Cell::new(row, 0), Color::FG_GREEN);
+            table.modify(Cell::new(row, 1), Color::FG_GREEN);
+        }
+
+        for (column, day) in (FIRST_DAY_COLUMN..).zip(Day::ALL) {
+            let old = previous.map_or(Hours::ZERO, |line| line.week.get(day));
+            let new = line.week.get(day);
+            if old == new {
+                continue;
+            }
+
+            let content = match old.is_zero() {
+                true => new.to_string(),
+                false => format!("{old} → {new}"),
+            };
+            let cell = Cell::new(row, column);
+            table
+                .modify(cell, Format::content(|_| content.clone()))
+                .modify(cell, Color::FG_GREEN);
+            changes += 1;
+        }
+
+        if previous.is_some_and(|previous| previous.comment != line.comment) {
+            table.modify(Cell::new(row, comment_column), Color::FG_GREEN);
+        }
+    }
+
+    let mut summary = format!("{} · {changes} day(s) changed", footer(after));
+    if removed > 0 {
+        summary.push_str(&format!(" · {removed} line(s) deleted"));
+    }
+    table
+        .with(Panel::footer(summary))
+        .with(Colorization::exact([gray()], Rows::last()))
+        .with(gray_borders())
+        .to_string()
+}
+
+/// The lines before and after a change side by side, where `None` is a line that was added or
+/// removed
+fn pair_lines<'a>(
+    before: &'a TimeSheet,
+    after: &'a TimeSheet,
+) -> Vec<(Option<&'a Line>, Option<&'a Line>)> {
+    let mut pairs = Vec::new();
+    let mut after_lines = after.lines.iter().peekable();
+    for line in &before.lines {
+        let is_kept = after_lines.peek().is_some_and(|next| {
+            next.has_same_job_and_task(line) && next.job_number == line.job_number
+        });
+        if is_kept {
+            pairs.push((Some(line), after_lines.next()));
+        } else {
+            pairs.push((Some(line), None));
+        }
+    }
+    pairs.extend(after_lines.map(|line| (None, Some(line))));
+
+    pairs
+}
+
+fn footer(time_sheet: &TimeSheet) -> String {
+    let week = format!("Week {}", time_sheet.week_number);
+    match time_sheet.status {
+        Status::Open => week,
+        status => format!("{week} · {status}"),
+    }
+}
+
+#[derive(tabled::Tabled)]
+struct JobRow<'a> {
+    #[tabled(rename = "Job number")]
+    number: &'a str,
+    #[tabled(rename = "Job name")]
+    name: &'a str,
+    #[tabled(rename = "Customer")]
+    customer: &'a str,
+}
+
+#[derive(tabled::Tabled)]
+struct TaskRow<'a> {
+    #[tabled(rename = "Job number")]
+    job_number: &'a str,
+    #[tabled(rename = "Job name")]
+    job_name: &'a str,
+    #[tabled(rename = "Customer")]
+    customer: &'a str,
+    #[tabled(rename = "Task name")]
+    description: &'a str,
+    #[tabled(rename = "Short name")]
+    short_name: &'a str,
+}
+
+pub(crate) fn jobs_table(jobs: &[Job]) -> String {
+    let rows = jobs.iter().map(|job| JobRow {
+        number: &job.number,
+        name: &job.name,
+        customer: &job.customer,
+    });
+
+    create_table(rows).with(gray_borders()).to_string()
+}
+
+pub(crate) fn tasks_table(tasks: &[JobTask]) -> String {
+    let rows = tasks.iter().map(|JobTask { job, task }| TaskRow {
+        job_number: &job.number,
+        job_name: &job.name,
+        customer: &job.customer,
+        description: &task.description,
+        short_name: &task.short_name,
+    });
+
+    create_table(rows).with(gray_borders()).to_string()
+}
+
+#[derive(tabled::Tabled)]
+struct CalendarRow {
+    #[tabled(rename = "Week")]
+    week: u32,
+    #[tabled(rename = "Mon")]
+    monday: String,
+    #[tabled(rename = "Tue")]
+    tuesday: String,
+    #[tabled(rename = "Wed")]
+    wednesday: String,
+    #[tabled(rename = "Thu")]
+    thursday: String,
+    #[tabled(rename = "Fri")]
+    friday: String,
+    #[tabled(rename = "Sat")]
+    saturday: String,
+    #[tabled(rename = "Sun")]
+    sunday: String,
+    #[tabled(rename = "Total")]
+    #[tabled(display = "display_hours")]
+    total: Hours,
+}
+
+impl CalendarRow {
+    fn new(week: u32) -> Self {
+        CalendarRow {
+            week,
+            monday: String::new(),
+            tuesday: String::new(),
+            wednesday: String::new(),
+            thursday: String::new(),
+            friday: String::new(),
+            saturday: String::new(),
+            sunday: String::new(),
+            total: Hours::ZERO,
+        }
+    }
+
+    fn add(&mut self, day: &DayTotal) {
+        // Days without enough hours are shown even if they have no hours at all
+        let hours = if day.is_missing_hours() {
+            day.registered.red().to_string()
+        } else {
+            display_hours(&day.registered)
+        };
+
+        let cell = match Day::from(day.date.weekday()) {
+            Day::Monday => &mut self.monday,
+            Day::Tuesday => &mut self.tuesday,
+            Day::Wednesday => &mut self.wednesday,
+            Day::Thursday => &mut self.thursday,
+            Day::Friday => &mut self.friday,
+            Day::Saturday => &mut self.saturday,
+            Day::Sunday => &mut self.sunday,
+        };
+        *cell = hours;
+        self.total = self.total + day.registered;
+    }
+}
+
+#[derive(tabled::Tabled)]
+struct JobTotalRow<'a> {
+    #[tabled(rename = "Job name")]
+    job: &'a str,
+    #[tabled(rename = "Hours")]
+    hours: Hours,
+}
+
+/// A calendar with the hours of each day, followed by the hours of each job
+pub(crate) fn overview_tables(overview: &Overview) -> String {
+    let mut weeks: Vec<CalendarRow> = Vec::new();
+    for day in &overview.days {
+        let week = day.date.iso_week().week();
+        match weeks.last_mut() {
+            Some(row) if row.week == week => row.add(day),
+            _ => {
+                let mut row = CalendarRow::new(week);
+                row.add(day);
+                weeks.push(row);
+            }
+        }
+    }
+
+    let mut summary = format!(
+        "{} – {} · {} of {} expected",
+        overview.start, overview.end, overview.registered, overview.norm
+    );
+    let missing_days = overview.missing_days().count();
+    if missing_days > 0 {
+        summary.push_str(&format!(" · {missing_days} day(s) missing hours"));
+    }
+
+    let calendar = create_table(weeks)
+        .with(Panel::footer(summary))
+        .with(Colorization::exact([gray()], Rows::last()))
+        .with(gray_borders())
+        .to_string();
+
+    let rows = overview.jobs.iter().map(|job| JobTotalRow {
+        job: &job.job,
+        hours: job.hours,
+    });
+    let jobs = create_table(rows).with(gray_borders()).to_string();
+
+    format!("{calendar}\n{jobs}")
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::domain::models::{hours::Hours, time_sheet::Week};
+    use crate::domain::models::overview::JobTotal;
+    use crate::domain::models::time_sheet::Totals;
 
     #[test]
     fn displays_hours() {
-        let hours = [1.5, 0.25, 12.75, 0.0, 23.999, 10.1];
-        let expected = ["1:30", "0:15", "12:45", "", "23:59", "10:06"];
+        let hours = [1.5, 0.25, 12.75, 0.0, 23.999, 10.1, 7.8];
+        let expected = ["1:30", "0:15", "12:45", "", "24:00", "10:06", "7:48"];
 
         for (i, &hours) in hours.iter().enumerate() {
-            let result = display_hours(&hours).to_string();
+            let result = display_hours(&Hours::from(hours)).to_string();
             assert_eq!(result, expected[i], "Failed at index {}", i);
         }
     }
 
     fn create_week(days: [u8; 7]) -> Week {
-        let days: Vec<f32> = days.into_iter().map(Into::into).collect();
+        let days: Vec<Hours> = days
+            .into_iter()
+            .map(|hours| Hours::from_minutes(u32::from(hours) * 60))
+            .collect();
         Week {
-            monday: Hours(days[0]),
-            tuesday: Hours(days[1]),
-            wednesday: Hours(days[2]),
-            thursday: Hours(days[3]),
-            friday: Hours(days[4]),
-            saturday: Hours(days[5]),
-            sunday: Hours(days[6]),
+            monday: days[0],
+            tuesday: days[1],
+            wednesday: days[2],
+            thursday: days[3],
+            friday: days[4],
+            saturday: days[5],
+            sunday: days[6],
         }
     }
 
@@ -135,20 +459,37 @@ mod test {
                 Line {
                     job: "Job number one".to_string(),
                     task: "Task number one".to_string(),
+                    job_number: String::new(),
+                    task_short_name: String::new(),
                     week: create_week([8, 8, 0, 0, 0, 0, 0]),
+                    comment: "Some comment".to_string(),
                 },
                 Line {
                     job: "job number two".to_string(),
                     task: "task number two".to_string(),
+                    job_number: String::new(),
+                    task_short_name: String::new(),
                     week: create_week([0, 0, 8, 8, 1, 1, 0]),
+                    comment: String::new(),
                 },
                 Line {
                     job: "job number three".to_string(),
                     task: "task number three".to_string(),
-                    week: create_week([0, 0, 0, 0, 7, 7, 8]),
+                    job_number: String::new(),
+                    task_short_name: String::new(),
+                    week: create_week([0, 0, 0, 0, 6, 7, 8]),
+                    comment: String::new(),
                 },
             ],
             week_number: 47,
+            totals: Totals {
+                registered: create_week([8, 8, 8, 8, 7, 8, 8]),
+                norm: create_week([8, 8, 8, 8, 8, 0, 0]),
+                regular: create_week([8, 8, 8, 8, 8, 0, 0]),
+            },
+            status: Status::Open,
+            employee: String::new(),
+            period: None,
         })
         .to_string();
 
@@ -164,22 +505,102 @@ mod test {
                 Line {
                     job: "Job number one".to_string(),
                     task: "Task number one".to_string(),
+                    job_number: String::new(),
+                    task_short_name: String::new(),
                     week: create_week([8, 8, 0, 0, 0, 0, 0]),
+                    comment: "Some comment".to_string(),
                 },
                 Line {
                     job: "job number two".to_string(),
                     task: "task number two".to_string(),
+                    job_number: String::new(),
+                    task_short_name: String::new(),
                     week: create_week([0, 0, 8, 8, 1, 1, 0]),
+                    comment: String::new(),
                 },
                 Line {
                     job: "job number three".to_string(),
                     task: "task number three".to_string(),
-                    week: create_week([0, 0, 0, 0, 7, 7, 8]),
+                    job_number: String::new(),
+                    task_short_name: String::new(),
+                    week: create_week([0, 0, 0, 0, 6, 7, 8]),
+                    comment: String::new(),
                 },
             ],
             week_number: 47,
+            totals: Totals {
+                registered: create_week([8, 8, 8, 8, 7, 8, 8]),
+                norm: create_week([8, 8, 8, 8, 8, 0, 0]),
+                regular: create_week([8, 8, 8, 8, 8, 0, 0]),
+            },
+            status: Status::Open,
+            employee: String::new(),
+            period: None,
         })
         .to_string();
         insta::assert_snapshot!(time_sheet.to_string());
     }
+
+    #[test]
+    fn display_ansi_stripped_overview() {
+        let day = |date: &str, registered: u8, norm: u8| DayTotal {
+            date: date.parse().unwrap(),
+            registered: Hours::from_minutes(u32::from(registered) * 60),
+            norm: Hours::from_minutes(u32::from(norm) * 60),
+        };
+        let overview = Overview {
+            start: "2026-10-01".parse().unwrap(),
+            end: "2026-10-06".parse().unwrap(),
+            days: vec![
+                day("2026-10-01", 8, 8),
+                day("2026-10-02", 4, 8),
+                day("2026-10-03", 0, 0),
+                day("2026-10-04", 0, 0),
+                day("2026-10-05", 0, 8),
+                day("2026-10-06", 9, 8),
+            ],
+            jobs: vec![JobTotal {
+                job: "Job number one".to_string(),
+                hours: Hours::from_minutes(21 * 60),
+            }],
+            registered: Hours::from_minutes(21 * 60),
+            norm: Hours::from_minutes(32 * 60),
+        };
+
+        let overview = overview_tables(&overview);
+        let ansi_stripped_overview = anstream::adapter::strip_str(&overview);
+        insta::assert_snapshot!(ansi_stripped_overview.to_string());
+    }
+
+    #[test]
+    fn diff_shows_removed_and_added_lines() {
+        let line =
+            |job: &str, days| Line::new(job.to_string(), "Task".to_string(), create_week(days));
+        let before = TimeSheet::new(
+            vec![
+                line("Job one", [8, 0, 0, 0, 0, 0, 0]),
+                line("Job two", [0, 4, 0, 0, 0, 0, 0]),
+            ],
+            47,
+        );
+        let mut after = before.clone();
+        after.remove_line(0);
+        after.lines[0]
+            .week
+            .set(Day::Tuesday, Hours::from_minutes(6 * 60));
+        after.lines.push(line("Job three", [0, 0, 2, 0, 0, 0, 0]));
+
+        let diff = diff_table(&before, &after);
+        let ansi_stripped_diff = anstream::adapter::strip_str(&diff);
+        insta::assert_snapshot!(ansi_stripped_diff.to_string());
+    }
+
+    #[test]
+    fn shows_status_in_footer() {
+        let time_sheet = TimeSheet::new(Vec::new(), 47);
+        assert_eq!(footer(&time_sheet), "Week 47");
+
+        let time_sheet = time_sheet.with_status(Status::Submitted);
+        assert_eq!(footer(&time_sheet), "Week 47 · Submitted");
+    }
 }
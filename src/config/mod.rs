@@ -9,6 +9,11 @@ pub struct Configuration(Config);
 
 impl Configuration {
     pub fn new(config_str: Option<String>) -> Self {
+        Self::try_new(config_str).expect("Failed to read configuration")
+    }
+
+    /// Like `new`, but returns an error if the configuration can't be read
+    pub fn try_new(config_str: Option<String>) -> Result<Self> {
         let builder = Config::builder()
             // Config file `~/.config/maconomy-cli/config.toml`
             .add_source(config::File::with_name(DEFAULT_PATH).required(false))
@@ -30,7 +35,8 @@ impl Configuration {
             None => builder,
         };
 
-        Self(builder.build().expect("Failed to read configuration"))
+        let config = builder.build().context("Failed to read configuration")?;
+        Ok(Self(config))
     }
 
     pub fn get_value<'a, T: Deserialize<'a>>(&self, value_name: &str) -> Result<T> {
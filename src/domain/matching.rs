@@ -0,0 +1,176 @@
+//! Matching of user input against job, task and line names. A name matches if it equals, starts
+//! with, or contains the query (case-insensitively), and better kinds of matches win.
+
+use std::fmt::Display;
+
+/// How well a name matches a query. Variants are ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum MatchKind {
+    Exact,
+    Prefix,
+    Substring,
+}
+
+impl MatchKind {
+    pub(crate) fn of(query: &str, name: &str) -> Option<MatchKind> {
+        let (query, name) = (query.trim().to_lowercase(), name.trim().to_lowercase());
+
+        if query.is_empty() {
+            None
+        } else if name == query {
+            Some(MatchKind::Exact)
+        } else if name.starts_with(&query) {
+            Some(MatchKind::Prefix)
+        } else if name.contains(&query) {
+            Some(MatchKind::Substring)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Matches<T> {
+    None,
+    Unique(T),
+    /// All matching candidates, ranked from best to worst match
+    Ambiguous(Vec<T>),
+}
+
+impl<T> Matches<T> {
+    /// Turns ambiguous matches into an error that lists the candidates using `describe`
+    pub(crate) fn into_result(
+        self,
+        kind: &'static str,
+        query: &str,
+        describe: impl Fn(&T) -> String,
+    ) -> Result<Option<T>, AmbiguousMatch> {
+        match self {
+            Matches::None => Ok(None),
+            Matches::Unique(candidate) => Ok(Some(candidate)),
+            Matches::Ambiguous(candidates) => Err(AmbiguousMatch {
+                kind,
+                query: query.to_string(),
+                candidates: candidates.iter().map(describe).collect(),
+            }),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(crate) struct AmbiguousMatch {
+    kind: &'static str,
+    query: String,
+    candidates: Vec<String>,
+}
+
+impl Display for AmbiguousMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, query) = (self.kind, &self.query);
+        write!(
+            f,
+            "{kind} '{query}' is ambiguous. Did you mean one of these?"
+        )?;
+
+        for (i, candidate) in self.candidates.iter().enumerate() {
+            write!(f, "\n  {}. {candidate}", i + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Finds the candidate with the best match, as ranked by `rank`. If several candidates share the
+/// best match kind, the match is ambiguous.
+pub(crate) fn find_best_match<T>(
+    candidates: impl IntoIterator<Item = T>,
+    rank: impl Fn(&T) -> Option<MatchKind>,
+) -> Matches<T> {
+    let mut ranked: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|candidate| rank(&candidate).map(|kind| (kind, candidate)))
+        .collect();
+
+    // Stable sort, so that candidates with equal rank keep their original order
+    ranked.sort_by_key(|(kind, _)| *kind);
+
+    let is_unique = match ranked.as_slice() {
+        [] => return Matches::None,
+        [_] => true,
+        [(best, _), (second_best, _), ..] => best < second_best,
+    };
+
+    let mut candidates = ranked.into_iter().map(|(_, candidate)| candidate);
+    if is_unique {
+        Matches::Unique(candidates.next().expect("There is at least one candidate"))
+    } else {
+        Matches::Ambiguous(candidates.collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(query: &str, names: &[&'a str]) -> Matches<&'a str> {
+        find_best_match(names.iter().copied(), |name| MatchKind::of(query, name))
+    }
+
+    #[test]
+    fn gets_match_kind() {
+        assert_eq!(MatchKind::of("acme", "ACME"), Some(MatchKind::Exact));
+        assert_eq!(MatchKind::of("acme", "Acme Corp"), Some(MatchKind::Prefix));
+        assert_eq!(
+            MatchKind::of("corp", "Acme Corp"),
+            Some(MatchKind::Substring)
+        );
+        assert_eq!(MatchKind::of("foo", "Acme Corp"), None);
+        assert_eq!(MatchKind::of("", "Acme Corp"), None);
+    }
+
+    #[test]
+    fn finds_unique_prefix() {
+        let names = ["Acme Corp", "Globex", "Initech"];
+
+        assert_eq!(find("acme", &names), Matches::Unique("Acme Corp"));
+        assert_eq!(find("tech", &names), Matches::Unique("Initech"));
+        assert_eq!(find("umbrella", &names), Matches::None);
+    }
+
+    #[test]
+    fn prefers_better_matches() {
+        let names = ["Development support", "Dev", "Web development"];
+
+        assert_eq!(find("dev", &names), Matches::Unique("Dev"));
+        assert_eq!(
+            find("devel", &names),
+            Matches::Unique("Development support")
+        );
+    }
+
+    #[test]
+    fn ranks_ambiguous_candidates() {
+        let names = ["Web development", "Development", "Development support"];
+
+        assert_eq!(
+            find("develop", &names),
+            Matches::Ambiguous(vec![
+                "Development",
+                "Development support",
+                "Web development"
+            ])
+        );
+    }
+
+    #[test]
+    fn describes_ambiguous_match() {
+        let names = ["Acme Corp", "Acme Labs"];
+        let err = find("acme", &names)
+            .into_result("Job", "acme", ToString::to_string)
+            .unwrap_err();
+
+        let expected = "Job 'acme' is ambiguous. Did you mean one of these?\n  1. Acme Corp\n  2. \
+                        Acme Labs";
+        assert_eq!(err.to_string(), expected);
+    }
+}
@@ -1,8 +1,19 @@
 pub(crate) mod models {
+    pub(crate) mod check;
     pub(crate) mod day;
     pub(crate) mod hours;
+    pub(crate) mod import;
+    pub(crate) mod import_rule;
+    pub(crate) mod job;
+    pub(crate) mod journal;
     pub(crate) mod line_number;
+    pub(crate) mod overview;
+    pub(crate) mod period;
+    pub(crate) mod report;
+    pub(crate) mod template;
     pub(crate) mod time_sheet;
+    pub(crate) mod timer;
     pub(crate) mod week;
 }
+pub(crate) mod matching;
 pub(crate) mod time_sheet_service;
@@ -0,0 +1,243 @@
+use super::{
+    day::Day,
+    hours::{self, Hours},
+    time_sheet::TimeSheet,
+};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::fmt::Display;
+
+/// Rules that a time sheet has to follow to be submitted, configured in `[checks]`. Every check is
+/// off unless it's configured
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct Checks {
+    /// Every day has at least the expected hours
+    #[serde(default)]
+    reach_norm: bool,
+    /// No day has more hours than this, for example "10:00"
+    #[serde(default, deserialize_with = "hours::deserialize_from_str")]
+    max_hours_per_day: Option<Hours>,
+    /// Regexes for jobs whose lines need a comment if they have hours
+    #[serde(default, deserialize_with = "deserialize_regexes")]
+    comment_required: Vec<Regex>,
+    /// No hours on Saturday and Sunday, except for the jobs in `weekend_jobs`
+    #[serde(default)]
+    no_weekends: bool,
+    /// Regexes for jobs that may have hours on Saturday and Sunday
+    #[serde(default, deserialize_with = "deserialize_regexes")]
+    weekend_jobs: Vec<Regex>,
+    /// Every line has hours
+    #[serde(default)]
+    no_empty_lines: bool,
+}
+
+impl Checks {
+    pub(crate) fn is_empty(&self) -> bool {
+        !self.reach_norm
+            && self.max_hours_per_day.is_none()
+            && self.comment_required.is_empty()
+            && !self.no_weekends
+            && !self.no_empty_lines
+    }
+
+    /// Every violation of the checks, grouped by check
+    pub(crate) fn evaluate(&self, time_sheet: &TimeSheet) -> Vec<Violation> {
+        let totals = &time_sheet.totals;
+        let mut violations = Vec::new();
+
+        if self.reach_norm {
+            for day in Day::ALL
+                .into_iter()
+                .filter(|&day| totals.is_below_norm(day))
+            {
+                let message = format!(
+                    "{day} has {}, less than the expected {}",
+                    totals.registered.get(day),
+                    totals.norm.get(day)
+                );
+                violations.push(Violation::new("reach_norm", message));
+            }
+        }
+
+        if let Some(max) = self.max_hours_per_day {
+            for day in totals.days_above(max) {
+                let message = format!(
+                    "{day} has {}, more than the maximum of {max}",
+                    totals.registered.get(day)
+                );
+                violations.push(Violation::new("max_hours_per_day", message));
+            }
+        }
+
+        for (i, line) in time_sheet.lines.iter().enumerate() {
+            let has_hours = !line.week.total().is_zero();
+            let needs_comment = self
+                .comment_required
+                .iter()
+                .any(|pattern| pattern.is_match(&line.job));
+            if has_hours && needs_comment && line.comment.trim().is_empty() {
+                let message = format!(
+                    "Line {} '{}', '{}' has no comment",
+                    i + 1,
+                    line.job,
+                    line.task
+                );
+                violations.push(Violation::new("comment_required", message));
+            }
+        }
+
+        if self.no_weekends {
+            for (i, line) in time_sheet.lines.iter().enumerate() {
+                let is_allowed = self
+                    .weekend_jobs
+                    .iter()
+                    .any(|pattern| pattern.is_match(&line.job));
+                let weekend = [Day::Saturday, Day::Sunday]
+                    .into_iter()
+                    .filter(|&day| !line.week.get(day).is_zero());
+                for day in weekend.filter(|_| !is_allowed) {
+                    let message = format!(
+                        "Line {} '{}', '{}' has {} on {day}",
+                        i + 1,
+                        line.job,
+                        line.task,
+                        line.week.get(day)
+                    );
+                    violations.push(Violation::new("no_weekends", message));
+                }
+            }
+        }
+
+        if self.no_empty_lines {
+            for (i, line) in time_sheet.lines.iter().enumerate() {
+                if line.week.total().is_zero() {
+                    let message = format!(
+                        "Line {} '{}', '{}' has no hours",
+                        i + 1,
+                        line.job,
+                        line.task
+                    );
+                    violations.push(Violation::new("no_empty_lines", message));
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+/// A way that a time sheet fails a check
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Violation {
+    /// The name of the check in the configuration
+    pub(crate) check: &'static str,
+    pub(crate) message: String,
+}
+
+impl Violation {
+    fn new(check: &'static str, message: String) -> Self {
+        Self { check, message }
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.check)
+    }
+}
+
+fn deserialize_regexes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Regex>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::time_sheet::{Line, Totals, Week};
+
+    fn checks(json: &str) -> Checks {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn line(job: &str, monday: u32, saturday: u32) -> Line {
+        let week = Week {
+            monday: Hours::from_minutes(monday),
+            saturday: Hours::from_minutes(saturday),
+            ..Default::default()
+        };
+        Line::new(job.to_string(), "Development".to_string(), week)
+    }
+
+    /// A time sheet with `registered` minutes on Monday, where 8 hours are expected
+    fn time_sheet(lines: Vec<Line>, registered: u32) -> TimeSheet {
+        let monday = |minutes| Week {
+            monday: Hours::from_minutes(minutes),
+            ..Default::default()
+        };
+        let totals = Totals {
+            registered: monday(registered),
+            norm: monday(480),
+            regular: Week::default(),
+        };
+        TimeSheet::new(lines, 43).with_totals(totals)
+    }
+
+    fn messages(violations: Vec<Violation>) -> Vec<String> {
+        violations
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn checks_nothing_by_default() {
+        let checks = checks("{}");
+        let time_sheet = time_sheet(vec![line("Acme", 0, 120)], 0);
+
+        assert!(checks.is_empty());
+        assert_eq!(checks.evaluate(&time_sheet), []);
+    }
+
+    #[test]
+    fn reports_violations_of_every_check() {
+        let checks = checks(
+            r#"{
+                "reach_norm": true,
+                "max_hours_per_day": "6:00",
+                "comment_required": ["^Acme$"],
+                "no_weekends": true,
+                "weekend_jobs": ["On-call"],
+                "no_empty_lines": true
+            }"#,
+        );
+        let lines = vec![
+            line("Acme", 420, 60),
+            line("On-call", 0, 60),
+            line("Globex", 0, 0),
+        ];
+        let time_sheet = time_sheet(lines, 420);
+
+        let violations = checks.evaluate(&time_sheet);
+
+        assert_eq!(
+            messages(violations),
+            [
+                "Monday has 7:00, less than the expected 8:00 (reach_norm)",
+                "Monday has 7:00, more than the maximum of 6:00 (max_hours_per_day)",
+                "Line 1 'Acme', 'Development' has no comment (comment_required)",
+                "Line 1 'Acme', 'Development' has 1:00 on Saturday (no_weekends)",
+                "Line 3 'Globex', 'Development' has no hours (no_empty_lines)",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_maximum() {
+        let err = serde_json::from_str::<Checks>(r#"{ "max_hours_per_day": "lots" }"#).unwrap_err();
+
+        assert!(err.to_string().starts_with("Invalid hours 'lots'"), "{err}");
+    }
+}
@@ -13,6 +13,19 @@ pub(crate) enum Day {
 
 pub(crate) type Days = HashSet<Day>;
 
+impl Day {
+    /// All days of the week, from Monday to Sunday
+    pub(crate) const ALL: [Day; 7] = [
+        Day::Monday,
+        Day::Tuesday,
+        Day::Wednesday,
+        Day::Thursday,
+        Day::Friday,
+        Day::Saturday,
+        Day::Sunday,
+    ];
+}
+
 impl FromStr for Day {
     type Err = anyhow::Error;
 
@@ -33,16 +46,7 @@ impl FromStr for Day {
 
 impl From<u8> for Day {
     fn from(day: u8) -> Self {
-        let week = [
-            Day::Monday,
-            Day::Tuesday,
-            Day::Wednesday,
-            Day::Thursday,
-            Day::Friday,
-            Day::Saturday,
-            Day::Sunday,
-        ];
-        *week.get(day as usize - 1).expect("Invalid day")
+        *Day::ALL.get(day as usize - 1).expect("Invalid day")
     }
 }
 
@@ -60,6 +64,20 @@ impl From<chrono::Weekday> for Day {
     }
 }
 
+impl From<Day> for chrono::Weekday {
+    fn from(day: Day) -> Self {
+        match day {
+            Day::Monday => chrono::Weekday::Mon,
+            Day::Tuesday => chrono::Weekday::Tue,
+            Day::Wednesday => chrono::Weekday::Wed,
+            Day::Thursday => chrono::Weekday::Thu,
+            Day::Friday => chrono::Weekday::Fri,
+            Day::Saturday => chrono::Weekday::Sat,
+            Day::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
 impl Display for Day {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let day = match self {
@@ -1,8 +1,453 @@
-#[derive(Debug, serde::Serialize)]
-pub(crate) struct Hours(pub(crate) f32);
+use anyhow::{anyhow, bail, Context};
+use std::{fmt::Display, str::FromStr};
 
+const MINUTES_PER_HOUR: u32 = 60;
+
+/// Maximum number of hours that can be registered on a single day
+const MAX_MINUTES: u32 = 24 * MINUTES_PER_HOUR;
+
+/// Maconomy stores hours as decimal numbers with two decimals
+const MACONOMY_DECIMALS: usize = 2;
+
+/// An exact number of hours, stored as whole minutes to avoid floating point drift
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Hours {
+    minutes: u32,
+}
+
+impl Hours {
+    pub(crate) const ZERO: Hours = Hours { minutes: 0 };
+
+    pub(crate) const fn from_minutes(minutes: u32) -> Self {
+        Self { minutes }
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.minutes == 0
+    }
+
+    pub(crate) fn checked_add(self, other: Hours) -> Option<Hours> {
+        let minutes = self.minutes.checked_add(other.minutes)?;
+        (minutes <= MAX_MINUTES).then_some(Self::from_minutes(minutes))
+    }
+
+    pub(crate) fn checked_sub(self, other: Hours) -> Option<Hours> {
+        self.minutes
+            .checked_sub(other.minutes)
+            .map(Self::from_minutes)
+    }
+
+    /// Rounds to the nearest multiple of `granularity`, with halves rounded up. A zero
+    /// granularity leaves the hours as they are
+    pub(crate) fn round_to(self, granularity: Hours) -> Hours {
+        if granularity.is_zero() {
+            return self;
+        }
+        let step = granularity.minutes;
+        let minutes = (self.minutes + step / 2) / step * step;
+
+        Self::from_minutes(minutes)
+    }
+
+    /// Decimal hours, rounded to the precision that Maconomy stores
+    pub(crate) fn as_decimal(&self) -> f64 {
+        let factor = 10_f64.powi(MACONOMY_DECIMALS as i32);
+        let hours = f64::from(self.minutes) / f64::from(MINUTES_PER_HOUR);
+
+        (hours * factor).round() / factor
+    }
+}
+
+/// Sums aren't bounded by the maximum hours of a single day, so that they can be used for totals
+impl std::ops::Add for Hours {
+    type Output = Hours;
+
+    fn add(self, other: Hours) -> Hours {
+        Self::from_minutes(self.minutes + other.minutes)
+    }
+}
+
+impl std::iter::Sum for Hours {
+    fn sum<I: Iterator<Item = Hours>>(iter: I) -> Hours {
+        iter.fold(Hours::ZERO, |sum, hours| sum + hours)
+    }
+}
+
+/// Converts decimal hours from Maconomy, rounded to the nearest minute
 impl From<f32> for Hours {
     fn from(hours: f32) -> Self {
-        Self(hours)
+        let minutes = (f64::from(hours) * f64::from(MINUTES_PER_HOUR)).round();
+        Self::from_minutes(minutes.max(0.0) as u32)
+    }
+}
+
+impl FromStr for Hours {
+    type Err = anyhow::Error;
+
+    /// Parses hours written as "4:30", "4h30m", "4h", "90m", "4.5" or "4"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_lowercase();
+        if input.is_empty() {
+            bail!("No hours provided");
+        }
+
+        let minutes = if let Some((hours, minutes)) = input.split_once(':') {
+            parse_hours_and_minutes(hours, minutes)?
+        } else if let Some((hours, minutes)) = input.split_once('h') {
+            let minutes = minutes.strip_suffix('m').unwrap_or(minutes);
+            parse_hours_and_minutes(hours, minutes)?
+        } else if let Some(minutes) = input.strip_suffix('m') {
+            parse_whole_number(minutes)?
+        } else {
+            parse_decimal_hours(&input)?
+        };
+
+        if minutes > MAX_MINUTES {
+            bail!("Can't register more than 24 hours on a single day");
+        }
+
+        Ok(Self::from_minutes(minutes))
+    }
+}
+
+/// A change to the hours of a day, either absolute or relative to the current hours
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HoursChange {
+    Set(Hours),
+    Add(Hours),
+    Subtract(Hours),
+}
+
+impl HoursChange {
+    /// Returns the hours that result from applying the change to `current`
+    pub(crate) fn apply(&self, current: Hours) -> anyhow::Result<Hours> {
+        match *self {
+            HoursChange::Set(hours) => Ok(hours),
+            HoursChange::Add(hours) => current.checked_add(hours).ok_or_else(|| {
+                anyhow!("Adding {hours} to {current} would exceed 24 hours on a single day")
+            }),
+            HoursChange::Subtract(hours) => current.checked_sub(hours).ok_or_else(|| {
+                anyhow!("Subtracting {hours} from {current} would result in negative hours")
+            }),
+        }
+    }
+}
+
+impl FromStr for HoursChange {
+    type Err = anyhow::Error;
+
+    /// Parses hours, optionally prefixed with `+` or `-` to make the change relative
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim();
+
+        let change = if let Some(hours) = input.strip_prefix('+') {
+            HoursChange::Add(hours.parse()?)
+        } else if let Some(hours) = input.strip_prefix('-') {
+            HoursChange::Subtract(hours.parse()?)
+        } else {
+            HoursChange::Set(input.parse()?)
+        };
+
+        Ok(change)
+    }
+}
+
+impl Display for Hours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hours = self.minutes / MINUTES_PER_HOUR;
+        let minutes = self.minutes % MINUTES_PER_HOUR;
+
+        write!(f, "{hours}:{minutes:02}")
+    }
+}
+
+impl serde::Serialize for Hours {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_decimal())
+    }
+}
+
+/// Reads decimal hours, as they are serialized
+impl<'de> serde::Deserialize<'de> for Hours {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hours = f64::deserialize(deserializer)?;
+        let minutes = (hours * f64::from(MINUTES_PER_HOUR)).round();
+
+        Ok(Self::from_minutes(minutes.max(0.0) as u32))
+    }
+}
+
+/// Reads hours written like the hours arguments, for example "0:15", as they are in the
+/// configuration. Also reads optional hours, for fields that have `#[serde(default)]` too
+pub(crate) fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: From<Hours>,
+{
+    let input = <String as serde::Deserialize>::deserialize(deserializer)?;
+    input
+        .parse::<Hours>()
+        .map(T::from)
+        .map_err(|_| serde::de::Error::custom(format!("Invalid hours '{input}'")))
+}
+
+fn parse_whole_number(input: &str) -> anyhow::Result<u32> {
+    if input.is_empty() || !input.chars().all(|c| c.is_ascii_digit()) {
+        bail!("Invalid number '{input}'");
+    }
+
+    input
+        .parse()
+        .with_context(|| format!("Invalid number '{input}'"))
+}
+
+fn parse_hours_and_minutes(hours: &str, minutes: &str) -> anyhow::Result<u32> {
+    if hours.is_empty() && minutes.is_empty() {
+        bail!("No hours or minutes provided");
+    }
+
+    let hours = if hours.is_empty() {
+        0
+    } else {
+        parse_whole_number(hours)?
+    };
+    let minutes = if minutes.is_empty() {
+        0
+    } else {
+        parse_whole_number(minutes)?
+    };
+
+    if minutes >= MINUTES_PER_HOUR {
+        bail!("Minutes must be less than 60, got {minutes}");
+    }
+
+    hours
+        .checked_mul(MINUTES_PER_HOUR)
+        .and_then(|hours| hours.checked_add(minutes))
+        .ok_or_else(|| anyhow!("Too many hours"))
+}
+
+fn parse_decimal_hours(input: &str) -> anyhow::Result<u32> {
+    let input = input.replace(',', ".");
+    let (whole, fraction) = input.split_once('.').unwrap_or((&input, ""));
+
+    if fraction.len() > MACONOMY_DECIMALS {
+        bail!("Maconomy only accepts hours with up to {MACONOMY_DECIMALS} decimals, got '{input}'");
+    }
+
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        parse_whole_number(whole)?
+    };
+    let hundredths = if fraction.is_empty() {
+        0
+    } else {
+        // Pad so that for example ".5" becomes 50 hundredths
+        let padded = format!("{fraction:0<width$}", width = MACONOMY_DECIMALS);
+        parse_whole_number(&padded)?
+    };
+
+    // Only decimals that Maconomy shows for a whole number of minutes are accepted, such as
+    // "0.33" for 20 minutes, so that the hours that are saved are the hours that were given
+    let fraction_minutes = (hundredths * MINUTES_PER_HOUR + 50) / 100;
+    let minutes = whole
+        .checked_mul(MINUTES_PER_HOUR)
+        .and_then(|hours| hours.checked_add(fraction_minutes))
+        .ok_or_else(|| anyhow!("Too many hours"))?;
+
+    if to_hundredths(fraction_minutes) != hundredths {
+        let nearest = Hours::from_minutes(minutes);
+        bail!(
+            "'{input}' isn't a whole number of minutes, the nearest is {nearest} ({:.2})",
+            nearest.as_decimal()
+        );
+    }
+
+    Ok(minutes)
+}
+
+/// The hundredths of an hour that Maconomy shows for minutes within an hour
+fn to_hundredths(minutes: u32) -> u32 {
+    (minutes * 100 + MINUTES_PER_HOUR / 2) / MINUTES_PER_HOUR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hours() {
+        let inputs = [
+            ("8", 480),
+            ("4:30", 270),
+            ("0:05", 5),
+            (":45", 45),
+            ("4h30m", 270),
+            ("4h30", 270),
+            ("4h", 240),
+            ("90m", 90),
+            ("4.5", 270),
+            ("4,5", 270),
+            (".25", 15),
+            ("7.8", 468),
+            ("0.33", 20),
+            ("24", 1440),
+        ];
+
+        for (input, expected_minutes) in inputs {
+            let hours: Hours = input.parse().unwrap();
+            assert_eq!(hours.minutes, expected_minutes, "Failed on input '{input}'");
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_hours() {
+        let inputs = [
+            "", "abc", "4:60", "4h75m", "-1", "4.555", "25", "1:2:3", "4.5h", "h", "4 30", "0.01",
+            "7.01",
+        ];
+
+        for input in inputs {
+            assert!(
+                input.parse::<Hours>().is_err(),
+                "Parsed invalid input '{input}'"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_decimals_between_minutes() {
+        let err = "7.01".parse::<Hours>().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "'7.01' isn't a whole number of minutes, the nearest is 7:01 (7.02)"
+        );
+    }
+
+    #[test]
+    fn parses_every_minute_as_maconomy_decimals() {
+        for minutes in 0..=MAX_MINUTES {
+            let decimal = format!("{:.2}", Hours::from_minutes(minutes).as_decimal());
+            assert_eq!(
+                decimal.parse::<Hours>().unwrap().minutes,
+                minutes,
+                "{decimal}"
+            );
+        }
+    }
+
+    #[test]
+    fn converts_from_maconomy_decimals() {
+        let decimals = [(7.8, 468), (0.33, 20), (23.999, 1440), (-1.0, 0)];
+
+        for (decimal, expected_minutes) in decimals {
+            assert_eq!(Hours::from(decimal).minutes, expected_minutes);
+        }
+    }
+
+    #[test]
+    fn round_trips_every_minute_through_maconomy_decimals() {
+        for minutes in 0..=MAX_MINUTES {
+            let decimal = Hours::from_minutes(minutes).as_decimal() as f32;
+            assert_eq!(Hours::from(decimal).minutes, minutes);
+        }
+    }
+
+    #[test]
+    fn parses_hours_changes() {
+        let inputs = [
+            ("8", HoursChange::Set(Hours::from_minutes(480))),
+            ("+1:30", HoursChange::Add(Hours::from_minutes(90))),
+            ("-0:30", HoursChange::Subtract(Hours::from_minutes(30))),
+            ("-45m", HoursChange::Subtract(Hours::from_minutes(45))),
+        ];
+
+        for (input, expected) in inputs {
+            assert_eq!(input.parse::<HoursChange>().unwrap(), expected);
+        }
+
+        assert!("+-1".parse::<HoursChange>().is_err());
+        assert!("--1".parse::<HoursChange>().is_err());
+    }
+
+    #[test]
+    fn applies_hours_changes() {
+        let current = Hours::from_minutes(60);
+        let change = |input: &str| input.parse::<HoursChange>().unwrap();
+
+        assert_eq!(change("4").apply(current).unwrap().minutes, 240);
+        assert_eq!(change("+1:30").apply(current).unwrap().minutes, 150);
+        assert_eq!(change("-0:30").apply(current).unwrap().minutes, 30);
+        assert_eq!(change("-1").apply(current).unwrap().minutes, 0);
+    }
+
+    #[test]
+    fn rejects_hours_changes_out_of_bounds() {
+        let err = HoursChange::Subtract(Hours::from_minutes(90))
+            .apply(Hours::from_minutes(60))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Subtracting 1:30 from 1:00 would result in negative hours"
+        );
+
+        let result = HoursChange::Add(Hours::from_minutes(60)).apply(Hours::from_minutes(1400));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn sums_beyond_a_single_day() {
+        let total: Hours = [8, 8, 9]
+            .map(|hours| Hours::from_minutes(hours * 60))
+            .into_iter()
+            .sum();
+
+        assert_eq!(total.to_string(), "25:00");
+    }
+
+    #[test]
+    fn rounds_to_granularity() {
+        let quarter = Hours::from_minutes(15);
+        let inputs = [
+            (0, 0),
+            (7, 0),
+            (8, 15),
+            (22, 15),
+            (23, 30),
+            (50, 45),
+            (60, 60),
+        ];
+
+        for (minutes, expected) in inputs {
+            let rounded = Hours::from_minutes(minutes).round_to(quarter);
+            assert_eq!(
+                rounded,
+                Hours::from_minutes(expected),
+                "Failed on {minutes}"
+            );
+        }
+        assert_eq!(
+            Hours::from_minutes(7).round_to(Hours::ZERO),
+            Hours::from_minutes(7)
+        );
+    }
+
+    #[test]
+    fn serializes_as_decimal_hours() {
+        let json = serde_json::to_string(&Hours::from_minutes(20)).unwrap();
+
+        assert_eq!(json, "0.33");
+    }
+
+    #[test]
+    fn round_trips_every_minute_through_json() {
+        for minutes in 0..=MAX_MINUTES {
+            let hours = Hours::from_minutes(minutes);
+            let json = serde_json::to_string(&hours).unwrap();
+
+            assert_eq!(serde_json::from_str::<Hours>(&json).unwrap(), hours);
+        }
     }
 }
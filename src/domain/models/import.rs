@@ -0,0 +1,363 @@
+use super::{
+    day::Day,
+    hours::Hours,
+    job::{Job, Task},
+    time_sheet::{Line, TimeSheet, Week},
+    week::WeekNumber,
+};
+use chrono::{Datelike, NaiveDate};
+use std::{collections::HashMap, fmt::Display};
+
+/// Hours on a date for a job and task, read from another time tracking tool
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportedEntry {
+    pub(crate) date: NaiveDate,
+    pub(crate) job: String,
+    pub(crate) task: String,
+    pub(crate) hours: Hours,
+    pub(crate) comment: Option<String>,
+    /// Where the entry comes from, such as "row 3", for reporting errors
+    pub(crate) origin: String,
+}
+
+/// Why an entry, or a part of the imported file, can't be imported
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportError {
+    pub(crate) origin: String,
+    pub(crate) message: String,
+}
+
+impl ImportError {
+    pub(crate) fn new(origin: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            origin: origin.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.origin, self.message)
+    }
+}
+
+/// The imported hours of a job and task in a week
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportedLine {
+    pub(crate) job: String,
+    pub(crate) task: String,
+    pub(crate) hours: HashMap<Day, Hours>,
+    /// The distinct comments of the entries, joined
+    pub(crate) comment: Option<String>,
+    /// Origins of the entries that make up the line
+    pub(crate) origins: Vec<String>,
+}
+
+impl ImportedLine {
+    fn new(entry: &ImportedEntry) -> Self {
+        Self {
+            job: entry.job.clone(),
+            task: entry.task.clone(),
+            hours: HashMap::new(),
+            comment: None,
+            origins: Vec::new(),
+        }
+    }
+
+    fn is_for(&self, entry: &ImportedEntry) -> bool {
+        self.job.to_lowercase() == entry.job.to_lowercase()
+            && self.task.to_lowercase() == entry.task.to_lowercase()
+    }
+
+    fn add(&mut self, entry: &ImportedEntry) -> Result<(), ImportError> {
+        let day = Day::from(entry.date.weekday());
+        let current = self.hours.get(&day).copied().unwrap_or(Hours::ZERO);
+        let hours = current.checked_add(entry.hours).ok_or_else(|| {
+            let message = format!(
+                "More than 24 hours on {} for '{}', '{}'",
+                entry.date, entry.job, entry.task
+            );
+            ImportError::new(&entry.origin, message)
+        })?;
+        self.hours.insert(day, hours);
+
+        if let Some(comment) = &entry.comment {
+            self.comment = join_comments(self.comment.take(), comment);
+        }
+        self.origins.push(entry.origin.clone());
+
+        Ok(())
+    }
+}
+
+/// Adds `comment` to `comments`, unless it's empty or already there
+fn join_comments(comments: Option<String>, comment: &str) -> Option<String> {
+    match comments {
+        _ if comment.is_empty() => comments,
+        None => Some(comment.to_string()),
+        Some(comments) if comments.split("; ").any(|c| c == comment) => Some(comments),
+        Some(comments) => Some(format!("{comments}; {comment}")),
+    }
+}
+
+/// The imported lines of a week
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImportedWeek {
+    pub(crate) week: WeekNumber,
+    pub(crate) lines: Vec<ImportedLine>,
+}
+
+/// Groups entries by week, in order, and by job and task within each week. The hours of entries
+/// on the same day are summed
+pub(crate) fn group_by_week(
+    entries: &[ImportedEntry],
+) -> Result<Vec<ImportedWeek>, Vec<ImportError>> {
+    let mut entries: Vec<_> = entries.iter().collect();
+    // Stable, so that entries on the same date keep their order
+    entries.sort_by_key(|entry| entry.date);
+
+    let mut weeks: Vec<ImportedWeek> = Vec::new();
+    let mut errors = Vec::new();
+    for entry in entries {
+        let week = WeekNumber::of(entry.date);
+        if !matches!(weeks.last(), Some(last) if last.week == week) {
+            weeks.push(ImportedWeek {
+                week,
+                lines: Vec::new(),
+            });
+        }
+        let lines = &mut weeks.last_mut().expect("A week was just added").lines;
+
+        let line = match lines.iter().position(|line| line.is_for(entry)) {
+            Some(i) => &mut lines[i],
+            None => {
+                lines.push(ImportedLine::new(entry));
+                lines.last_mut().expect("A line was just added")
+            }
+        };
+        if let Err(err) = line.add(entry) {
+            errors.push(err);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(weeks)
+    } else {
+        Err(errors)
+    }
+}
+
+/// The line that imported hours go to
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LineTarget {
+    /// A (0-indexed) line that the time sheet already has
+    Existing(u8),
+    /// A line that has to be added first
+    New(Job, Task),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PlannedLine {
+    pub(crate) target: LineTarget,
+    pub(crate) hours: HashMap<Day, Hours>,
+    pub(crate) comment: Option<String>,
+}
+
+impl PlannedLine {
+    pub(crate) fn new(target: LineTarget, line: &ImportedLine) -> Self {
+        Self {
+            target,
+            hours: line.hours.clone(),
+            comment: line.comment.clone(),
+        }
+    }
+
+    /// Adds the hours and comment of another imported line that goes to the same line, such as
+    /// one that names the job by its number instead of its name
+    pub(crate) fn merge(&mut self, line: &ImportedLine) -> Result<(), String> {
+        for (&day, &hours) in &line.hours {
+            let current = self.hours.get(&day).copied().unwrap_or(Hours::ZERO);
+            let hours = current
+                .checked_add(hours)
+                .ok_or_else(|| format!("More than 24 hours on {day} for '{}'", line.job))?;
+            self.hours.insert(day, hours);
+        }
+        if let Some(comment) = &line.comment {
+            self.comment = join_comments(self.comment.take(), comment);
+        }
+
+        Ok(())
+    }
+}
+
+/// The changes to a week, together with the time sheet before and after them
+#[derive(Debug, Clone)]
+pub(crate) struct PlannedWeek {
+    pub(crate) week: WeekNumber,
+    pub(crate) lines: Vec<PlannedLine>,
+    pub(crate) before: TimeSheet,
+    pub(crate) after: TimeSheet,
+}
+
+impl PlannedWeek {
+    /// Works out the time sheet after the changes, without saving anything. New lines are added
+    /// at the end, the same way as Maconomy adds them
+    pub(crate) fn new(week: WeekNumber, before: TimeSheet, lines: Vec<PlannedLine>) -> Self {
+        let mut after = before.clone();
+        for planned in &lines {
+            let line_number = match &planned.target {
+                LineTarget::Existing(line_number) => *line_number as usize,
+                LineTarget::New(job, task) => {
+                    let line =
+                        Line::new(job.name.clone(), task.description.clone(), Week::default())
+                            .with_job_number(job.number.clone())
+                            .with_task_short_name(task.short_name.clone());
+                    after.lines.push(line);
+                    after.lines.len() - 1
+                }
+            };
+
+            after.set_line_hours(line_number, &planned.hours);
+            if let Some(comment) = &planned.comment {
+                after.lines[line_number].comment = comment.clone();
+            }
+        }
+
+        Self {
+            week,
+            lines,
+            before,
+            after,
+        }
+    }
+}
+
+/// What importing would change, and the entries that can't be imported
+#[derive(Debug, Default)]
+pub(crate) struct ImportPlan {
+    pub(crate) weeks: Vec<PlannedWeek>,
+    pub(crate) errors: Vec<ImportError>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(date: &str, job: &str, task: &str, minutes: u32) -> ImportedEntry {
+        ImportedEntry {
+            date: date.parse().unwrap(),
+            job: job.to_string(),
+            task: task.to_string(),
+            hours: Hours::from_minutes(minutes),
+            comment: None,
+            origin: format!("{date} {job}"),
+        }
+    }
+
+    #[test]
+    fn groups_entries_by_week_and_line() {
+        let entries = [
+            // Week 43 of 2024, out of order
+            entry("2024-10-22", "Acme", "Development", 60),
+            entry("2024-10-21", "Acme", "Development", 4 * 60),
+            entry("2024-10-21", "acme", "development", 30),
+            entry("2024-10-21", "Globex", "Meetings", 60),
+            // Week 44
+            entry("2024-10-28", "Acme", "Development", 8 * 60),
+        ];
+
+        let weeks = group_by_week(&entries).unwrap();
+
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week, WeekNumber::new(43, 2024).unwrap());
+        assert_eq!(weeks[0].lines.len(), 2);
+        let acme = &weeks[0].lines[0];
+        assert_eq!(acme.job, "Acme");
+        assert_eq!(acme.hours[&Day::Monday], Hours::from_minutes(4 * 60 + 30));
+        assert_eq!(acme.hours[&Day::Tuesday], Hours::from_minutes(60));
+        assert_eq!(weeks[1].week, WeekNumber::new(44, 2024).unwrap());
+    }
+
+    #[test]
+    fn joins_distinct_comments() {
+        let with_comment = |comment: &str| ImportedEntry {
+            comment: Some(comment.to_string()),
+            ..entry("2024-10-21", "Acme", "Development", 60)
+        };
+        let entries = [
+            with_comment("Fixed bug"),
+            with_comment("Review"),
+            with_comment("Fixed bug"),
+        ];
+
+        let weeks = group_by_week(&entries).unwrap();
+
+        assert_eq!(
+            weeks[0].lines[0].comment.as_deref(),
+            Some("Fixed bug; Review")
+        );
+    }
+
+    #[test]
+    fn rejects_more_than_a_day_of_hours() {
+        let entries = [
+            entry("2024-10-21", "Acme", "Development", 20 * 60),
+            entry("2024-10-21", "Acme", "Development", 5 * 60),
+        ];
+
+        let errors = group_by_week(&entries).unwrap_err();
+
+        assert_eq!(
+            errors,
+            [ImportError::new(
+                "2024-10-21 Acme",
+                "More than 24 hours on 2024-10-21 for 'Acme', 'Development'"
+            )]
+        );
+    }
+
+    #[test]
+    fn plans_changes_to_existing_and_new_lines() {
+        let existing = Line::new(
+            "Acme".to_string(),
+            "Development".to_string(),
+            Week::default(),
+        );
+        let before = TimeSheet::new(vec![existing], 43);
+        let job = Job {
+            number: "5678".to_string(),
+            name: "Globex".to_string(),
+            customer: "Globex Corporation".to_string(),
+        };
+        let task = Task {
+            short_name: "MEET".to_string(),
+            description: "Meetings".to_string(),
+        };
+        let lines = vec![
+            PlannedLine {
+                target: LineTarget::Existing(0),
+                hours: HashMap::from([(Day::Monday, Hours::from_minutes(8 * 60))]),
+                comment: Some("Fixed bugs".to_string()),
+            },
+            PlannedLine {
+                target: LineTarget::New(job, task),
+                hours: HashMap::from([(Day::Tuesday, Hours::from_minutes(60))]),
+                comment: None,
+            },
+        ];
+
+        let week = PlannedWeek::new(WeekNumber::new(43, 2024).unwrap(), before, lines);
+
+        assert_eq!(week.before.lines.len(), 1);
+        assert_eq!(week.after.lines.len(), 2);
+        assert_eq!(week.after.lines[0].week.monday, Hours::from_minutes(8 * 60));
+        assert_eq!(week.after.lines[0].comment, "Fixed bugs");
+        assert_eq!(week.after.lines[1].job, "Globex");
+        assert_eq!(week.after.lines[1].job_number, "5678");
+        assert_eq!(
+            week.after.totals.registered.total(),
+            Hours::from_minutes(9 * 60)
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use super::{
+    hours::{self, Hours},
+    import::{ImportError, ImportedEntry},
+};
+use chrono::{NaiveDate, TimeDelta};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+/// Something done for a while, such as a calendar event or a timewarrior interval, that rules
+/// map to a job and task
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Activity {
+    /// The day that the activity starts on, which its hours are registered on
+    pub(crate) date: NaiveDate,
+    pub(crate) duration: TimeDelta,
+    /// The title of an event or the description of a time entry
+    pub(crate) summary: String,
+    pub(crate) project: Option<String>,
+    /// Categories of an event or tags of a time entry
+    pub(crate) categories: Vec<String>,
+    /// For example "event 'Standup' on 2026-10-12", for reporting errors
+    pub(crate) origin: String,
+}
+
+/// Maps activities from other tools to a job and task. A rule applies if every pattern it has
+/// matches, so a rule without patterns applies to everything
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ImportRule {
+    /// Regex for the summary of an activity
+    #[serde(default, alias = "description", deserialize_with = "deserialize_regex")]
+    pub(crate) summary: Option<Regex>,
+    /// Regex for the project of an activity. Activities without a project don't match it
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub(crate) project: Option<Regex>,
+    /// Regex that at least one of the categories (or tags) of an activity has to match
+    #[serde(default, alias = "tag", deserialize_with = "deserialize_regex")]
+    pub(crate) category: Option<Regex>,
+    pub(crate) job: String,
+    pub(crate) task: String,
+}
+
+impl ImportRule {
+    pub(crate) fn matches(&self, activity: &Activity) -> bool {
+        let summary_matches = self
+            .summary
+            .as_ref()
+            .is_none_or(|pattern| pattern.is_match(&activity.summary));
+        let project_matches = self.project.as_ref().is_none_or(|pattern| {
+            activity
+                .project
+                .as_ref()
+                .is_some_and(|project| pattern.is_match(project))
+        });
+        let category_matches = self.category.as_ref().is_none_or(|pattern| {
+            activity
+                .categories
+                .iter()
+                .any(|category| pattern.is_match(category))
+        });
+
+        summary_matches && project_matches && category_matches
+    }
+}
+
+/// Settings of an import from another tool, configured in for example `[ics]` or
+/// `[timewarrior]`
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ImportSettings {
+    /// Durations of activities are rounded to this, for example "0:15"
+    #[serde(default, deserialize_with = "hours::deserialize_from_str")]
+    granularity: Option<Hours>,
+    /// How dates with slashes, like "03/04/2024", are read in CSV reports. Without it, only dates
+    /// that can be read one way are
+    #[serde(default)]
+    pub(crate) date_order: Option<DateOrder>,
+    /// Checked in order, and the first rule that applies to an activity is used
+    pub(crate) rules: Vec<ImportRule>,
+}
+
+/// The order of the day and month in dates with slashes
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum DateOrder {
+    /// As in the US, for example "10/21/2024"
+    MonthFirst,
+    /// As in most of Europe, for example "21/10/2024"
+    DayFirst,
+}
+
+impl ImportSettings {
+    pub(crate) const DEFAULT_GRANULARITY: Hours = Hours::from_minutes(15);
+
+    pub(crate) fn granularity(&self) -> Hours {
+        self.granularity.unwrap_or(Self::DEFAULT_GRANULARITY)
+    }
+
+    /// The first rule that applies
+    pub(crate) fn find_rule(&self, activity: &Activity) -> Option<&ImportRule> {
+        self.rules.iter().find(|rule| rule.matches(activity))
+    }
+}
+
+/// Activities read from another tool, and the ones that are left out, with why
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Activities {
+    pub(crate) activities: Vec<Activity>,
+    pub(crate) skipped: Vec<String>,
+}
+
+/// The entries of the activities that rules apply to, and the activities that are left out, with
+/// why
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct MappedEntries {
+    pub(crate) entries: Vec<ImportedEntry>,
+    pub(crate) skipped: Vec<String>,
+}
+
+/// Maps activities to entries with the first rule that applies, and rounds their durations.
+/// Activities that no rule applies to, that round to nothing, or that are longer than a day are
+/// skipped
+pub(crate) fn to_entries(
+    activities: Activities,
+    settings: &ImportSettings,
+) -> Result<MappedEntries, Vec<ImportError>> {
+    let granularity = settings.granularity();
+
+    let mut result = MappedEntries {
+        entries: Vec::new(),
+        skipped: activities.skipped,
+    };
+    let mut errors = Vec::new();
+    for activity in activities.activities {
+        let origin = activity.origin.clone();
+        let Ok(minutes) = u32::try_from(activity.duration.num_minutes()) else {
+            errors.push(ImportError::new(origin, "Ends before it starts"));
+            continue;
+        };
+        let Some(hours) = Hours::ZERO.checked_add(Hours::from_minutes(minutes)) else {
+            result.skipped.push(format!("{origin}: longer than a day"));
+            continue;
+        };
+        let hours = hours.round_to(granularity);
+        if hours.is_zero() {
+            result
+                .skipped
+                .push(format!("{origin}: shorter than {granularity}"));
+            continue;
+        }
+        let Some(rule) = settings.find_rule(&activity) else {
+            result.skipped.push(format!("{origin}: no rule applies"));
+            continue;
+        };
+
+        result.entries.push(ImportedEntry {
+            date: activity.date,
+            job: rule.job.clone(),
+            task: rule.task.clone(),
+            hours,
+            comment: None,
+            origin,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Regex>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|pattern| Regex::new(&pattern).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(rules: &str) -> ImportSettings {
+        serde_json::from_str(&format!(r#"{{"rules": {rules}}}"#)).unwrap()
+    }
+
+    fn activity(summary: &str, project: Option<&str>, categories: &[&str]) -> Activity {
+        Activity {
+            date: "2024-10-21".parse().unwrap(),
+            duration: TimeDelta::minutes(50),
+            summary: summary.to_string(),
+            project: project.map(str::to_string),
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            origin: format!("activity '{summary}'"),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let settings = settings(
+            r#"[
+                {"summary": "(?i)stand-?up", "job": "Acme", "task": "Meetings"},
+                {"category": "^Internal$", "job": "Internal", "task": "Meetings"},
+                {"summary": "Review", "tag": "Globex", "job": "Globex", "task": "Meetings"},
+                {"project": "^Initech", "job": "Initech", "task": "Meetings"},
+                {"job": "Fallback", "task": "Meetings"}
+            ]"#,
+        );
+        let job = |activity| settings.find_rule(&activity).map(|rule| rule.job.clone());
+
+        assert_eq!(
+            job(activity("Daily Standup", None, &["Internal"])),
+            Some("Acme".into())
+        );
+        assert_eq!(
+            job(activity("Planning", None, &["Work", "Internal"])),
+            Some("Internal".into())
+        );
+        assert_eq!(
+            job(activity("Review", None, &["Globex"])),
+            Some("Globex".into())
+        );
+        assert_eq!(
+            job(activity("Review", Some("Initech HR"), &[])),
+            Some("Initech".into())
+        );
+        assert_eq!(job(activity("Review", None, &[])), Some("Fallback".into()));
+    }
+
+    #[test]
+    fn maps_activities_to_rounded_entries() {
+        let settings =
+            settings(r#"[{"summary": "(?i)standup", "job": "Acme", "task": "Meetings"}]"#);
+        let short = Activity {
+            duration: TimeDelta::minutes(5),
+            ..activity("Quick standup", None, &[])
+        };
+        let activities = Activities {
+            activities: vec![
+                activity("Standup", None, &[]),
+                short,
+                activity("Lunch", None, &[]),
+            ],
+            skipped: vec!["event 'Holiday' on 2024-10-25: all-day event".to_string()],
+        };
+
+        let result = to_entries(activities, &settings).unwrap();
+
+        assert_eq!(
+            result.entries,
+            [ImportedEntry {
+                date: "2024-10-21".parse().unwrap(),
+                job: "Acme".to_string(),
+                task: "Meetings".to_string(),
+                hours: Hours::from_minutes(45),
+                comment: None,
+                origin: "activity 'Standup'".to_string(),
+            }]
+        );
+        assert_eq!(
+            result.skipped,
+            [
+                "event 'Holiday' on 2024-10-25: all-day event",
+                "activity 'Quick standup': shorter than 0:15",
+                "activity 'Lunch': no rule applies",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_activities_that_end_before_they_start() {
+        let settings = settings(r#"[{"job": "Acme", "task": "Meetings"}]"#);
+        let activities = Activities {
+            activities: vec![Activity {
+                duration: TimeDelta::minutes(-30),
+                ..activity("Standup", None, &[])
+            }],
+            skipped: Vec::new(),
+        };
+
+        let errors = to_entries(activities, &settings).unwrap_err();
+
+        assert_eq!(
+            errors,
+            [ImportError::new(
+                "activity 'Standup'",
+                "Ends before it starts"
+            )]
+        );
+    }
+
+    #[test]
+    fn deserializes_settings() {
+        let settings: ImportSettings = serde_json::from_str(
+            r#"{"granularity": "0:30", "rules": [{"summary": "(?i)standup", "job": "Acme", "task": "Meetings"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.granularity(), Hours::from_minutes(30));
+        assert!(settings.rules[0].matches(&activity("STANDUP", None, &[])));
+        assert!(settings.rules[0].category.is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let result = serde_json::from_str::<ImportRule>(
+            r#"{"summary": "(unclosed", "job": "Acme", "task": "Meetings"}"#,
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,22 @@
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct Job {
+    pub(crate) number: String,
+    pub(crate) name: String,
+    pub(crate) customer: String,
+}
+
+/// A task that can be registered on a job
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct Task {
+    /// Maconomy's short name for the task (i.e. `taskname`)
+    pub(crate) short_name: String,
+    /// The full task name, which is what `--task` expects (i.e. `tasktextvar`)
+    pub(crate) description: String,
+}
+
+/// A task together with the job that it belongs to, as `maconomy tasks` lists them
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct JobTask {
+    pub(crate) job: Job,
+    pub(crate) task: Task,
+}
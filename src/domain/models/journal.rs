@@ -0,0 +1,290 @@
+use super::{
+    day::Day,
+    time_sheet::{Line, TimeSheet, Week},
+    week::WeekNumber,
+};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// A change that was saved to Maconomy, kept so that it can be listed and undone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct JournalEntry {
+    /// When the command that made the change was started, which groups the changes of a command
+    pub(crate) command_started: DateTime<Utc>,
+    pub(crate) time: DateTime<Utc>,
+    pub(crate) week: WeekNumber,
+    pub(crate) change: Change,
+}
+
+impl JournalEntry {
+    pub(crate) fn new(command_started: DateTime<Utc>, week: WeekNumber, change: Change) -> Self {
+        Self {
+            command_started,
+            time: Utc::now(),
+            week,
+            change,
+        }
+    }
+
+    pub(crate) fn command_started_local(&self) -> DateTime<Local> {
+        self.command_started.with_timezone(&Local)
+    }
+}
+
+/// Groups entries by the command that made them, in the order of `entries`
+pub(crate) fn by_command(entries: &[JournalEntry]) -> Vec<&[JournalEntry]> {
+    entries
+        .chunk_by(|a, b| a.command_started == b.command_started)
+        .collect()
+}
+
+/// Identifies a line, since line numbers change when lines before it are deleted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LineKey {
+    pub(crate) job: String,
+    pub(crate) task: String,
+    pub(crate) job_number: String,
+    pub(crate) task_short_name: String,
+}
+
+impl LineKey {
+    /// The (0-indexed) line of `time_sheet` with this job and task. The line at `line_number`
+    /// is preferred, since a time sheet can have several lines with the same job and task
+    pub(crate) fn find_in(&self, time_sheet: &TimeSheet, line_number: u8) -> Option<u8> {
+        let is_same = |line: &Line| {
+            line.job_number == self.job_number
+                && line.task_short_name == self.task_short_name
+                && line.job == self.job
+                && line.task == self.task
+        };
+        if time_sheet
+            .lines
+            .get(line_number as usize)
+            .is_some_and(is_same)
+        {
+            return Some(line_number);
+        }
+
+        time_sheet
+            .lines
+            .iter()
+            .position(is_same)
+            .map(|row| row as u8)
+    }
+}
+
+impl From<&Line> for LineKey {
+    fn from(line: &Line) -> Self {
+        Self {
+            job: line.job.clone(),
+            task: line.task.clone(),
+            job_number: line.job_number.clone(),
+            task_short_name: line.task_short_name.clone(),
+        }
+    }
+}
+
+impl Display for LineKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "'{}', '{}'", self.job, self.task)
+    }
+}
+
+/// A change to a time sheet, with what's needed to revert it. Line numbers are 0-indexed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum Change {
+    Hours {
+        line: LineKey,
+        line_number: u8,
+        before: Week,
+        after: Week,
+    },
+    Comment {
+        line: LineKey,
+        line_number: u8,
+        before: String,
+        after: String,
+    },
+    AddLine {
+        line: LineKey,
+        line_number: u8,
+    },
+    /// Keeps the whole line, so that it can be added again
+    DeleteLine {
+        line: Line,
+        line_number: u8,
+    },
+    Submit,
+}
+
+impl Change {
+    /// The change to the hours of a line, if any. A line that `before` doesn't have is new
+    pub(crate) fn hours(before: &TimeSheet, after: &TimeSheet, line_number: u8) -> Option<Self> {
+        let line = after.lines.get(line_number as usize)?;
+        let previous = before
+            .lines
+            .get(line_number as usize)
+            .map(|line| line.week.clone())
+            .unwrap_or_default();
+        let is_changed = Day::ALL
+            .into_iter()
+            .any(|day| previous.get(day) != line.week.get(day));
+
+        is_changed.then(|| Change::Hours {
+            line: LineKey::from(line),
+            line_number,
+            before: previous,
+            after: line.week.clone(),
+        })
+    }
+
+    /// The change to the comment of a line, if any
+    pub(crate) fn comment(before: &TimeSheet, after: &TimeSheet, line_number: u8) -> Option<Self> {
+        let line = after.lines.get(line_number as usize)?;
+        let previous = before
+            .lines
+            .get(line_number as usize)
+            .map(|line| line.comment.clone())
+            .unwrap_or_default();
+
+        (previous != line.comment).then(|| Change::Comment {
+            line: LineKey::from(line),
+            line_number,
+            before: previous,
+            after: line.comment.clone(),
+        })
+    }
+
+    /// The line that was added last to `after`
+    pub(crate) fn add_line(after: &TimeSheet) -> Option<Self> {
+        let line_number = after.lines.len().checked_sub(1)?;
+
+        Some(Change::AddLine {
+            line: LineKey::from(&after.lines[line_number]),
+            line_number: line_number as u8,
+        })
+    }
+
+    pub(crate) fn delete_line(before: &TimeSheet, line_number: u8) -> Option<Self> {
+        let line = before.lines.get(line_number as usize)?;
+
+        Some(Change::DeleteLine {
+            line: line.clone(),
+            line_number,
+        })
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Hours {
+                line,
+                before,
+                after,
+                ..
+            } => {
+                let days: Vec<_> = Day::ALL
+                    .into_iter()
+                    .filter(|&day| before.get(day) != after.get(day))
+                    .map(|day| format!("{day} {} → {}", before.get(day), after.get(day)))
+                    .collect();
+                write!(f, "Set hours of {line}: {}", days.join(", "))
+            }
+            Change::Comment {
+                line,
+                before,
+                after,
+                ..
+            } => write!(f, "Set comment of {line}: '{before}' → '{after}'"),
+            Change::AddLine { line, .. } => write!(f, "Added line {line}"),
+            Change::DeleteLine { line, line_number } => write!(
+                f,
+                "Deleted line {} {} with {}",
+                line_number + 1,
+                LineKey::from(line),
+                line.week.total()
+            ),
+            Change::Submit => write!(f, "Submitted"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::hours::Hours;
+
+    fn line(job: &str, monday: u32) -> Line {
+        let week = Week {
+            monday: Hours::from_minutes(monday),
+            ..Default::default()
+        };
+        Line::new(job.to_string(), "Development".to_string(), week)
+            .with_job_number(format!("{job} #"))
+            .with_task_short_name("DEV".to_string())
+    }
+
+    #[test]
+    fn records_changed_hours() {
+        let before = TimeSheet::new(vec![line("Acme", 60)], 43);
+        let after = TimeSheet::new(vec![line("Acme", 90), line("Globex", 30)], 43);
+
+        let change = Change::hours(&before, &after, 0).unwrap();
+        assert_eq!(
+            change.to_string(),
+            "Set hours of 'Acme', 'Development': Monday 1:00 → 1:30"
+        );
+        let change = Change::hours(&before, &after, 1).unwrap();
+        assert_eq!(
+            change.to_string(),
+            "Set hours of 'Globex', 'Development': Monday 0:00 → 0:30"
+        );
+        assert_eq!(Change::hours(&before, &before, 0), None);
+    }
+
+    #[test]
+    fn finds_lines_that_have_moved() {
+        let time_sheet = TimeSheet::new(vec![line("Globex", 0), line("Acme", 0)], 43);
+        let key = LineKey::from(&line("Acme", 0));
+
+        assert_eq!(key.find_in(&time_sheet, 1), Some(1));
+        // For example after the first line has been deleted
+        assert_eq!(key.find_in(&time_sheet, 2), Some(1));
+        let missing = LineKey::from(&line("Initech", 0));
+        assert_eq!(missing.find_in(&time_sheet, 0), None);
+    }
+
+    #[test]
+    fn groups_entries_by_command() {
+        let started = |minute: u32| format!("2024-10-21T08:{minute:02}:00Z").parse().unwrap();
+        let entry = |minute| JournalEntry {
+            command_started: started(minute),
+            time: started(minute),
+            week: WeekNumber::new(43, 2024).unwrap(),
+            change: Change::Submit,
+        };
+        let entries = [entry(0), entry(0), entry(5)];
+
+        let commands = by_command(&entries);
+
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].len(), 2);
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let time_sheet = TimeSheet::new(vec![line("Acme", 90)], 43);
+        let entry = JournalEntry::new(
+            "2024-10-21T08:00:00Z".parse().unwrap(),
+            WeekNumber::new(43, 2024).unwrap(),
+            Change::delete_line(&time_sheet, 0).unwrap(),
+        );
+
+        let json = serde_json::to_string(&entry).unwrap();
+
+        assert_eq!(serde_json::from_str::<JournalEntry>(&json).unwrap(), entry);
+    }
+}
@@ -0,0 +1,158 @@
+use super::{day::Day, hours::Hours, period::Period, time_sheet::TimeSheet, week::WeekNumber};
+use chrono::NaiveDate;
+
+/// Registered and expected hours of a single day
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct DayTotal {
+    pub(crate) date: NaiveDate,
+    pub(crate) registered: Hours,
+    pub(crate) norm: Hours,
+}
+
+impl DayTotal {
+    /// Whether fewer hours than the norm are registered
+    pub(crate) fn is_missing_hours(&self) -> bool {
+        self.registered < self.norm
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct JobTotal {
+    pub(crate) job: String,
+    pub(crate) hours: Hours,
+}
+
+/// Summary of the hours in a period that may span several weeks
+#[derive(Debug, serde::Serialize)]
+pub(crate) struct Overview {
+    pub(crate) start: NaiveDate,
+    pub(crate) end: NaiveDate,
+    /// Every day of the period, in order
+    pub(crate) days: Vec<DayTotal>,
+    /// Hours per job, with the most hours first
+    pub(crate) jobs: Vec<JobTotal>,
+    pub(crate) registered: Hours,
+    pub(crate) norm: Hours,
+}
+
+impl Overview {
+    /// Summarizes the days of `time_sheets` that are in `period`
+    pub(crate) fn new(period: &Period, time_sheets: &[(WeekNumber, TimeSheet)]) -> Self {
+        let mut days = Vec::new();
+        let mut jobs: Vec<JobTotal> = Vec::new();
+
+        for (week, time_sheet) in time_sheets {
+            let dates = Day::ALL
+                .into_iter()
+                .filter_map(|day| week.date(day).map(|date| (day, date)))
+                .filter(|&(_, date)| period.contains(date));
+
+            for (day, date) in dates {
+                days.push(DayTotal {
+                    date,
+                    registered: time_sheet.totals.registered.get(day),
+                    norm: time_sheet.totals.norm.get(day),
+                });
+
+                for line in &time_sheet.lines {
+                    let hours = line.week.get(day);
+                    match jobs.iter_mut().find(|total| total.job == line.job) {
+                        Some(total) => total.hours = total.hours + hours,
+                        None => jobs.push(JobTotal {
+                            job: line.job.clone(),
+                            hours,
+                        }),
+                    }
+                }
+            }
+        }
+
+        days.sort_by_key(|day| day.date);
+        jobs.retain(|job| !job.hours.is_zero());
+        // Stable, so jobs with the same hours stay in the order they were first seen in
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.hours));
+
+        Self {
+            start: period.start,
+            end: period.end,
+            registered: days.iter().map(|day| day.registered).sum(),
+            norm: days.iter().map(|day| day.norm).sum(),
+            days,
+            jobs,
+        }
+    }
+
+    /// Days with fewer hours than the norm
+    pub(crate) fn missing_days(&self) -> impl Iterator<Item = &DayTotal> {
+        self.days.iter().filter(|day| day.is_missing_hours())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::time_sheet::{Line, Totals, Week};
+
+    fn hours(hours: u32) -> Hours {
+        Hours::from_minutes(hours * 60)
+    }
+
+    fn create_time_sheet(week: u8, job: &str, monday: u32, friday: u32) -> TimeSheet {
+        let line_week = Week {
+            monday: hours(monday),
+            friday: hours(friday),
+            ..Default::default()
+        };
+        let norm = Week {
+            monday: hours(8),
+            friday: hours(8),
+            ..Default::default()
+        };
+        let totals = Totals {
+            registered: line_week.clone(),
+            norm,
+            regular: line_week.clone(),
+        };
+        let line = Line::new(job.to_string(), "Development".to_string(), line_week);
+
+        TimeSheet::new(vec![line], week).with_totals(totals)
+    }
+
+    #[test]
+    fn summarizes_days_in_period() {
+        // October 2026 starts on a Thursday and ends on a Saturday
+        let period = Period::month(2026, 10).unwrap();
+        let time_sheets = [
+            (
+                WeekNumber::new(40, 2026).unwrap(),
+                create_time_sheet(40, "Acme", 8, 8),
+            ),
+            (
+                WeekNumber::new(44, 2026).unwrap(),
+                create_time_sheet(44, "Globex", 8, 4),
+            ),
+        ];
+
+        let overview = Overview::new(&period, &time_sheets);
+
+        // Monday to Wednesday of week 40 are in September, and Sunday of week 44 is in November
+        assert_eq!(overview.days.len(), 4 + 6);
+        assert_eq!(overview.registered, hours(8 + 8 + 4));
+        assert_eq!(
+            overview.jobs,
+            [
+                JobTotal {
+                    job: "Globex".to_string(),
+                    hours: hours(12)
+                },
+                JobTotal {
+                    job: "Acme".to_string(),
+                    hours: hours(8)
+                },
+            ]
+        );
+
+        let missing: Vec<_> = overview.missing_days().map(|day| day.date).collect();
+        assert_eq!(missing, ["2026-10-30".parse::<NaiveDate>().unwrap()]);
+    }
+}
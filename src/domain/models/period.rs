@@ -0,0 +1,198 @@
+use super::{day, week::WeekNumber};
+use anyhow::{anyhow, bail};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use std::fmt::Display;
+
+/// A range of dates, such as a month or a number of weeks, that may span several weeks
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct Period {
+    pub(crate) start: NaiveDate,
+    /// The last day of the period, inclusive
+    pub(crate) end: NaiveDate,
+}
+
+impl Period {
+    pub(crate) fn new(start: NaiveDate, end: NaiveDate) -> anyhow::Result<Self> {
+        if start > end {
+            bail!("Invalid period: {start} is after {end}");
+        }
+
+        Ok(Self { start, end })
+    }
+
+    pub(crate) fn month(year: i32, month: u32) -> anyhow::Result<Self> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| anyhow!("Invalid month '{year}-{month:02}'"))?;
+        let end = start
+            .checked_add_months(chrono::Months::new(1))
+            .and_then(|next_month| next_month.pred_opt())
+            .ok_or_else(|| anyhow!("Invalid month '{year}-{month:02}'"))?;
+
+        Self::new(start, end)
+    }
+
+    /// From the Monday of week `first` up to and including the Sunday of week `last`
+    pub(crate) fn weeks(first: &WeekNumber, last: &WeekNumber) -> anyhow::Result<Self> {
+        let start = first
+            .first_day()
+            .ok_or_else(|| anyhow!("Invalid week '{}'", first.number))?;
+        let end = NaiveDate::from_isoywd_opt(last.year, last.number.into(), Weekday::Sun)
+            .ok_or_else(|| anyhow!("Invalid week '{}'", last.number))?;
+
+        Self::new(start, end)
+    }
+
+    pub(crate) fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    pub(crate) fn dates(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.start.iter_days().take_while(|&date| date <= self.end)
+    }
+
+    /// The days of the period, grouped by week, with the weeks in order. Saturdays and Sundays
+    /// between the first and the last day are left out, while the first and last day are kept even
+    /// if they're in a weekend, since they were asked for
+    pub(crate) fn workdays_per_week(&self) -> Vec<(WeekNumber, day::Days)> {
+        let is_workday = |date: &NaiveDate| {
+            !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+                || *date == self.start
+                || *date == self.end
+        };
+        let mut weeks: Vec<(WeekNumber, day::Days)> = Vec::new();
+
+        for date in self.dates().filter(is_workday) {
+            let week = WeekNumber::of(date);
+            let day = day::Day::from(date.weekday());
+            match weeks.last_mut() {
+                Some((last, days)) if *last == week => {
+                    days.insert(day);
+                }
+                _ => weeks.push((week, day::Days::from([day]))),
+            }
+        }
+
+        weeks
+    }
+
+    /// The weeks that the period overlaps, in order
+    pub(crate) fn week_numbers(&self) -> Vec<WeekNumber> {
+        let mut weeks: Vec<WeekNumber> = Vec::new();
+        let mut monday = self.start - Days::new(self.start.weekday().num_days_from_monday().into());
+
+        while monday <= self.end {
+            weeks.push(WeekNumber::of(monday));
+            monday = monday + Days::new(7);
+        }
+
+        weeks
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} – {}", self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn gets_month() {
+        let period = Period::month(2024, 2).unwrap();
+
+        assert_eq!(period.start, date("2024-02-01"));
+        assert_eq!(period.end, date("2024-02-29"));
+        assert!(Period::month(2024, 13).is_err());
+    }
+
+    #[test]
+    fn gets_weeks_of_month() {
+        let weeks: Vec<_> = Period::month(2026, 10)
+            .unwrap()
+            .week_numbers()
+            .into_iter()
+            .map(|week| week.number)
+            .collect();
+
+        assert_eq!(weeks, [40, 41, 42, 43, 44]);
+    }
+
+    #[test]
+    fn gets_weeks_across_years() {
+        let first = WeekNumber::new(52, 2024).unwrap();
+        let last = WeekNumber::new(2, 2025).unwrap();
+        let period = Period::weeks(&first, &last).unwrap();
+
+        assert_eq!(period.start, date("2024-12-23"));
+        assert_eq!(period.end, date("2025-01-12"));
+        assert_eq!(
+            period.week_numbers(),
+            [
+                first,
+                WeekNumber::new(1, 2025).unwrap(),
+                WeekNumber::new(2, 2025).unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_days_by_week() {
+        // From a Friday to the Tuesday after
+        let period = Period::new(date("2026-10-09"), date("2026-10-13")).unwrap();
+
+        let expected = [
+            (
+                WeekNumber::new(41, 2026).unwrap(),
+                day::Days::from([day::Day::Friday]),
+            ),
+            (
+                WeekNumber::new(42, 2026).unwrap(),
+                day::Days::from([day::Day::Monday, day::Day::Tuesday]),
+            ),
+        ];
+        assert_eq!(period.workdays_per_week(), expected);
+    }
+
+    #[test]
+    fn skips_weekends_within_period() {
+        // From a Saturday to the Sunday two weeks later
+        let period = Period::new(date("2026-10-10"), date("2026-10-25")).unwrap();
+
+        let weeks = period.workdays_per_week();
+
+        let workdays = [
+            day::Day::Monday,
+            day::Day::Tuesday,
+            day::Day::Wednesday,
+            day::Day::Thursday,
+            day::Day::Friday,
+        ];
+        let last_week = workdays.into_iter().chain([day::Day::Sunday]);
+        let expected = [
+            (
+                WeekNumber::new(41, 2026).unwrap(),
+                day::Days::from([day::Day::Saturday]),
+            ),
+            (
+                WeekNumber::new(42, 2026).unwrap(),
+                day::Days::from(workdays),
+            ),
+            (WeekNumber::new(43, 2026).unwrap(), last_week.collect()),
+        ];
+        assert_eq!(weeks, expected);
+    }
+
+    #[test]
+    fn rejects_backwards_period() {
+        let result = Period::new(date("2024-10-02"), date("2024-10-01"));
+
+        assert!(result.is_err());
+    }
+}
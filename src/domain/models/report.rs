@@ -0,0 +1,194 @@
+use super::{day::Day, hours::Hours, period::Period, time_sheet::TimeSheet, week::WeekNumber};
+use chrono::NaiveDate;
+
+/// The hours of a line on a single day
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReportEntry {
+    pub(crate) date: NaiveDate,
+    pub(crate) job_number: String,
+    pub(crate) job: String,
+    pub(crate) task_short_name: String,
+    pub(crate) task: String,
+    pub(crate) comment: String,
+    pub(crate) hours: Hours,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JobSum {
+    pub(crate) job_number: String,
+    pub(crate) job: String,
+    pub(crate) hours: Hours,
+}
+
+/// What was registered in a period that may span several weeks, for handing over to clients
+#[derive(Debug)]
+pub(crate) struct Report {
+    /// Empty if Maconomy doesn't have the name
+    pub(crate) employee: String,
+    pub(crate) period: Period,
+    /// Every day and line with hours, by date and then in the order of the lines
+    pub(crate) entries: Vec<ReportEntry>,
+    /// Hours per job, in the order that the jobs first appear in
+    pub(crate) jobs: Vec<JobSum>,
+    pub(crate) total: Hours,
+}
+
+impl Report {
+    /// Reports the days of `time_sheets` that are in `period`. The period is narrowed to the
+    /// periods of the time sheets, if Maconomy has them
+    pub(crate) fn new(period: &Period, time_sheets: &[(WeekNumber, TimeSheet)]) -> Self {
+        let mut entries = Vec::new();
+        for (week, time_sheet) in time_sheets {
+            let dates = Day::ALL
+                .into_iter()
+                .filter_map(|day| week.date(day).map(|date| (day, date)))
+                .filter(|&(_, date)| period.contains(date));
+
+            for (day, date) in dates {
+                for line in &time_sheet.lines {
+                    let hours = line.week.get(day);
+                    if hours.is_zero() {
+                        continue;
+                    }
+                    entries.push(ReportEntry {
+                        date,
+                        job_number: line.job_number.clone(),
+                        job: line.job.clone(),
+                        task_short_name: line.task_short_name.clone(),
+                        task: line.task.clone(),
+                        comment: line.comment.clone(),
+                        hours,
+                    });
+                }
+            }
+        }
+        // Stable, so that the lines of a day stay in order
+        entries.sort_by_key(|entry| entry.date);
+
+        let mut jobs: Vec<JobSum> = Vec::new();
+        for entry in &entries {
+            let same_job =
+                |sum: &&mut JobSum| sum.job_number == entry.job_number && sum.job == entry.job;
+            match jobs.iter_mut().find(same_job) {
+                Some(sum) => sum.hours = sum.hours + entry.hours,
+                None => jobs.push(JobSum {
+                    job_number: entry.job_number.clone(),
+                    job: entry.job.clone(),
+                    hours: entry.hours,
+                }),
+            }
+        }
+
+        let start = time_sheets
+            .first()
+            .and_then(|(_, time_sheet)| time_sheet.period.as_ref())
+            .map_or(period.start, |first| first.start.max(period.start));
+        let end = time_sheets
+            .last()
+            .and_then(|(_, time_sheet)| time_sheet.period.as_ref())
+            .map_or(period.end, |last| last.end.min(period.end));
+        let employee = time_sheets
+            .iter()
+            .map(|(_, time_sheet)| time_sheet.employee.trim())
+            .find(|employee| !employee.is_empty())
+            .unwrap_or_default()
+            .to_string();
+
+        Self {
+            employee,
+            period: Period::new(start, end).unwrap_or_else(|_| period.clone()),
+            total: entries.iter().map(|entry| entry.hours).sum(),
+            entries,
+            jobs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::time_sheet::{Line, Week};
+
+    fn hours(hours: u32) -> Hours {
+        Hours::from_minutes(hours * 60)
+    }
+
+    fn line(job: &str, task: &str, monday: u32, friday: u32) -> Line {
+        let week = Week {
+            monday: hours(monday),
+            friday: hours(friday),
+            ..Default::default()
+        };
+        Line::new(job.to_string(), task.to_string(), week).with_job_number(format!("{job} #"))
+    }
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    #[test]
+    fn reports_days_and_jobs_in_period() {
+        // Week 40 of 2026 starts on September 28th, and week 44 ends on November 1st
+        let period = Period::month(2026, 10).unwrap();
+        let week_40 = TimeSheet::new(
+            vec![
+                line("Acme", "Development", 8, 6),
+                line("Globex", "Meetings", 0, 2),
+            ],
+            40,
+        )
+        .with_employee("John Smith".to_string())
+        .with_period(Period::new(date("2026-09-28"), date("2026-10-04")).unwrap());
+        let week_44 = TimeSheet::new(vec![line("Globex", "Meetings", 4, 0)], 44)
+            .with_period(Period::new(date("2026-10-26"), date("2026-11-01")).unwrap());
+        let time_sheets = [
+            (WeekNumber::new(40, 2026).unwrap(), week_40),
+            (WeekNumber::new(44, 2026).unwrap(), week_44),
+        ];
+
+        let report = Report::new(&period, &time_sheets);
+
+        assert_eq!(report.employee, "John Smith");
+        assert_eq!(report.period, period);
+        // Monday of week 40 is in September
+        let entries: Vec<_> = report
+            .entries
+            .iter()
+            .map(|entry| (entry.date.to_string(), entry.job.as_str(), entry.hours))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("2026-10-02".to_string(), "Acme", hours(6)),
+                ("2026-10-02".to_string(), "Globex", hours(2)),
+                ("2026-10-26".to_string(), "Globex", hours(4)),
+            ]
+        );
+        let jobs: Vec<_> = report
+            .jobs
+            .iter()
+            .map(|job| (job.job.as_str(), job.hours))
+            .collect();
+        assert_eq!(jobs, [("Acme", hours(6)), ("Globex", hours(6))]);
+        assert_eq!(report.total, hours(12));
+    }
+
+    #[test]
+    fn narrows_period_to_time_sheets() {
+        let period = Period::weeks(
+            &WeekNumber::new(43, 2024).unwrap(),
+            &WeekNumber::new(43, 2024).unwrap(),
+        )
+        .unwrap();
+        // For example a time sheet that ends on Friday
+        let time_sheet = TimeSheet::new(Vec::new(), 43)
+            .with_period(Period::new(date("2024-10-21"), date("2024-10-25")).unwrap());
+        let time_sheets = [(WeekNumber::new(43, 2024).unwrap(), time_sheet)];
+
+        let report = Report::new(&period, &time_sheets);
+
+        assert_eq!(report.period.end, date("2024-10-25"));
+        assert_eq!(report.employee, "");
+        assert!(report.entries.is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+/// A set of hours that can be applied to a week, configured in `[templates.<name>]`
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Template {
+    pub(crate) entries: Vec<TemplateEntry>,
+}
+
+/// Hours for a job and task on one or more days. Days and hours are kept as written in the
+/// configuration, and are parsed like the `--day` and hours arguments
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TemplateEntry {
+    pub(crate) job: String,
+    pub(crate) task: String,
+    /// For example "mon-thu" or "monday, friday"
+    pub(crate) days: String,
+    /// For example "8" or "4:30"
+    pub(crate) hours: String,
+}
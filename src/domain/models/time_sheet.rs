@@ -1,6 +1,13 @@
-use super::hours::Hours;
+use super::{
+    day::{Day, Days},
+    hours::{Hours, HoursChange},
+    period::Period,
+};
+use crate::domain::matching::{find_best_match, AmbiguousMatch, MatchKind};
+use std::{collections::HashMap, fmt::Display};
 
-#[derive(Debug, serde::Serialize)]
+/// Deserialized without the total that it's serialized with
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
 pub(crate) struct Week {
     pub(crate) monday: Hours,
     pub(crate) tuesday: Hours,
@@ -11,44 +18,571 @@ pub(crate) struct Week {
     pub(crate) sunday: Hours,
 }
 
-#[derive(Debug, serde::Serialize)]
+impl Week {
+    pub(crate) fn get(&self, day: Day) -> Hours {
+        match day {
+            Day::Monday => self.monday,
+            Day::Tuesday => self.tuesday,
+            Day::Wednesday => self.wednesday,
+            Day::Thursday => self.thursday,
+            Day::Friday => self.friday,
+            Day::Saturday => self.saturday,
+            Day::Sunday => self.sunday,
+        }
+    }
+
+    /// Sum of the hours of all days
+    pub(crate) fn total(&self) -> Hours {
+        Day::ALL.into_iter().map(|day| self.get(day)).sum()
+    }
+
+    /// Hours of the days that have any hours registered
+    pub(crate) fn registered_hours(&self) -> HashMap<Day, Hours> {
+        Day::ALL
+            .into_iter()
+            .map(|day| (day, self.get(day)))
+            .filter(|(_, hours)| !hours.is_zero())
+            .collect()
+    }
+
+    pub(crate) fn set(&mut self, day: Day, hours: Hours) {
+        let day = match day {
+            Day::Monday => &mut self.monday,
+            Day::Tuesday => &mut self.tuesday,
+            Day::Wednesday => &mut self.wednesday,
+            Day::Thursday => &mut self.thursday,
+            Day::Friday => &mut self.friday,
+            Day::Saturday => &mut self.saturday,
+            Day::Sunday => &mut self.sunday,
+        };
+        *day = hours;
+    }
+}
+
+/// Serializes the days together with their total
+impl serde::Serialize for Week {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut week = serializer.serialize_struct("Week", 8)?;
+        week.serialize_field("monday", &self.monday)?;
+        week.serialize_field("tuesday", &self.tuesday)?;
+        week.serialize_field("wednesday", &self.wednesday)?;
+        week.serialize_field("thursday", &self.thursday)?;
+        week.serialize_field("friday", &self.friday)?;
+        week.serialize_field("saturday", &self.saturday)?;
+        week.serialize_field("sunday", &self.sunday)?;
+        week.serialize_field("total", &self.total())?;
+        week.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub(crate) struct Line {
     pub(crate) job: String,
     pub(crate) task: String,
     pub(crate) week: Week,
+    /// Maconomy's `entrytext`
+    pub(crate) comment: String,
+    /// Empty if the line hasn't been saved to Maconomy
+    pub(crate) job_number: String,
+    /// Maconomy's `taskname`. Empty if the line hasn't been saved to Maconomy
+    pub(crate) task_short_name: String,
 }
 
 impl Line {
     pub(crate) fn new(job: String, task: String, week: Week) -> Self {
-        Self { job, task, week }
+        Self {
+            job,
+            task,
+            week,
+            comment: String::new(),
+            job_number: String::new(),
+            task_short_name: String::new(),
+        }
+    }
+
+    pub(crate) fn with_comment(mut self, comment: String) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    pub(crate) fn with_job_number(mut self, job_number: String) -> Self {
+        self.job_number = job_number;
+        self
+    }
+
+    pub(crate) fn with_task_short_name(mut self, task_short_name: String) -> Self {
+        self.task_short_name = task_short_name;
+        self
+    }
+
+    /// How well the line matches `job` and `task`, i.e. the worse of the two matches
+    fn match_kind(&self, job: &str, task: &str) -> Option<MatchKind> {
+        let job = MatchKind::of(job, &self.job)?;
+        let task = MatchKind::of(task, &self.task)?;
+
+        Some(job.max(task))
+    }
+
+    /// Whether the line is for exactly this job and task in Maconomy, by their numbers rather than
+    /// their names
+    pub(crate) fn is_for(&self, job_number: &str, task_short_name: &str) -> bool {
+        self.job_number == job_number && self.task_short_name == task_short_name
+    }
+
+    pub(crate) fn has_same_job_and_task(&self, other: &Line) -> bool {
+        self.job.to_lowercase() == other.job.to_lowercase()
+            && self.task.to_lowercase() == other.task.to_lowercase()
+    }
+}
+
+/// Hours per day of the whole time sheet, as calculated by Maconomy
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub(crate) struct Totals {
+    /// Registered hours of all lines (Maconomy's `totalnumberday*var`)
+    pub(crate) registered: Week,
+    /// Expected hours according to the employee's norm time (Maconomy's `fixednumberday*var`)
+    pub(crate) norm: Week,
+    /// Registered hours that count as regular time (Maconomy's `regulartimeday*var`)
+    pub(crate) regular: Week,
+}
+
+impl Totals {
+    /// Whether fewer hours than the norm are registered on `day`
+    pub(crate) fn is_below_norm(&self, day: Day) -> bool {
+        self.registered.get(day) < self.norm.get(day)
+    }
+
+    /// The days with less regular time than the norm, e.g. because some of the hours are overtime
+    pub(crate) fn underfilled_days(&self) -> Vec<Day> {
+        Day::ALL
+            .into_iter()
+            .filter(|&day| self.regular.get(day) < self.norm.get(day))
+            .collect()
     }
 
-    fn has_job_and_task(&self, job: &str, task: &str) -> bool {
-        self.job.to_lowercase() == job.to_lowercase()
-            && self.task.to_lowercase() == task.to_lowercase()
+    /// The days with more hours registered than `max`
+    pub(crate) fn days_above(&self, max: Hours) -> Vec<Day> {
+        Day::ALL
+            .into_iter()
+            .filter(|&day| self.registered.get(day) > max)
+            .collect()
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+/// Where the time sheet is in the submission and approval process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    #[default]
+    Open,
+    Submitted,
+    Approved,
+    /// Sent back by the approver, so that it can be changed and submitted again
+    Rejected,
+}
+
+impl Status {
+    /// Whether the time sheet is waiting for or has received approval, and shouldn't be changed
+    pub(crate) fn is_locked(&self) -> bool {
+        matches!(self, Status::Submitted | Status::Approved)
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            Status::Open => "Open",
+            Status::Submitted => "Submitted",
+            Status::Approved => "Approved",
+            Status::Rejected => "Rejected",
+        };
+        write!(f, "{status}")
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
 pub(crate) struct TimeSheet {
     pub(crate) lines: Vec<Line>,
     pub(crate) week_number: u8,
+    pub(crate) totals: Totals,
+    pub(crate) status: Status,
+    /// Name of the employee that the time sheet belongs to
+    pub(crate) employee: String,
+    /// The dates that the time sheet covers, according to Maconomy
+    pub(crate) period: Option<Period>,
 }
 
 impl TimeSheet {
     pub(crate) fn new(lines: Vec<Line>, week_number: u8) -> Self {
-        Self { lines, week_number }
+        Self {
+            lines,
+            week_number,
+            totals: Totals::default(),
+            status: Status::default(),
+            employee: String::new(),
+            period: None,
+        }
+    }
+
+    pub(crate) fn with_employee(mut self, employee: String) -> Self {
+        self.employee = employee;
+        self
+    }
+
+    pub(crate) fn with_period(mut self, period: Period) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    pub(crate) fn with_totals(mut self, totals: Totals) -> Self {
+        self.totals = totals;
+        self
+    }
+
+    pub(crate) fn with_status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
     }
 }
 
 impl TimeSheet {
-    pub(crate) fn find_line_nr(&self, job: &str, task: &str) -> Option<u8> {
-        let (row, _) = self
-            .lines
+    /// Whether the time sheet has a line for the same job and task in Maconomy as `line`
+    pub(crate) fn has_line(&self, line: &Line) -> bool {
+        self.lines
             .iter()
-            .enumerate()
-            .find(|(_, line)| line.has_job_and_task(job, task))?;
+            .any(|existing| existing.is_for(&line.job_number, &line.task_short_name))
+    }
+
+    /// Finds the (0-indexed) line that best matches `job` and `task`. Both may be prefixes or
+    /// substrings of the line's names, as long as only one line matches best.
+    pub(crate) fn find_line_nr(&self, job: &str, task: &str) -> Result<Option<u8>, AmbiguousMatch> {
+        self.find_line_nr_among(job, task, |_| true)
+    }
+
+    /// Like `find_line_nr`, but only among the lines without a job number, which can't be matched
+    /// by their job and task numbers
+    pub(crate) fn find_unnumbered_line_nr(
+        &self,
+        job: &str,
+        task: &str,
+    ) -> Result<Option<u8>, AmbiguousMatch> {
+        self.find_line_nr_among(job, task, |line| line.job_number.is_empty())
+    }
+
+    fn find_line_nr_among(
+        &self,
+        job: &str,
+        task: &str,
+        is_candidate: impl Fn(&Line) -> bool,
+    ) -> Result<Option<u8>, AmbiguousMatch> {
+        // Lines with the same job and task count as the same candidate, and the first one is used
+        let candidates = self.lines.iter().enumerate().filter(|&(row, line)| {
+            is_candidate(line)
+                && !self.lines[..row]
+                    .iter()
+                    .any(|previous| previous.has_same_job_and_task(line))
+        });
+
+        let line_number = find_best_match(candidates, |(_, line)| line.match_kind(job, task))
+            .into_result("Line", &format!("{job}, {task}"), |(_, line)| {
+                format!("{}, {}", line.job, line.task)
+            })?
+            .map(|(row, _)| row as u8);
+
+        Ok(line_number)
+    }
+
+    /// Applies `change` to the line that matches `job` and `task` without saving it anywhere, for
+    /// previewing changes. Adds a line with the names as given if no line matches
+    pub(crate) fn apply(
+        &mut self,
+        change: HoursChange,
+        days: &Days,
+        job: &str,
+        task: &str,
+    ) -> anyhow::Result<()> {
+        let line_number = match self.find_line_nr(job, task)? {
+            Some(line_number) => line_number as usize,
+            None => {
+                let line = Line::new(job.to_string(), task.to_string(), Week::default());
+                self.lines.push(line);
+                self.lines.len() - 1
+            }
+        };
+
+        for &day in days {
+            let current = self.lines[line_number].week.get(day);
+            let hours = change
+                .apply(current)
+                .map_err(|err| anyhow::anyhow!("{err} on {day}"))?;
+            self.set_hours(line_number, day, hours);
+        }
+
+        Ok(())
+    }
+
+    /// Merges lines for the same job and task in Maconomy into the first of them, by adding their
+    /// hours to it. Returns the lines that were merged away
+    pub(crate) fn merge_duplicate_lines(&mut self) -> Vec<Line> {
+        let mut lines: Vec<Line> = Vec::new();
+        let mut merged = Vec::new();
+        for line in std::mem::take(&mut self.lines) {
+            let first = lines
+                .iter_mut()
+                .find(|first| first.is_for(&line.job_number, &line.task_short_name));
+            match first {
+                Some(first) => {
+                    for day in Day::ALL {
+                        first
+                            .week
+                            .set(day, first.week.get(day) + line.week.get(day));
+                    }
+                    merged.push(line);
+                }
+                None => lines.push(line),
+            }
+        }
+        self.lines = lines;
+
+        merged
+    }
+
+    /// Sets the hours of an existing (0-indexed) line without saving them anywhere, for
+    /// previewing changes
+    pub(crate) fn set_line_hours(&mut self, line_number: usize, hours: &HashMap<Day, Hours>) {
+        for (&day, &hours) in hours {
+            self.set_hours(line_number, day, hours);
+        }
+    }
+
+    /// Removes an existing (0-indexed) line without saving anything, for previewing changes
+    pub(crate) fn remove_line(&mut self, line_number: usize) -> Line {
+        let line = self.lines.remove(line_number);
+        for day in Day::ALL {
+            let total = self.totals.registered.get(day);
+            let total = total.checked_sub(line.week.get(day)).unwrap_or(Hours::ZERO);
+            self.totals.registered.set(day, total);
+        }
+
+        line
+    }
+
+    fn set_hours(&mut self, line_number: usize, day: Day, hours: Hours) {
+        let week = &mut self.lines[line_number].week;
+        let current = week.get(day);
+        week.set(day, hours);
+
+        // Keep the day's total in line with the change
+        let total = self.totals.registered.get(day);
+        let total = total.checked_sub(current).unwrap_or(Hours::ZERO) + hours;
+        self.totals.registered.set(day, total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_line(job: &str, task: &str) -> Line {
+        let week = Week {
+            monday: Hours::ZERO,
+            tuesday: Hours::ZERO,
+            wednesday: Hours::ZERO,
+            thursday: Hours::ZERO,
+            friday: Hours::ZERO,
+            saturday: Hours::ZERO,
+            sunday: Hours::ZERO,
+        };
+        Line::new(job.to_string(), task.to_string(), week)
+    }
+
+    fn create_time_sheet() -> TimeSheet {
+        let lines = vec![
+            create_line("Acme Corp", "Development"),
+            create_line("Acme Corp", "Meetings"),
+            create_line("Globex", "Development"),
+            create_line("Acme Corp", "Development"),
+        ];
+        TimeSheet::new(lines, 42)
+    }
+
+    #[test]
+    fn finds_line_by_prefix() {
+        let time_sheet = create_time_sheet();
+
+        assert_eq!(time_sheet.find_line_nr("acme", "meet").unwrap(), Some(1));
+        assert_eq!(time_sheet.find_line_nr("glob", "dev").unwrap(), Some(2));
+        assert_eq!(time_sheet.find_line_nr("initech", "dev").unwrap(), None);
+    }
+
+    #[test]
+    fn finds_only_lines_without_job_number_by_name() {
+        let numbered = create_line("Acme Corp", "Meetings").with_job_number("1000".to_string());
+        let lines = vec![numbered, create_line("Acme Corp", "Development")];
+        let time_sheet = TimeSheet::new(lines, 42);
+
+        assert_eq!(
+            time_sheet.find_unnumbered_line_nr("acme", "dev").unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            time_sheet.find_unnumbered_line_nr("acme", "meet").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn has_line_only_for_same_job_and_task_numbers() {
+        let line = |task: &str, task_short_name: &str| {
+            create_line("Acme Corp", task)
+                .with_job_number("1000".to_string())
+                .with_task_short_name(task_short_name.to_string())
+        };
+        let time_sheet = TimeSheet::new(vec![line("Development support", "SUP")], 42);
+
+        assert!(time_sheet.has_line(&line("Development support", "SUP")));
+        // Even though its names are a prefix of the names of the existing line
+        assert!(!time_sheet.has_line(&line("Development", "DEV")));
+    }
+
+    #[test]
+    fn merges_duplicate_lines_into_the_first() {
+        let line = |task: &str, task_short_name: &str, monday: u32| {
+            let mut line = create_line("Acme Corp", task)
+                .with_job_number("1000".to_string())
+                .with_task_short_name(task_short_name.to_string());
+            line.week.monday = Hours::from_minutes(monday);
+            line
+        };
+        let lines = vec![
+            line("Development", "DEV", 60),
+            line("Meetings", "MEET", 30),
+            line("Development", "DEV", 90),
+        ];
+        let mut time_sheet = TimeSheet::new(lines, 42);
+
+        let merged = time_sheet.merge_duplicate_lines();
+
+        assert_eq!(merged, [line("Development", "DEV", 90)]);
+        assert_eq!(
+            time_sheet.lines,
+            [
+                line("Development", "DEV", 150),
+                line("Meetings", "MEET", 30)
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_first_of_identical_lines() {
+        let time_sheet = create_time_sheet();
+
+        assert_eq!(time_sheet.find_line_nr("acme", "dev").unwrap(), Some(0));
+    }
+
+    #[test]
+    fn rejects_ambiguous_line() {
+        let time_sheet = create_time_sheet();
+        let err = time_sheet.find_line_nr("acme", "e").unwrap_err();
+
+        let expected = "Line 'acme, e' is ambiguous. Did you mean one of these?\n  \
+                        1. Acme Corp, Development\n  2. Acme Corp, Meetings";
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    fn applies_changes_locally() {
+        let mut time_sheet = create_time_sheet();
+        let days = Days::from([Day::Monday, Day::Tuesday]);
+
+        let change = HoursChange::Set(Hours::from_minutes(480));
+        time_sheet.apply(change, &days, "glob", "dev").unwrap();
+        let change = HoursChange::Add(Hours::from_minutes(60));
+        time_sheet
+            .apply(change, &days, "Initech", "Support")
+            .unwrap();
+
+        assert_eq!(
+            time_sheet.lines[2].week.get(Day::Tuesday),
+            Hours::from_minutes(480)
+        );
+        let new_line = time_sheet.lines.last().unwrap();
+        assert_eq!(
+            (new_line.job.as_str(), new_line.task.as_str()),
+            ("Initech", "Support")
+        );
+        assert_eq!(new_line.week.get(Day::Monday), Hours::from_minutes(60));
+    }
+
+    #[test]
+    fn updates_totals_when_applying_changes() {
+        let mut time_sheet = create_time_sheet();
+        time_sheet
+            .totals
+            .registered
+            .set(Day::Monday, Hours::from_minutes(120));
+        time_sheet
+            .totals
+            .norm
+            .set(Day::Monday, Hours::from_minutes(480));
+        let days = Days::from([Day::Monday]);
+
+        let change = HoursChange::Set(Hours::from_minutes(300));
+        time_sheet.apply(change, &days, "glob", "dev").unwrap();
+
+        assert_eq!(
+            time_sheet.totals.registered.get(Day::Monday),
+            Hours::from_minutes(420)
+        );
+        assert!(time_sheet.totals.is_below_norm(Day::Monday));
+        assert!(!time_sheet.totals.is_below_norm(Day::Tuesday));
+    }
+
+    #[test]
+    fn removes_lines_locally() {
+        let mut time_sheet = create_time_sheet();
+        let days = Days::from([Day::Monday]);
+        let change = HoursChange::Set(Hours::from_minutes(300));
+        time_sheet.apply(change, &days, "glob", "dev").unwrap();
+
+        let removed = time_sheet.remove_line(2);
+
+        assert_eq!(removed.job, "Globex");
+        assert_eq!(time_sheet.lines.len(), 3);
+        assert_eq!(time_sheet.totals.registered.get(Day::Monday), Hours::ZERO);
+    }
+
+    #[test]
+    fn rejects_invalid_local_changes() {
+        let mut time_sheet = create_time_sheet();
+        let days = Days::from([Day::Friday]);
+
+        let change = HoursChange::Subtract(Hours::from_minutes(60));
+        let err = time_sheet.apply(change, &days, "glob", "dev").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Subtracting 1:00 from 0:00 would result in negative hours on Friday"
+        );
+    }
+
+    #[test]
+    fn finds_underfilled_and_overfilled_days() {
+        let week = |monday: u32, tuesday: u32| Week {
+            monday: Hours::from_minutes(monday),
+            tuesday: Hours::from_minutes(tuesday),
+            ..Default::default()
+        };
+        let totals = Totals {
+            registered: week(600, 480),
+            norm: week(480, 480),
+            regular: week(480, 420),
+        };
 
-        Some(row as u8)
+        assert_eq!(totals.underfilled_days(), [Day::Tuesday]);
+        assert_eq!(totals.days_above(Hours::from_minutes(540)), [Day::Monday]);
+        assert_eq!(totals.days_above(Hours::from_minutes(600)), []);
     }
 }
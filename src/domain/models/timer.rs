@@ -0,0 +1,154 @@
+use super::hours::{self, Hours};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A timer for a job and task, which books the time that has passed when it's stopped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Timer {
+    pub(crate) job: String,
+    pub(crate) task: String,
+    /// Kept in UTC, so that changing time zone while the timer runs doesn't change its time
+    pub(crate) started: DateTime<Utc>,
+}
+
+/// Settings of the timer, configured in `[timer]`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub(crate) struct TimerSettings {
+    /// The time of each day is rounded to this, for example "0:05"
+    #[serde(default, deserialize_with = "hours::deserialize_from_str")]
+    granularity: Option<Hours>,
+}
+
+impl TimerSettings {
+    pub(crate) fn granularity(&self) -> Hours {
+        self.granularity.unwrap_or(Timer::DEFAULT_GRANULARITY)
+    }
+}
+
+impl Timer {
+    pub(crate) const DEFAULT_GRANULARITY: Hours = Hours::from_minutes(15);
+
+    pub(crate) fn new(job: String, task: String, started: DateTime<Utc>) -> Self {
+        Self { job, task, started }
+    }
+
+    /// Whole minutes that have passed since the timer was started, which is zero if the clock
+    /// has been turned back since
+    pub(crate) fn elapsed(&self, now: DateTime<Utc>) -> Hours {
+        minutes_between(self.started, now)
+    }
+
+    /// The time that has passed since the timer was started, split at midnight in `time_zone`, so
+    /// that the time of each day can be booked on that day
+    pub(crate) fn elapsed_per_day<Tz: TimeZone>(
+        &self,
+        now: DateTime<Utc>,
+        time_zone: &Tz,
+    ) -> Vec<DayPart> {
+        let mut parts = Vec::new();
+        let mut start = self.started;
+        while start < now {
+            let date = start.with_timezone(time_zone).date_naive();
+            let next_midnight = date
+                .succ_opt()
+                .and_then(|next| {
+                    time_zone
+                        .from_local_datetime(&next.and_time(NaiveTime::MIN))
+                        .earliest()
+                })
+                .map(|midnight| midnight.with_timezone(&Utc));
+            let end = next_midnight.map_or(now, |midnight| midnight.min(now));
+            parts.push(DayPart {
+                date,
+                elapsed: minutes_between(start, end),
+                end,
+            });
+            start = end;
+        }
+
+        parts
+    }
+
+    pub(crate) fn started_local(&self) -> DateTime<Local> {
+        self.started.with_timezone(&Local)
+    }
+}
+
+/// The part of the time of a timer that is on one day
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DayPart {
+    pub(crate) date: NaiveDate,
+    pub(crate) elapsed: Hours,
+    /// When the part ends, which is midnight unless it's the last part
+    pub(crate) end: DateTime<Utc>,
+}
+
+fn minutes_between(start: DateTime<Utc>, end: DateTime<Utc>) -> Hours {
+    let minutes = (end - start).num_minutes().max(0);
+    Hours::from_minutes(u32::try_from(minutes).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer() -> Timer {
+        let started = "2024-10-21T08:00:00Z".parse().unwrap();
+        Timer::new("Acme".to_string(), "Development".to_string(), started)
+    }
+
+    #[test]
+    fn counts_whole_minutes() {
+        let now = "2024-10-21T09:22:59Z".parse().unwrap();
+
+        assert_eq!(timer().elapsed(now), Hours::from_minutes(82));
+    }
+
+    #[test]
+    fn never_counts_backwards() {
+        let now = "2024-10-21T07:00:00Z".parse().unwrap();
+
+        assert_eq!(timer().elapsed(now), Hours::ZERO);
+    }
+
+    #[test]
+    fn splits_time_at_midnight() {
+        let now = "2024-10-23T09:30:00Z".parse().unwrap();
+
+        let parts = timer().elapsed_per_day(now, &Utc);
+
+        let expected = [
+            ("2024-10-21", 16 * 60, "2024-10-22T00:00:00Z"),
+            ("2024-10-22", 24 * 60, "2024-10-23T00:00:00Z"),
+            ("2024-10-23", 9 * 60 + 30, "2024-10-23T09:30:00Z"),
+        ]
+        .map(|(date, minutes, end)| DayPart {
+            date: date.parse().unwrap(),
+            elapsed: Hours::from_minutes(minutes),
+            end: end.parse().unwrap(),
+        });
+        assert_eq!(parts, expected);
+    }
+
+    #[test]
+    fn keeps_time_within_a_day_together() {
+        let now = "2024-10-21T09:22:59Z".parse().unwrap();
+
+        let parts = timer().elapsed_per_day(now, &Utc);
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].elapsed, Hours::from_minutes(82));
+        assert!(timer().elapsed_per_day(timer().started, &Utc).is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let json = serde_json::to_string(&timer()).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"job":"Acme","task":"Development","started":"2024-10-21T08:00:00Z"}"#
+        );
+        assert_eq!(serde_json::from_str::<Timer>(&json).unwrap(), timer());
+    }
+}
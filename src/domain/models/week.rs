@@ -1,8 +1,9 @@
+use super::day::Day;
 use anyhow::anyhow;
 use chrono::{Datelike, NaiveDate, Weekday};
 use std::fmt::Display;
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub(crate) struct WeekNumber {
     pub(crate) number: u8,
     pub(crate) year: i32,
@@ -21,9 +22,42 @@ impl WeekNumber {
         WeekNumber::new(week, year)
     }
 
+    /// The week that `date` is in
+    pub(crate) fn of(date: NaiveDate) -> Self {
+        let week = date.iso_week();
+        let number = week
+            .week()
+            .try_into()
+            .expect("Week numbers are always less than 255");
+
+        Self {
+            number,
+            year: week.year(),
+        }
+    }
+
     pub(crate) fn first_day(&self) -> Option<NaiveDate> {
         first_day_of_week(self.number, self.year)
     }
+
+    /// The date of `day` in this week
+    pub(crate) fn date(&self, day: Day) -> Option<NaiveDate> {
+        NaiveDate::from_isoywd_opt(self.year, self.number.into(), day.into())
+    }
+
+    /// The week `n` weeks before this one
+    pub(crate) fn weeks_before(&self, n: u8) -> anyhow::Result<Self> {
+        let first_day = self
+            .first_day()
+            .ok_or_else(|| anyhow!("Invalid week '{}'", self.number))?;
+        let week = (first_day - chrono::Duration::weeks(n.into())).iso_week();
+        let number = week
+            .week()
+            .try_into()
+            .expect("Week numbers are always less than 255");
+
+        WeekNumber::new(number, week.year())
+    }
 }
 
 impl Default for WeekNumber {
@@ -72,4 +106,28 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn gets_date_of_day() {
+        let week = WeekNumber::new(1, 2025).unwrap();
+
+        assert_eq!(
+            week.date(Day::Wednesday).unwrap(),
+            NaiveDate::parse_from_str("2025-01-01", "%Y-%m-%d").unwrap()
+        );
+    }
+
+    #[test]
+    fn gets_previous_weeks() {
+        let week = WeekNumber::new(2, 2025).unwrap();
+
+        assert_eq!(
+            week.weeks_before(1).unwrap(),
+            WeekNumber::new(1, 2025).unwrap()
+        );
+        assert_eq!(
+            week.weeks_before(2).unwrap(),
+            WeekNumber::new(52, 2024).unwrap()
+        );
+    }
 }
@@ -1,13 +1,24 @@
+use crate::domain::matching::AmbiguousMatch;
 use crate::infrastructure::repositories::maconomy_http_client::AddRowError;
 use crate::infrastructure::repositories::time_sheet_repository::{
-    AddLineError, TimeSheetRepository,
+    resolve_line_number, AddLineError, TimeSheetRepository,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use log::warn;
+use std::collections::HashMap;
 use std::rc::Rc;
 use tokio::sync::Mutex;
 
+use super::models::day::Day;
 use super::models::day::Days;
+use super::models::hours::{Hours, HoursChange};
+use super::models::import::{
+    ImportError, ImportPlan, ImportedLine, ImportedWeek, LineTarget, PlannedLine, PlannedWeek,
+};
+use super::models::job::{Job, Task};
+use super::models::journal::{Change, JournalEntry, LineKey};
+use super::models::line_number::LineNumber;
+use super::models::time_sheet::{Line, TimeSheet, Week};
 use super::models::week::WeekNumber;
 
 #[derive(thiserror::Error, Debug)]
@@ -16,11 +27,51 @@ pub(crate) enum SetTimeError {
     JobNotFound(String),
     #[error("Task '{0}' not found")]
     TaskNotFound(String),
+    #[error("{0}")]
+    InvalidHours(String),
+    #[error(transparent)]
+    Ambiguous(#[from] AmbiguousMatch),
     #[error("Something went wrong when setting hours: {0}")]
     Unknown(#[from] anyhow::Error),
     // TODO: handle authentication error
 }
 
+impl From<AddLineError> for SetTimeError {
+    fn from(err: AddLineError) -> Self {
+        match err {
+            AddLineError::JobNotFound(err) => SetTimeError::JobNotFound(err),
+            AddLineError::TaskNotFound(err) => SetTimeError::TaskNotFound(err),
+            AddLineError::InvalidHours(err) => SetTimeError::InvalidHours(err),
+            AddLineError::Ambiguous(err) => SetTimeError::Ambiguous(err),
+            err => {
+                warn!("{err}");
+                anyhow::anyhow!(err).into()
+            }
+        }
+    }
+}
+
+/// Result of copying lines from one week to another
+#[derive(Debug, Default)]
+pub(crate) struct CopiedLines {
+    pub(crate) copied: Vec<Line>,
+    /// Lines that the target week already had
+    pub(crate) skipped: Vec<Line>,
+    /// Later lines of the source week for the same job and task as a copied line, which were
+    /// copied as part of it
+    pub(crate) merged: Vec<Line>,
+}
+
+/// A week before and after a change that hasn't been saved
+#[derive(Debug)]
+pub(crate) struct Preview {
+    pub(crate) before: TimeSheet,
+    pub(crate) after: TimeSheet,
+}
+
+/// Jobs and tasks that have been looked up by their (lowercase) names, or why they weren't found
+type FoundJobsAndTasks = HashMap<(String, String), Result<(Job, Task), String>>;
+
 pub(crate) struct TimeSheetService<'a> {
     repository: Rc<Mutex<TimeSheetRepository<'a>>>,
 }
@@ -39,49 +90,487 @@ impl TimeSheetService<'_> {
         days: &Days,
         week: &WeekNumber,
     ) -> Result<(), SetTimeError> {
-        self.set_time(0.0, days, week, job, task).await
+        self.set_time(HoursChange::Set(Hours::ZERO), days, week, job, task)
+            .await
     }
 
     /// Sets time (initializes the week if it is uninitialized)
     pub(crate) async fn set_time(
         &mut self,
-        hours: f32,
+        change: HoursChange,
         days: &Days,
         week: &WeekNumber,
         job: &str,
         task: &str,
     ) -> Result<(), SetTimeError> {
         let mut repository = self.repository.lock().await;
-        if let Err(err) = repository.set_time(hours, days, week, job, task).await {
-            return match err {
-                AddLineError::WeekUninitialized(AddRowError::Unknown(err)) => todo!("{}", err),
-                AddLineError::WeekUninitialized(AddRowError::WeekUninitialized) => {
-                    eprintln!("Creating new timesheet...");
+        with_time_sheet(&mut repository, async |repository| {
+            repository.set_time(change, days, week, job, task).await
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Works out what `set_time` would change, without changing anything. The job and task are
+    /// looked up if the week has no line for them
+    pub(crate) async fn preview_set_time(
+        &mut self,
+        change: HoursChange,
+        days: &Days,
+        week: &WeekNumber,
+        job: &str,
+        task: &str,
+    ) -> Result<Preview, SetTimeError> {
+        let mut repository = self.repository.lock().await;
+        let before = repository.get_time_sheet(week).await?;
+
+        let mut after = before.clone();
+        let line_number = match before.find_line_nr(job, task)? {
+            Some(line_number) => line_number as usize,
+            None => {
+                let (job, task) = repository.find_job_and_task(job, task).await?;
+                let line = Line::new(job.name, task.description, Week::default())
+                    .with_job_number(job.number)
+                    .with_task_short_name(task.short_name);
+                after.lines.push(line);
+                after.lines.len() - 1
+            }
+        };
+
+        let current = &after.lines[line_number].week;
+        let hours = days
+            .iter()
+            .map(|&day| {
+                let hours = change
+                    .apply(current.get(day))
+                    .map_err(|err| SetTimeError::InvalidHours(format!("{err} on {day}")))?;
+                Ok((day, hours))
+            })
+            .collect::<Result<HashMap<_, _>, SetTimeError>>()?;
+        after.set_line_hours(line_number, &hours);
+
+        Ok(Preview { before, after })
+    }
+
+    /// Works out what deleting a line would change, without changing anything
+    pub(crate) async fn preview_delete_line(
+        &mut self,
+        line_number: &LineNumber,
+        week: &WeekNumber,
+    ) -> Result<Preview> {
+        let before = self.repository.lock().await.get_time_sheet(week).await?;
+
+        let row = resolve_line_number(line_number, &before)? as usize;
+        if row >= before.lines.len() {
+            anyhow::bail!("{week} has no line {}", row + 1);
+        }
+        let mut after = before.clone();
+        after.remove_line(row);
+
+        Ok(Preview { before, after })
+    }
+
+    /// Adds a new line for exactly the given job and task, and returns its (0-indexed) line number
+    /// (initializes the week if it is uninitialized)
+    pub(crate) async fn add_line(
+        &mut self,
+        week: &WeekNumber,
+        job: &Job,
+        task: &Task,
+    ) -> Result<u8, SetTimeError> {
+        let mut repository = self.repository.lock().await;
+        let line_number = with_time_sheet(&mut repository, async |repository| {
+            repository.add_new_line(week, job, task).await
+        })
+        .await?;
+
+        Ok(line_number)
+    }
+
+    /// Copies the lines of week `from` that are missing in week `to`, optionally with their hours
+    /// (initializes the week if it is uninitialized)
+    pub(crate) async fn copy_lines(
+        &mut self,
+        from: &WeekNumber,
+        to: &WeekNumber,
+        with_hours: bool,
+    ) -> Result<CopiedLines, SetTimeError> {
+        let (_, result) = self.plan_copy(from, to).await?;
+
+        for line in &result.copied {
+            // Lines of the source week are already in Maconomy, so their job and task are known
+            let job = Job {
+                number: line.job_number.clone(),
+                name: line.job.clone(),
+                customer: String::new(),
+            };
+            let task = Task {
+                short_name: line.task_short_name.clone(),
+                description: line.task.clone(),
+            };
+            let line_number = self.add_line(to, &job, &task).await?;
+
+            let hours = line.week.registered_hours();
+            if with_hours && !hours.is_empty() {
+                let mut repository = self.repository.lock().await;
+                repository.set_line_hours(to, line_number, &hours).await?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Works out what `copy_lines` would change, without changing anything
+    pub(crate) async fn preview_copy_lines(
+        &mut self,
+        from: &WeekNumber,
+        to: &WeekNumber,
+        with_hours: bool,
+    ) -> Result<Preview, SetTimeError> {
+        let (before, result) = self.plan_copy(from, to).await?;
+
+        let mut after = before.clone();
+        for line in result.copied {
+            let hours = line.week.registered_hours();
+            let copy = Line::new(line.job, line.task, Week::default())
+                .with_job_number(line.job_number)
+                .with_task_short_name(line.task_short_name);
+            after.lines.push(copy);
+            if with_hours {
+                after.set_line_hours(after.lines.len() - 1, &hours);
+            }
+        }
+
+        Ok(Preview { before, after })
+    }
+
+    /// Works out which lines of week `from` are copied to week `to`, and returns them with the
+    /// time sheet of week `to`
+    async fn plan_copy(
+        &mut self,
+        from: &WeekNumber,
+        to: &WeekNumber,
+    ) -> Result<(TimeSheet, CopiedLines), SetTimeError> {
+        let (mut source, target) = {
+            let mut repository = self.repository.lock().await;
+            let source = repository.get_time_sheet(from).await?;
+            (source, repository.get_time_sheet(to).await?)
+        };
+
+        // The source week may have several lines for the same job and task, which are copied as
+        // one line with all of their hours
+        let merged = source.merge_duplicate_lines();
+
+        let mut result = CopiedLines::default();
+        for line in source.lines {
+            if target.has_line(&line) {
+                result.skipped.push(line);
+            } else {
+                result.copied.push(line);
+            }
+        }
+        result.merged = merged
+            .into_iter()
+            .filter(|line| {
+                result
+                    .copied
+                    .iter()
+                    .any(|copied| copied.is_for(&line.job_number, &line.task_short_name))
+            })
+            .collect();
+
+        Ok((target, result))
+    }
+
+    /// Works out what setting the comment of a line would change, without changing anything
+    pub(crate) async fn preview_set_comment(
+        &mut self,
+        line_number: &LineNumber,
+        comment: &str,
+        week: &WeekNumber,
+    ) -> Result<Preview> {
+        let before = self.repository.lock().await.get_time_sheet(week).await?;
+
+        let row = resolve_line_number(line_number, &before)? as usize;
+        if row >= before.lines.len() {
+            anyhow::bail!("{week} has no line {}", row + 1);
+        }
+        let mut after = before.clone();
+        after.lines[row].comment = comment.to_string();
+
+        Ok(Preview { before, after })
+    }
+
+    /// Reverts a change from the journal. Lines are found by their job and task, since their line
+    /// numbers may have changed. Deleted lines are added again at the end of the time sheet. Hours
+    /// that have been changed since are only overwritten with `force`
+    pub(crate) async fn undo(&mut self, entry: &JournalEntry, force: bool) -> Result<()> {
+        let week = &entry.week;
+        let mut repository = self.repository.lock().await;
+        let time_sheet = repository.get_time_sheet(week).await?;
+        let find_line = |line: &LineKey, line_number: u8| {
+            line.find_in(&time_sheet, line_number)
+                .with_context(|| format!("{week} no longer has the line {line}"))
+        };
+
+        match &entry.change {
+            Change::Hours {
+                line,
+                line_number,
+                before,
+                after,
+            } => {
+                let line_number = find_line(line, *line_number)?;
+                let changed_days: Vec<_> = Day::ALL
+                    .into_iter()
+                    .filter(|&day| before.get(day) != after.get(day))
+                    .collect();
+
+                let current = &time_sheet.lines[line_number as usize].week;
+                let changed_since: Vec<_> = changed_days
+                    .iter()
+                    .filter(|&&day| current.get(day) != after.get(day))
+                    .map(|day| day.to_string())
+                    .collect();
+                if !changed_since.is_empty() && !force {
+                    anyhow::bail!(
+                        "The hours of {line} on {} have been changed since, which undoing would \
+                        overwrite. Use `--force` to undo anyway",
+                        changed_since.join(", ")
+                    );
+                }
 
-                    repository.create_new_timesheet().await?;
+                let hours = changed_days
+                    .into_iter()
+                    .map(|day| (day, before.get(day)))
+                    .collect();
+                repository.set_line_hours(week, line_number, &hours).await
+            }
+            Change::Comment {
+                line,
+                line_number,
+                before,
+                ..
+            } => {
+                let line_number = LineNumber::Number(find_line(line, *line_number)? + 1);
+                repository
+                    .set_line_comment(week, &line_number, before)
+                    .await
+            }
+            Change::AddLine { line, line_number } => {
+                let line_number = find_line(line, *line_number)?;
+                if !time_sheet.lines[line_number as usize]
+                    .week
+                    .total()
+                    .is_zero()
+                {
+                    anyhow::bail!("The line {line} has hours, which removing it would lose");
+                }
+                let line_number = LineNumber::Number(line_number + 1);
+                repository.delete_line(&line_number, week).await
+            }
+            Change::DeleteLine { line, .. } => {
+                let job = Job {
+                    number: line.job_number.clone(),
+                    name: line.job.clone(),
+                    customer: String::new(),
+                };
+                let task = Task {
+                    short_name: line.task_short_name.clone(),
+                    description: line.task.clone(),
+                };
+                let line_number = repository
+                    .add_new_line(week, &job, &task)
+                    .await
+                    .map_err(|err| anyhow::anyhow!(err))?;
 
+                let hours = line.week.registered_hours();
+                if !hours.is_empty() {
+                    repository.set_line_hours(week, line_number, &hours).await?;
+                }
+                if !line.comment.is_empty() {
+                    let line_number = LineNumber::Number(line_number + 1);
                     repository
-                        .set_time(hours, days, week, job, task)
-                        .await
-                        .map_err(|err| {
-                            let msg = format!(
-                                "Failed to set time, even after creating a new timesheet: {err}"
-                            );
-                            warn!("{msg}");
-                            anyhow::anyhow!(msg)
-                        })?;
-
-                    Ok(())
+                        .set_line_comment(week, &line_number, &line.comment)
+                        .await?;
                 }
-                AddLineError::JobNotFound(err) => Err(SetTimeError::JobNotFound(err)),
-                AddLineError::TaskNotFound(err) => Err(SetTimeError::TaskNotFound(err)),
-                err => {
+                Ok(())
+            }
+            Change::Submit => anyhow::bail!("Submitting {week} can't be undone"),
+        }
+    }
+
+    /// Works out how to import `weeks` without changing anything. Jobs and tasks that a week has
+    /// no line for are looked up, and the entries that can't be imported are reported in the
+    /// plan. Weeks that have been submitted or approved are only planned with `force`
+    pub(crate) async fn plan_import(
+        &mut self,
+        weeks: &[ImportedWeek],
+        force: bool,
+    ) -> Result<ImportPlan, SetTimeError> {
+        let mut plan = ImportPlan::default();
+        // The same job and task is often imported into several weeks
+        let mut found = HashMap::new();
+
+        for imported in weeks {
+            let before = self
+                .repository
+                .lock()
+                .await
+                .get_time_sheet(&imported.week)
+                .await?;
+            if before.status.is_locked() && !force {
+                let status = before.status.to_string().to_lowercase();
+                let message = format!(
+                    "{} has been {status}. Use `--force` to import anyway",
+                    imported.week
+                );
+                plan.errors.extend(report(&imported.lines, &message));
+                continue;
+            }
+
+            let mut lines: Vec<PlannedLine> = Vec::new();
+            for line in &imported.lines {
+                let target = match self.find_target(line, &before, &mut found).await? {
+                    Ok(target) => target,
+                    Err(message) => {
+                        plan.errors.extend(report([line], &message));
+                        continue;
+                    }
+                };
+
+                match lines.iter_mut().find(|planned| planned.target == target) {
+                    Some(planned) => {
+                        if let Err(message) = planned.merge(line) {
+                            plan.errors.extend(report([line], &message));
+                        }
+                    }
+                    None => lines.push(PlannedLine::new(target, line)),
+                }
+            }
+
+            if !lines.is_empty() {
+                let week = imported.week.clone();
+                plan.weeks.push(PlannedWeek::new(week, before, lines));
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Finds the line of `time_sheet` that `line` goes to, or the job and task of a new line.
+    /// Lines are matched by the numbers of the job and task that `line` names, and only lines
+    /// without a job number are matched by name. Lookups are remembered in `found`. Returns the
+    /// reason if `line` can't be imported
+    async fn find_target(
+        &mut self,
+        line: &ImportedLine,
+        time_sheet: &TimeSheet,
+        found: &mut FoundJobsAndTasks,
+    ) -> Result<Result<LineTarget, String>, SetTimeError> {
+        let key = (line.job.to_lowercase(), line.task.to_lowercase());
+        if !found.contains_key(&key) {
+            let repository = self.repository.lock().await;
+            let result = match repository.find_job_and_task(&line.job, &line.task).await {
+                Ok(job_and_task) => Ok(job_and_task),
+                Err(
+                    err @ (AddLineError::JobNotFound(_)
+                    | AddLineError::TaskNotFound(_)
+                    | AddLineError::Ambiguous(_)),
+                ) => Err(err.to_string()),
+                Err(err) => {
                     warn!("{err}");
-                    Err(anyhow::anyhow!(err).into())
+                    return Err(anyhow::anyhow!(err).into());
                 }
             };
+            found.insert(key.clone(), result);
+        }
+
+        // The line may be named differently in the imported file, for example by job number
+        if let Ok((job, task)) = &found[&key] {
+            let existing = time_sheet
+                .lines
+                .iter()
+                .position(|existing| existing.is_for(&job.number, &task.short_name));
+            if let Some(line_number) = existing {
+                return Ok(Ok(LineTarget::Existing(line_number as u8)));
+            }
+        }
+
+        match time_sheet.find_unnumbered_line_nr(&line.job, &line.task) {
+            Ok(Some(line_number)) => return Ok(Ok(LineTarget::Existing(line_number))),
+            Ok(None) => (),
+            Err(err) => return Ok(Err(err.to_string())),
+        }
+
+        let target = match &found[&key] {
+            Ok((job, task)) => Ok(LineTarget::New(job.clone(), task.clone())),
+            Err(message) => Err(message.clone()),
         };
 
+        Ok(target)
+    }
+
+    /// Saves the changes of a plan from `plan_import` (initializes weeks that are uninitialized)
+    pub(crate) async fn import(&mut self, plan: &ImportPlan) -> Result<(), SetTimeError> {
+        for planned in &plan.weeks {
+            let mut line_numbers = Vec::new();
+            for line in &planned.lines {
+                let line_number = match &line.target {
+                    LineTarget::Existing(line_number) => *line_number,
+                    LineTarget::New(job, task) => self.add_line(&planned.week, job, task).await?,
+                };
+                line_numbers.push(line_number);
+            }
+
+            let hours: Vec<_> = line_numbers
+                .iter()
+                .zip(&planned.lines)
+                .map(|(&line_number, line)| (line_number, &line.hours))
+                .collect();
+            let mut repository = self.repository.lock().await;
+            repository.set_lines_hours(&planned.week, &hours).await?;
+
+            for (&line_number, line) in line_numbers.iter().zip(&planned.lines) {
+                if let Some(comment) = &line.comment {
+                    let line_number = LineNumber::Number(line_number + 1);
+                    repository
+                        .set_line_comment(&planned.week, &line_number, comment)
+                        .await?;
+                }
+            }
+        }
+
         Ok(())
     }
 }
+
+/// One error per entry of `lines`
+fn report<'a>(
+    lines: impl IntoIterator<Item = &'a ImportedLine>,
+    message: &str,
+) -> Vec<ImportError> {
+    lines
+        .into_iter()
+        .flat_map(|line| &line.origins)
+        .map(|origin| ImportError::new(origin, message))
+        .collect()
+}
+
+/// Runs `operation`, and runs it once more after creating the time sheet if the week doesn't have
+/// one yet
+async fn with_time_sheet<'a, T>(
+    repository: &mut TimeSheetRepository<'a>,
+    mut operation: impl AsyncFnMut(&mut TimeSheetRepository<'a>) -> Result<T, AddLineError>,
+) -> Result<T, AddLineError> {
+    match operation(repository).await {
+        Err(AddLineError::WeekUninitialized(AddRowError::WeekUninitialized)) => {
+            eprintln!("Creating new timesheet...");
+            repository.create_new_timesheet().await?;
+
+            operation(repository).await
+        }
+        result => result,
+    }
+}
@@ -0,0 +1,212 @@
+//! Local cache of job and task names, so that shell completions never have to call Maconomy (or
+//! open a browser to log in) while the user is typing.
+
+use crate::config::Configuration;
+use crate::domain::matching::MatchKind;
+use crate::domain::models::{
+    job::{Job, Task},
+    time_sheet::Line,
+};
+use anyhow::{Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const DEFAULT_PATH: &str = "~/.local/share/maconomy-cli/completion_cache.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CachedLine {
+    job: String,
+    task: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct CompletionCache {
+    /// Lines of the current week's time sheet
+    #[serde(default)]
+    lines: Vec<CachedLine>,
+    /// Names of jobs seen when listing jobs
+    #[serde(default)]
+    jobs: Vec<String>,
+    /// Task names by job name, seen when listing tasks
+    #[serde(default)]
+    tasks: BTreeMap<String, Vec<String>>,
+}
+
+impl CompletionCache {
+    /// Gets the (expanded) path of the cache, which can be set with `completion_cache_path`
+    pub(crate) fn path(config: &Configuration) -> Result<String> {
+        let path = config
+            .get_optional_value("completion_cache_path")?
+            .unwrap_or(DEFAULT_PATH.to_string());
+
+        let path = shellexpand::full(&path).context("Failed to expand completion cache path")?;
+        Ok(path.into_owned())
+    }
+
+    /// Reads the cache, or returns an empty cache if it is missing or unreadable
+    pub(crate) fn load(path: &str) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(|| {
+                debug!("No usable completion cache found at {path}");
+                Self::default()
+            })
+    }
+
+    pub(crate) fn save(&self, path: &str) -> Result<()> {
+        if let Some(parent) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directories for {path}"))?;
+        }
+
+        let json = serde_json::to_string(self).context("Failed to serialize completion cache")?;
+        std::fs::write(path, json).context("Failed to write completion cache")
+    }
+
+    pub(crate) fn set_lines(&mut self, lines: &[Line]) {
+        self.lines = lines
+            .iter()
+            .map(|line| CachedLine {
+                job: line.job.clone(),
+                task: line.task.clone(),
+            })
+            .collect();
+    }
+
+    /// Remembers the jobs, on top of the ones that are already cached. Jobs are only ever listed
+    /// partially (when searching), so cached jobs are never forgotten
+    pub(crate) fn add_jobs(&mut self, jobs: &[Job]) {
+        for job in jobs {
+            if !self.jobs.contains(&job.name) {
+                self.jobs.push(job.name.clone());
+            }
+        }
+        self.jobs.sort_unstable();
+    }
+
+    /// Remembers the tasks of the job, by its name rather than by what it was looked up with
+    pub(crate) fn set_tasks(&mut self, job: &Job, tasks: &[Task]) {
+        let tasks = tasks.iter().map(|task| task.description.clone()).collect();
+        self.tasks.insert(job.name.clone(), tasks);
+    }
+
+    /// Job names, with the jobs of the current week first
+    pub(crate) fn job_names(&self) -> Vec<&str> {
+        let line_jobs = self.lines.iter().map(|line| line.job.as_str());
+        let cached_jobs = self.jobs.iter().chain(self.tasks.keys());
+
+        dedup(line_jobs.chain(cached_jobs.map(String::as_str)))
+    }
+
+    /// Task names, with the tasks of the current week first. If `job` is given, only the tasks of
+    /// jobs that it matches are included
+    pub(crate) fn task_names(&self, job: Option<&str>) -> Vec<&str> {
+        let is_job = |name: &str| job.is_none_or(|job| MatchKind::of(job, name).is_some());
+
+        let line_tasks = self
+            .lines
+            .iter()
+            .filter(|line| is_job(&line.job))
+            .map(|line| line.task.as_str());
+        let cached_tasks = self
+            .tasks
+            .iter()
+            .filter(|(job, _)| is_job(job))
+            .flat_map(|(_, tasks)| tasks.iter().map(String::as_str));
+
+        dedup(line_tasks.chain(cached_tasks))
+    }
+}
+
+/// Removes duplicates, keeping the first occurrence
+fn dedup<'a>(names: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let mut unique = Vec::new();
+    for name in names {
+        if !unique.contains(&name) {
+            unique.push(name);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{hours::Hours, time_sheet::Week};
+
+    fn create_cache() -> CompletionCache {
+        let week = Week {
+            monday: Hours::ZERO,
+            tuesday: Hours::ZERO,
+            wednesday: Hours::ZERO,
+            thursday: Hours::ZERO,
+            friday: Hours::ZERO,
+            saturday: Hours::ZERO,
+            sunday: Hours::ZERO,
+        };
+        let lines = [
+            Line::new("Acme".to_string(), "Development".to_string(), week.clone()),
+            Line::new("Globex".to_string(), "Meetings".to_string(), week),
+        ];
+        let job = |name: &str| Job {
+            number: "1".to_string(),
+            name: name.to_string(),
+            customer: "Customer".to_string(),
+        };
+        let task = |description: &str| Task {
+            short_name: "100".to_string(),
+            description: description.to_string(),
+        };
+
+        let mut cache = CompletionCache::default();
+        cache.set_lines(&lines);
+        cache.add_jobs(&[job("Initech"), job("Acme")]);
+        cache.set_tasks(&job("Acme"), &[task("Development"), task("Support")]);
+        cache
+    }
+
+    #[test]
+    fn lists_job_names_with_current_week_first() {
+        let cache = create_cache();
+
+        assert_eq!(cache.job_names(), vec!["Acme", "Globex", "Initech"]);
+    }
+
+    #[test]
+    fn lists_job_of_tasks_by_name() {
+        let mut cache = CompletionCache::default();
+        // As when the tasks were listed with `maconomy tasks --job glob`
+        let job = Job {
+            number: "2".to_string(),
+            name: "Globex".to_string(),
+            customer: "Customer".to_string(),
+        };
+
+        cache.set_tasks(&job, &[]);
+
+        assert_eq!(cache.job_names(), vec!["Globex"]);
+    }
+
+    #[test]
+    fn lists_task_names() {
+        let cache = create_cache();
+
+        assert_eq!(
+            cache.task_names(None),
+            vec!["Development", "Meetings", "Support"]
+        );
+        assert_eq!(
+            cache.task_names(Some("acme")),
+            vec!["Development", "Support"]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_empty_cache() {
+        let cache = CompletionCache::load("/nonexistent/completion_cache.json");
+
+        assert!(cache.job_names().is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+//! Helpers for the files that maconomy keeps next to the cookie, like the journal and the timer
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// The path of `file_name` in the same directory as the cookie
+pub(crate) fn next_to(cookie_path: &str, file_name: &str) -> Result<PathBuf> {
+    let cookie_path = shellexpand::full(cookie_path).context("Failed to expand cookie path")?;
+    let directory = Path::new(cookie_path.as_ref())
+        .parent()
+        .unwrap_or(Path::new("."));
+
+    Ok(directory.join(file_name))
+}
+
+/// The contents of the file, or `None` if it doesn't exist yet. `what` names the file in errors
+pub(crate) fn read(path: &Path, what: &str) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Failed to read {what} {}", path.display())),
+    }
+}
+
+/// Creates the directories that the file is in, if they don't exist yet
+pub(crate) fn create_directory(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directories for {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// A temporary directory for the tests of a store, unique to this process
+#[cfg(test)]
+pub(crate) fn test_directory(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("maconomy_{name}_{}", std::process::id()))
+}
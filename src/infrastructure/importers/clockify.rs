@@ -0,0 +1,154 @@
+//! Time entries of Clockify, exported as a detailed report in CSV or JSON, or from the API as JSON
+
+use super::{date_time::parse_rfc3339, report_csv};
+use crate::domain::models::{
+    import::ImportError,
+    import_rule::{Activities, Activity, DateOrder},
+};
+use serde::Deserialize;
+
+/// A detailed report in JSON has its entries in `timeentries`, while the API returns a list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Export {
+    Report {
+        #[serde(alias = "timeEntries")]
+        timeentries: Vec<TimeEntry>,
+    },
+    Entries(Vec<TimeEntry>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeEntry {
+    #[serde(default)]
+    description: Option<String>,
+    time_interval: TimeInterval,
+    /// Only in reports, or from the API with `hydrated=true`
+    #[serde(default)]
+    project_name: Option<String>,
+    #[serde(default)]
+    project: Option<Named>,
+    #[serde(default)]
+    tags: Option<Vec<Named>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimeInterval {
+    start: String,
+    /// Missing while the entry is being tracked
+    end: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+/// Reads a detailed report in CSV or JSON, or time entries as returned by the API. Dates with
+/// slashes in a CSV report are read in `date_order`
+pub(crate) fn read_activities(
+    export: &str,
+    date_order: Option<DateOrder>,
+) -> Result<Activities, Vec<ImportError>> {
+    if report_csv::is_csv(export) {
+        return report_csv::read_activities(export, date_order);
+    }
+
+    let export: Export = serde_json::from_str(export).map_err(|err| {
+        let message = format!("Not a Clockify report or list of time entries: {err}");
+        vec![ImportError::new("file", message)]
+    })?;
+    let entries = match export {
+        Export::Report { timeentries } => timeentries,
+        Export::Entries(entries) => entries,
+    };
+
+    let mut result = Activities::default();
+    let mut errors = Vec::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        let origin = format!("time entry {}", i + 1);
+        let interval = &entry.time_interval;
+        let Some(start) = parse_rfc3339(&interval.start) else {
+            let message = format!("Invalid start '{}'", interval.start);
+            errors.push(ImportError::new(origin, message));
+            continue;
+        };
+        let Some(end) = &interval.end else {
+            result
+                .skipped
+                .push(format!("{origin}: still being tracked"));
+            continue;
+        };
+        let Some(end) = parse_rfc3339(end) else {
+            errors.push(ImportError::new(origin, format!("Invalid end '{end}'")));
+            continue;
+        };
+
+        result.activities.push(Activity {
+            date: start.date(),
+            duration: end - start,
+            summary: entry.description.unwrap_or_default(),
+            project: entry
+                .project_name
+                .or(entry.project.map(|project| project.name)),
+            categories: entry
+                .tags
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tag| tag.name)
+                .collect(),
+            origin,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn reads_detailed_report() {
+        let export = r#"{
+            "totals": [],
+            "timeentries": [
+                {
+                    "description": "Fixed bugs",
+                    "timeInterval": {"start": "2024-10-21T08:00:00+02:00", "end": "2024-10-21T09:30:00+02:00", "duration": 5400},
+                    "projectName": "Acme Website",
+                    "tags": [{"name": "dev"}, {"name": "urgent"}]
+                }
+            ]
+        }"#;
+
+        let result = read_activities(export, None).unwrap();
+
+        let activity = &result.activities[0];
+        assert_eq!(activity.summary, "Fixed bugs");
+        assert_eq!(activity.duration, TimeDelta::minutes(90));
+        assert_eq!(activity.project.as_deref(), Some("Acme Website"));
+        assert_eq!(activity.categories, ["dev", "urgent"]);
+    }
+
+    #[test]
+    fn reads_time_entries() {
+        let export = r#"[
+            {"description": "Standup", "timeInterval": {"start": "2024-10-22T09:00:00Z", "end": "2024-10-22T09:15:00Z"}, "project": {"name": "Acme"}, "tags": null},
+            {"description": "Running", "timeInterval": {"start": "2024-10-23T09:00:00Z", "end": null}}
+        ]"#;
+
+        let result = read_activities(export, None).unwrap();
+
+        assert_eq!(result.activities.len(), 1);
+        assert_eq!(result.activities[0].project.as_deref(), Some("Acme"));
+        assert_eq!(result.activities[0].duration, TimeDelta::minutes(15));
+        assert_eq!(result.skipped, ["time entry 2: still being tracked"]);
+    }
+}
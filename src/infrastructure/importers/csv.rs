@@ -0,0 +1,162 @@
+//! Time entries exported from other tools as CSV, with the columns `date`, `job`, `task`, `hours`
+//! and optionally `comment`
+
+use crate::domain::models::import::{ImportError, ImportedEntry};
+use chrono::NaiveDate;
+use serde::{de::DeserializeOwned, Deserialize};
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    date: String,
+    job: String,
+    task: String,
+    hours: String,
+    #[serde(default)]
+    comment: Option<String>,
+}
+
+/// Reads the entries of a CSV file with a header row. Columns may be in any order, and the names
+/// in the header are case-insensitive. Every row that can't be read is reported
+pub(crate) fn read_entries(
+    reader: impl std::io::Read,
+) -> Result<Vec<ImportedEntry>, Vec<ImportError>> {
+    read_records(reader, parse_record)
+}
+
+/// Reads the rows of a CSV file with a header row as `R`, by the names in the header, which are
+/// case-insensitive, and parses them with `parse`, which gets the origin of the row for reporting
+/// errors. Every row that can't be read or parsed is reported
+pub(super) fn read_records<R: DeserializeOwned, T>(
+    reader: impl std::io::Read,
+    mut parse: impl FnMut(R, String) -> Result<T, ImportError>,
+) -> Result<Vec<T>, Vec<ImportError>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+
+    let headers = reader
+        .headers()
+        .map_err(|err| vec![ImportError::new("header", err.to_string())])?
+        .iter()
+        .map(|header| header.to_lowercase())
+        .collect::<csv::StringRecord>();
+
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        let result = record
+            .map_err(|err| {
+                let line = err.position().map_or(0, |position| position.line());
+                ImportError::new(format!("row {line}"), err.to_string())
+            })
+            .and_then(|record| {
+                let line = record.position().map_or(0, |position| position.line());
+                let origin = format!("row {line}");
+                let record = record
+                    .deserialize(Some(&headers))
+                    .map_err(|err| ImportError::new(&origin, err.to_string()))?;
+                parse(record, origin)
+            });
+
+        match result {
+            Ok(value) => parsed.push(value),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(errors)
+    }
+}
+
+fn parse_record(record: Record, origin: String) -> Result<ImportedEntry, ImportError> {
+    let date = NaiveDate::parse_from_str(&record.date, "%Y-%m-%d").map_err(|_| {
+        let message = format!(
+            "Invalid date '{}', expected for example \"2026-10-14\"",
+            record.date
+        );
+        ImportError::new(&origin, message)
+    })?;
+    let hours = record.hours.parse().map_err(|err| {
+        let message = format!("Invalid hours '{}': {err}", record.hours);
+        ImportError::new(&origin, message)
+    })?;
+    if record.job.is_empty() || record.task.is_empty() {
+        return Err(ImportError::new(&origin, "Both job and task are required"));
+    }
+
+    Ok(ImportedEntry {
+        date,
+        job: record.job,
+        task: record.task,
+        hours,
+        comment: record.comment.filter(|comment| !comment.is_empty()),
+        origin,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::hours::Hours;
+
+    #[test]
+    fn reads_entries() {
+        let csv = "\
+Date,Job,Task,Hours,Comment
+2024-10-21,Acme,Development,4:30,Fixed bugs
+2024-10-22, Globex , Meetings ,1.5,
+";
+
+        let entries = read_entries(csv.as_bytes()).unwrap();
+
+        let expected = [
+            ImportedEntry {
+                date: "2024-10-21".parse().unwrap(),
+                job: "Acme".to_string(),
+                task: "Development".to_string(),
+                hours: Hours::from_minutes(4 * 60 + 30),
+                comment: Some("Fixed bugs".to_string()),
+                origin: "row 2".to_string(),
+            },
+            ImportedEntry {
+                date: "2024-10-22".parse().unwrap(),
+                job: "Globex".to_string(),
+                task: "Meetings".to_string(),
+                hours: Hours::from_minutes(90),
+                comment: None,
+                origin: "row 3".to_string(),
+            },
+        ];
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn comment_column_is_optional() {
+        let csv = "task,job,date,hours\nDevelopment,Acme,2024-10-21,8\n";
+
+        let entries = read_entries(csv.as_bytes()).unwrap();
+
+        assert_eq!(entries[0].job, "Acme");
+        assert_eq!(entries[0].comment, None);
+    }
+
+    #[test]
+    fn reports_every_invalid_row() {
+        let csv = "\
+date,job,task,hours
+2024-10-21,Acme,Development,8
+21/10/2024,Acme,Development,8
+2024-10-22,Acme,Development,lots
+2024-10-23,Acme,,8
+";
+
+        let errors = read_entries(csv.as_bytes()).unwrap_err();
+
+        let origins: Vec<_> = errors.iter().map(|err| err.origin.as_str()).collect();
+        assert_eq!(origins, ["row 3", "row 4", "row 5"]);
+    }
+}
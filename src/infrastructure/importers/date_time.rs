@@ -0,0 +1,140 @@
+//! Dates and times in the formats that time tracking tools export. Times are converted to local
+//! time, since that's the time that hours are registered in
+
+use crate::domain::models::import_rule::DateOrder;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+
+/// The basic ISO 8601 format of iCalendar and timewarrior, like "20241021T090000"
+pub(crate) const BASIC_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%d.%m.%Y"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+pub(crate) fn utc_to_local(utc: NaiveDateTime) -> NaiveDateTime {
+    DateTime::<Utc>::from_naive_utc_and_offset(utc, Utc)
+        .with_timezone(&Local)
+        .naive_local()
+}
+
+/// Parses a time with an offset, like "2024-10-21T09:00:00+02:00" or "2024-10-21T07:00:00Z"
+pub(crate) fn parse_rfc3339(value: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|time| time.with_timezone(&Local).naive_local())
+}
+
+/// Why a date couldn't be parsed
+#[derive(Debug, PartialEq)]
+pub(crate) enum DateError {
+    Invalid,
+    /// A date with slashes that could be either day or month first, such as "03/04/2024"
+    Ambiguous,
+}
+
+/// Parses dates like "2024-10-21", "21.10.2024" or "10/21/2024". Dates with slashes are read in
+/// `order`, or without an order, only if they can be read one way
+pub(crate) fn parse_date(value: &str, order: Option<DateOrder>) -> Result<NaiveDate, DateError> {
+    if let Some(date) = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+    {
+        return Ok(date);
+    }
+
+    let month_first = NaiveDate::parse_from_str(value, "%m/%d/%Y").ok();
+    let day_first = NaiveDate::parse_from_str(value, "%d/%m/%Y").ok();
+    match (order, month_first, day_first) {
+        (Some(DateOrder::MonthFirst), Some(date), _) => Ok(date),
+        (Some(DateOrder::DayFirst), _, Some(date)) => Ok(date),
+        (Some(_), _, _) | (None, None, None) => Err(DateError::Invalid),
+        (None, Some(month_first), Some(day_first)) if month_first != day_first => {
+            Err(DateError::Ambiguous)
+        }
+        (None, Some(date), _) | (None, None, Some(date)) => Ok(date),
+    }
+}
+
+/// Parses times like "09:00", "09:00:00" or "9:00:00 AM"
+pub(crate) fn parse_time(value: &str) -> Option<NaiveTime> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+}
+
+/// Parses durations like "1:30:00" or "1:30"
+pub(crate) fn parse_clock_duration(value: &str) -> Option<TimeDelta> {
+    let mut parts = value.split(':');
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let seconds: i64 = parts
+        .next()
+        .map_or(Some(0), |seconds| seconds.parse().ok())?;
+    if parts.next().is_some() || minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    Some(TimeDelta::seconds(hours * 3600 + minutes * 60 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_dates_and_times() {
+        let date = NaiveDate::from_ymd_opt(2024, 10, 21).unwrap();
+        assert_eq!(parse_date("2024-10-21", None), Ok(date));
+        assert_eq!(parse_date("10/21/2024", None), Ok(date));
+        assert_eq!(parse_date("21/10/2024", None), Ok(date));
+        assert_eq!(parse_date("21.10.2024", None), Ok(date));
+        assert_eq!(parse_date("2024-21-10", None), Err(DateError::Invalid));
+
+        let time = NaiveTime::from_hms_opt(13, 5, 0);
+        assert_eq!(parse_time("13:05"), time);
+        assert_eq!(parse_time("13:05:00"), time);
+        assert_eq!(parse_time("1:05:00 PM"), time);
+        assert_eq!(parse_time("1:05 pm"), time);
+        assert_eq!(parse_time("25:00"), None);
+    }
+
+    #[test]
+    fn parses_ambiguous_dates_in_configured_order() {
+        let march = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let april = NaiveDate::from_ymd_opt(2024, 4, 3).unwrap();
+
+        assert_eq!(parse_date("03/04/2024", None), Err(DateError::Ambiguous));
+        assert_eq!(
+            parse_date("03/04/2024", Some(DateOrder::MonthFirst)),
+            Ok(march)
+        );
+        assert_eq!(
+            parse_date("03/04/2024", Some(DateOrder::DayFirst)),
+            Ok(april)
+        );
+        // The same date either way
+        assert_eq!(
+            parse_date("03/03/2024", None),
+            Ok(NaiveDate::from_ymd_opt(2024, 3, 3).unwrap())
+        );
+        assert_eq!(
+            parse_date("10/21/2024", Some(DateOrder::DayFirst)),
+            Err(DateError::Invalid)
+        );
+    }
+
+    #[test]
+    fn parses_clock_durations() {
+        assert_eq!(
+            parse_clock_duration("1:30:00"),
+            Some(TimeDelta::minutes(90))
+        );
+        assert_eq!(parse_clock_duration("0:45"), Some(TimeDelta::minutes(45)));
+        assert_eq!(
+            parse_clock_duration("10:00:30"),
+            Some(TimeDelta::seconds(36030))
+        );
+        for input in ["", "1", "1:60", "1:00:00:00", "1.5"] {
+            assert_eq!(parse_clock_duration(input), None, "Accepted {input}");
+        }
+    }
+}
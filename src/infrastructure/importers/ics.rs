@@ -0,0 +1,878 @@
+//! Events of an iCalendar (.ics) file, such as one exported from Outlook or Google Calendar
+
+use super::date_time::{utc_to_local, BASIC_FORMAT};
+use crate::domain::models::{
+    import::ImportError,
+    import_rule::{Activities, Activity},
+    week::WeekNumber,
+};
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+
+#[derive(Debug, Default, Clone)]
+struct Event {
+    uid: String,
+    summary: String,
+    categories: Vec<String>,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    duration: Option<TimeDelta>,
+    cancelled: bool,
+    recurrence: Option<Recurrence>,
+    /// Occurrences in addition to those of `recurrence`, from `RDATE`
+    extra_dates: Vec<EventTime>,
+    /// Occurrences that are left out, from `EXDATE`
+    exceptions: Vec<EventTime>,
+    /// The start of the occurrence of a recurring event with the same UID that this event
+    /// replaces
+    recurrence_id: Option<EventTime>,
+    /// The line of `BEGIN:VEVENT`, for reporting errors
+    line: usize,
+}
+
+/// The rule of a recurring event, from `RRULE`
+#[derive(Debug, Clone, PartialEq)]
+struct Recurrence {
+    frequency: String,
+    interval: u32,
+    count: Option<usize>,
+    /// The last time that an occurrence may start, inclusive
+    until: Option<EventTime>,
+    /// The days of the week of the occurrences, from `BYDAY`
+    weekdays: Vec<Weekday>,
+    /// Whether the rule has parts that aren't supported, such as `BYMONTH` or `BYDAY=1MO`
+    unsupported: bool,
+}
+
+/// What becomes of an event
+enum Outcome {
+    Activity(Activity),
+    /// Why the event is left out
+    Skipped(String),
+    OutsideWeek,
+}
+
+/// Parameters of a property, such as `TZID` in "DTSTART;TZID=Europe/Copenhagen:20241021T090000",
+/// with upper case names
+type Parameters = Vec<(String, String)>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EventTime {
+    AllDay(NaiveDate),
+    /// Times are in local time, including times with a `TZID`, which are assumed to be in the same
+    /// time zone as this computer
+    Local(NaiveDateTime),
+}
+
+/// Reads the events of a calendar that start in `week`. Daily and weekly recurring events are
+/// expanded to their occurrences in the week, while other recurring events, all-day and cancelled
+/// events are skipped. Every event that can't be read is reported
+pub(crate) fn read_activities(
+    calendar: &str,
+    week: &WeekNumber,
+) -> Result<Activities, Vec<ImportError>> {
+    let events = read_events(calendar)?;
+    // Occurrences of recurring events that are replaced by other events, by UID
+    let replaced: Vec<(&str, EventTime)> = events
+        .iter()
+        .filter_map(|event| Some((event.uid.as_str(), event.recurrence_id?)))
+        .collect();
+
+    let mut result = Activities::default();
+    let mut errors = Vec::new();
+    for event in &events {
+        let occurrences = match occurrences(event, week, &replaced) {
+            Ok(occurrences) => occurrences,
+            Err(reason) => {
+                result.skipped.push(reason);
+                continue;
+            }
+        };
+        for occurrence in occurrences {
+            match to_activity(occurrence, week) {
+                Ok(Outcome::Activity(activity)) => result.activities.push(activity),
+                Ok(Outcome::Skipped(reason)) => result.skipped.push(reason),
+                Ok(Outcome::OutsideWeek) => (),
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+fn to_activity(event: Event, week: &WeekNumber) -> Result<Outcome, ImportError> {
+    let describe = |date: Option<NaiveDate>| match date {
+        Some(date) => format!("event '{}' on {date}", event.summary),
+        None => format!("event '{}'", event.summary),
+    };
+    let start = match event.start {
+        None => {
+            let origin = format!("{} (line {})", describe(None), event.line);
+            return Err(ImportError::new(origin, "Missing DTSTART"));
+        }
+        Some(EventTime::AllDay(date)) if WeekNumber::of(date) != *week => {
+            return Ok(Outcome::OutsideWeek);
+        }
+        Some(EventTime::AllDay(date)) => {
+            let reason = format!("{}: all-day event", describe(Some(date)));
+            return Ok(Outcome::Skipped(reason));
+        }
+        Some(EventTime::Local(start)) => start,
+    };
+    if WeekNumber::of(start.date()) != *week {
+        return Ok(Outcome::OutsideWeek);
+    }
+    let origin = describe(Some(start.date()));
+
+    if event.cancelled {
+        return Ok(Outcome::Skipped(format!("{origin}: cancelled")));
+    }
+
+    let end = match (event.end, event.duration) {
+        (Some(EventTime::Local(end)), _) => end,
+        (Some(EventTime::AllDay(_)), _) => {
+            return Err(ImportError::new(
+                origin,
+                "DTEND is a date, but DTSTART has a time",
+            ));
+        }
+        (None, Some(duration)) => start + duration,
+        (None, None) => start,
+    };
+
+    Ok(Outcome::Activity(Activity {
+        date: start.date(),
+        duration: end - start,
+        summary: event.summary,
+        project: None,
+        categories: event.categories,
+        origin,
+    }))
+}
+
+/// The occurrences of `event` that may be in `week`, as events of their own. An event that doesn't
+/// recur is its only occurrence. Returns the reason if the event recurs in a way that isn't
+/// supported
+fn occurrences(
+    event: &Event,
+    week: &WeekNumber,
+    replaced: &[(&str, EventTime)],
+) -> Result<Vec<Event>, String> {
+    let Some(start) = event.start else {
+        return Ok(vec![event.clone()]);
+    };
+    let Some(first_day) = week.first_day() else {
+        return Ok(Vec::new());
+    };
+    let last_day = first_day + Days::new(6);
+    let is_within_until = |date: NaiveDate| {
+        let until = event.recurrence.as_ref().and_then(|rule| rule.until);
+        match (until, start) {
+            (None, _) => true,
+            (Some(EventTime::AllDay(until)), _) => date <= until,
+            (Some(EventTime::Local(until)), EventTime::Local(start)) => {
+                date.and_time(start.time()) <= until
+            }
+            (Some(EventTime::Local(until)), EventTime::AllDay(_)) => date <= until.date(),
+        }
+    };
+
+    let mut starts = match &event.recurrence {
+        None => vec![start],
+        Some(_) if start.date() > last_day || !is_within_until(first_day) => Vec::new(),
+        Some(rule)
+            if rule.unsupported || !matches!(rule.frequency.as_str(), "DAILY" | "WEEKLY") =>
+        {
+            return Err(format!(
+                "event '{}': only daily and weekly recurring events are supported, export the \
+                 occurrences instead",
+                event.summary
+            ));
+        }
+        Some(rule) => rule
+            .dates(start.date())
+            .take_while(|&date| is_within_until(date))
+            .take(rule.count.unwrap_or(usize::MAX))
+            .take_while(|&date| date <= last_day)
+            .filter(|&date| date >= first_day)
+            .map(|date| start.on(date))
+            .collect(),
+    };
+    starts.extend(&event.extra_dates);
+    starts.sort_by_key(EventTime::as_local);
+    starts.dedup();
+
+    let is_recurring = event.recurrence.is_some() || !event.extra_dates.is_empty();
+    let is_left_out = |time: EventTime| {
+        let is_replaced = replaced
+            .iter()
+            .any(|&(uid, replaced)| uid == event.uid && replaced.matches(time));
+        is_recurring && (is_replaced || event.exceptions.iter().any(|ex| ex.matches(time)))
+    };
+    let occurrences = starts
+        .into_iter()
+        .filter(|&occurrence| !is_left_out(occurrence))
+        .map(|occurrence| {
+            let offset = occurrence.as_local() - start.as_local();
+            Event {
+                start: Some(occurrence),
+                end: event.end.map(|end| end.shifted(offset)),
+                ..event.clone()
+            }
+        })
+        .collect();
+
+    Ok(occurrences)
+}
+
+impl Recurrence {
+    /// Every date that the rule gives, from `start` on, without an end
+    fn dates(&self, start: NaiveDate) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        let interval = u64::from(self.interval);
+        let is_on_weekday = move |date: &NaiveDate| {
+            self.weekdays.is_empty() || self.weekdays.contains(&date.weekday())
+        };
+
+        if self.frequency == "DAILY" {
+            let dates = (0..).map(move |i| start + Days::new(i * interval));
+            return Box::new(dates.filter(is_on_weekday));
+        }
+
+        let mut weekdays = self.weekdays.clone();
+        if weekdays.is_empty() {
+            weekdays.push(start.weekday());
+        }
+        weekdays.sort_by_key(Weekday::num_days_from_monday);
+        let monday = start - Days::new(start.weekday().num_days_from_monday().into());
+        let dates = (0..).flat_map(move |i| {
+            let week = monday + Days::new(i * interval * 7);
+            weekdays
+                .clone()
+                .into_iter()
+                .map(move |day| week + Days::new(day.num_days_from_monday().into()))
+        });
+        Box::new(dates.filter(move |&date| date >= start))
+    }
+}
+
+impl EventTime {
+    fn date(&self) -> NaiveDate {
+        match self {
+            EventTime::AllDay(date) => *date,
+            EventTime::Local(time) => time.date(),
+        }
+    }
+
+    fn as_local(&self) -> NaiveDateTime {
+        match self {
+            EventTime::AllDay(date) => date.and_time(NaiveTime::MIN),
+            EventTime::Local(time) => *time,
+        }
+    }
+
+    /// The same time of day on `date`
+    fn on(self, date: NaiveDate) -> Self {
+        match self {
+            EventTime::AllDay(_) => EventTime::AllDay(date),
+            EventTime::Local(time) => EventTime::Local(date.and_time(time.time())),
+        }
+    }
+
+    fn shifted(self, offset: TimeDelta) -> Self {
+        match self {
+            EventTime::AllDay(_) => EventTime::AllDay((self.as_local() + offset).date()),
+            EventTime::Local(time) => EventTime::Local(time + offset),
+        }
+    }
+
+    /// Whether an `EXDATE` or `RECURRENCE-ID` of `self` refers to the occurrence at `time`. A
+    /// date refers to every occurrence on that day
+    fn matches(&self, time: EventTime) -> bool {
+        match self {
+            EventTime::AllDay(date) => *date == time.date(),
+            EventTime::Local(_) => *self == time,
+        }
+    }
+}
+
+fn read_events(calendar: &str) -> Result<Vec<Event>, Vec<ImportError>> {
+    let mut events = Vec::new();
+    let mut errors = Vec::new();
+    let mut event: Option<Event> = None;
+    // Components within events, such as alarms, have properties of their own
+    let mut nested = 0;
+
+    for (line_number, line) in unfold(calendar) {
+        let Some((name, parameters, value)) = split_property(&line) else {
+            if !line.trim().is_empty() {
+                errors.push(ImportError::new(
+                    format!("line {line_number}"),
+                    "Invalid line, expected a property such as \"SUMMARY:Standup\"",
+                ));
+            }
+            continue;
+        };
+
+        match (name.as_str(), value.to_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") if event.is_none() => {
+                event = Some(Event {
+                    line: line_number,
+                    ..Default::default()
+                });
+            }
+            ("BEGIN", _) if event.is_some() => nested += 1,
+            ("END", "VEVENT") if nested == 0 => events.extend(event.take()),
+            ("END", _) if event.is_some() => nested -= 1,
+            _ => {
+                let Some(event) = event.as_mut().filter(|_| nested == 0) else {
+                    continue;
+                };
+                if let Err(message) = read_property(event, &name, &parameters, value) {
+                    errors.push(ImportError::new(format!("line {line_number}"), message));
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(events)
+    } else {
+        Err(errors)
+    }
+}
+
+fn read_property(
+    event: &mut Event,
+    name: &str,
+    parameters: &Parameters,
+    value: &str,
+) -> Result<(), String> {
+    match name {
+        "UID" => event.uid = value.to_string(),
+        "SUMMARY" => event.summary = unescape(value),
+        "CATEGORIES" => event
+            .categories
+            .extend(split_list(value).iter().map(|category| unescape(category))),
+        "DTSTART" => event.start = Some(parse_time(parameters, value)?),
+        "DTEND" => event.end = Some(parse_time(parameters, value)?),
+        "DURATION" => event.duration = Some(parse_duration(value)?),
+        "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+        "RRULE" => event.recurrence = Some(parse_recurrence(value)?),
+        "RDATE" => event.extra_dates.extend(parse_times(parameters, value)?),
+        "EXDATE" => event.exceptions.extend(parse_times(parameters, value)?),
+        "RECURRENCE-ID" => event.recurrence_id = Some(parse_time(parameters, value)?),
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Joins lines that are folded over several lines, which start with a space or tab. Returns the
+/// 1-indexed number of the first line of each
+fn unfold(calendar: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (i, line) in calendar.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, last))) => last.push_str(continuation),
+            _ => lines.push((i + 1, line.to_string())),
+        }
+    }
+
+    lines
+}
+
+/// Splits "NAME;PARAM=VALUE:value" into the upper case name, the parameters and the value
+fn split_property(line: &str) -> Option<(String, Parameters, &str)> {
+    // Parameter values may contain colons when they are quoted
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        match c {
+            '"' => quoted = !quoted,
+            ':' if !quoted => return Some(i),
+            _ => (),
+        }
+        None
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    let parameters = parts
+        .filter_map(|parameter| parameter.split_once('='))
+        .map(|(key, value)| (key.to_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+
+    Some((name, parameters, value))
+}
+
+fn parse_time(parameters: &Parameters, value: &str) -> Result<EventTime, String> {
+    let is_date = parameters
+        .iter()
+        .any(|(key, value)| key == "VALUE" && value.eq_ignore_ascii_case("DATE"));
+    if is_date || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(EventTime::AllDay)
+            .map_err(|_| format!("Invalid date '{value}'"));
+    }
+
+    let invalid = || format!("Invalid date and time '{value}'");
+    let time = match value.strip_suffix(['Z', 'z']) {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, BASIC_FORMAT)
+            .map(utc_to_local)
+            .map_err(|_| invalid())?,
+        None => NaiveDateTime::parse_from_str(value, BASIC_FORMAT).map_err(|_| invalid())?,
+    };
+
+    Ok(EventTime::Local(time))
+}
+
+/// Parses a list of times, such as the value of `EXDATE`
+fn parse_times(parameters: &Parameters, value: &str) -> Result<Vec<EventTime>, String> {
+    let is_period = parameters
+        .iter()
+        .any(|(key, value)| key == "VALUE" && value.eq_ignore_ascii_case("PERIOD"));
+    if is_period {
+        return Err(format!("Periods like '{value}' aren't supported"));
+    }
+
+    split_list(value)
+        .into_iter()
+        .map(|time| parse_time(parameters, time))
+        .collect()
+}
+
+/// Parses rules such as "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20241231T235959Z"
+fn parse_recurrence(value: &str) -> Result<Recurrence, String> {
+    let invalid = |part: &str| format!("Invalid part '{part}' of RRULE '{value}'");
+    let mut recurrence = Recurrence {
+        frequency: String::new(),
+        interval: 1,
+        count: None,
+        until: None,
+        weekdays: Vec::new(),
+        unsupported: false,
+    };
+
+    for part in value.split(';').filter(|part| !part.is_empty()) {
+        let (key, rule_value) = part.split_once('=').ok_or_else(|| invalid(part))?;
+        match key.to_uppercase().as_str() {
+            "FREQ" => recurrence.frequency = rule_value.to_uppercase(),
+            "INTERVAL" => {
+                recurrence.interval = rule_value
+                    .parse()
+                    .ok()
+                    .filter(|&interval| interval > 0)
+                    .ok_or_else(|| invalid(part))?;
+            }
+            "COUNT" => recurrence.count = Some(rule_value.parse().map_err(|_| invalid(part))?),
+            "UNTIL" => recurrence.until = Some(parse_time(&Vec::new(), rule_value)?),
+            "BYDAY" => {
+                for day in rule_value.split(',') {
+                    match parse_weekday(day) {
+                        Some(weekday) => recurrence.weekdays.push(weekday),
+                        // Such as "1MO" for the first Monday of a month
+                        None => recurrence.unsupported = true,
+                    }
+                }
+            }
+            // The first day of the week only matters to weekly rules on several days with an
+            // interval, and Monday is the default
+            "WKST" => (),
+            _ => recurrence.unsupported = true,
+        }
+    }
+    if recurrence.frequency.is_empty() {
+        return Err(format!("Missing FREQ in RRULE '{value}'"));
+    }
+
+    Ok(recurrence)
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    let weekday = match day.trim().to_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    };
+
+    Some(weekday)
+}
+
+/// Parses durations such as "PT1H30M", "P1D" or "P1W"
+fn parse_duration(value: &str) -> Result<TimeDelta, String> {
+    let invalid = || format!("Invalid duration '{value}'");
+    let unsigned = value.strip_prefix('+').unwrap_or(value);
+    let (negative, unsigned) = match unsigned.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, unsigned),
+    };
+    let designators = unsigned.strip_prefix('P').ok_or_else(invalid)?;
+
+    let mut seconds = 0;
+    let mut units = 0;
+    let mut number = String::new();
+    let mut in_time = false;
+    for c in designators.chars() {
+        let unit = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            }
+            'T' if !in_time && number.is_empty() => {
+                in_time = true;
+                continue;
+            }
+            'W' if !in_time => 7 * 24 * 3600,
+            'D' if !in_time => 24 * 3600,
+            'H' if in_time => 3600,
+            'M' if in_time => 60,
+            'S' if in_time => 1,
+            _ => return Err(invalid()),
+        };
+        let amount: i64 = number.parse().map_err(|_| invalid())?;
+        seconds += amount * unit;
+        units += 1;
+        number.clear();
+    }
+    if units == 0 || !number.is_empty() {
+        return Err(invalid());
+    }
+
+    let duration = TimeDelta::seconds(seconds);
+    Ok(if negative { -duration } else { duration })
+}
+
+/// Splits a list of values on the commas that aren't escaped
+fn split_list(value: &str) -> Vec<&str> {
+    let mut values = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                values.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    values.push(&value[start..]);
+
+    values
+        .into_iter()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push(' '),
+            Some(escaped) => unescaped.push(escaped),
+            None => (),
+        }
+    }
+
+    unescaped.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "\
+BEGIN:VCALENDAR
+VERSION:2.0
+PRODID:-//Test//EN
+BEGIN:VEVENT
+UID:1
+SUMMARY:Daily standup
+DTSTART:20241021T090000
+DTEND:20241021T091000
+BEGIN:VALARM
+ACTION:DISPLAY
+SUMMARY:Reminder
+TRIGGER:-PT5M
+END:VALARM
+END:VEVENT
+BEGIN:VEVENT
+UID:2
+SUMMARY:Sprint planning with the
+  whole team
+CATEGORIES:Acme,Planning
+DTSTART;TZID=\"Europe/Copenhagen\":20241022T130000
+DURATION:PT1H55M
+END:VEVENT
+BEGIN:VEVENT
+UID:3
+SUMMARY:Lunch
+DTSTART:20241022T113000
+DTEND:20241022T120000
+END:VEVENT
+BEGIN:VEVENT
+UID:4
+SUMMARY:Conference
+DTSTART;VALUE=DATE:20241023
+DTEND;VALUE=DATE:20241024
+END:VEVENT
+BEGIN:VEVENT
+UID:5
+SUMMARY:Sync every other week
+RRULE:FREQ=WEEKLY;INTERVAL=2
+DTSTART:20241010T100000
+DTEND:20241010T110000
+END:VEVENT
+BEGIN:VEVENT
+UID:6
+SUMMARY:Cancelled standup
+STATUS:CANCELLED
+DTSTART:20241025T090000
+DTEND:20241025T091500
+END:VEVENT
+BEGIN:VEVENT
+UID:7
+SUMMARY:Standup next week
+DTSTART:20241028T090000
+DTEND:20241028T091500
+END:VEVENT
+END:VCALENDAR
+";
+
+    fn week_43() -> WeekNumber {
+        WeekNumber::new(43, 2024).unwrap()
+    }
+
+    #[test]
+    fn reads_events_of_the_week() {
+        let result = read_activities(CALENDAR, &week_43()).unwrap();
+
+        let activities: Vec<_> = result
+            .activities
+            .iter()
+            .map(|activity| {
+                let minutes = activity.duration.num_minutes();
+                (
+                    activity.date.to_string(),
+                    activity.summary.as_str(),
+                    minutes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            activities,
+            [
+                ("2024-10-21".to_string(), "Daily standup", 10),
+                (
+                    "2024-10-22".to_string(),
+                    "Sprint planning with the whole team",
+                    115
+                ),
+                ("2024-10-22".to_string(), "Lunch", 30),
+                ("2024-10-24".to_string(), "Sync every other week", 60),
+            ]
+        );
+        assert_eq!(result.activities[1].categories, ["Acme", "Planning"]);
+        assert_eq!(
+            result.activities[1].origin,
+            "event 'Sprint planning with the whole team' on 2024-10-22"
+        );
+        insta::assert_debug_snapshot!(result.skipped);
+    }
+
+    fn origins_and_minutes(activities: &[Activity]) -> Vec<(&str, i64)> {
+        activities
+            .iter()
+            .map(|activity| (activity.origin.as_str(), activity.duration.num_minutes()))
+            .collect()
+    }
+
+    #[test]
+    fn expands_recurring_events() {
+        let calendar = "\
+BEGIN:VEVENT
+UID:standup
+SUMMARY:Standup
+DTSTART:20241014T091500
+DTEND:20241014T093000
+RRULE:FREQ=DAILY;BYDAY=MO,TU,WE,TH,FR;COUNT=8
+EXDATE:20241022T091500
+END:VEVENT
+BEGIN:VEVENT
+UID:standup
+RECURRENCE-ID:20241023T091500
+SUMMARY:Standup
+DTSTART:20241023T100000
+DTEND:20241023T103000
+END:VEVENT
+BEGIN:VEVENT
+UID:retro
+SUMMARY:Retro
+DTSTART:20241001T140000
+DTEND:20241001T150000
+RRULE:FREQ=WEEKLY;BYDAY=TU,TH;UNTIL=20241022T235959
+END:VEVENT
+BEGIN:VEVENT
+UID:old
+SUMMARY:Old sync
+DTSTART:20240903T100000
+DTEND:20240903T110000
+RRULE:FREQ=WEEKLY;UNTIL=20241001
+END:VEVENT
+BEGIN:VEVENT
+UID:review
+SUMMARY:Monthly review
+DTSTART:20241007T100000
+DTEND:20241007T110000
+RRULE:FREQ=MONTHLY;BYDAY=1MO
+END:VEVENT
+BEGIN:VEVENT
+UID:workshop
+SUMMARY:Workshop
+DTSTART:20241001T130000
+DTEND:20241001T140000
+RDATE:20241025T150000
+END:VEVENT
+";
+
+        let result = read_activities(calendar, &week_43()).unwrap();
+
+        assert_eq!(
+            origins_and_minutes(&result.activities),
+            [
+                ("event 'Standup' on 2024-10-21", 15),
+                ("event 'Standup' on 2024-10-23", 30),
+                ("event 'Retro' on 2024-10-22", 60),
+                ("event 'Workshop' on 2024-10-25", 60),
+            ]
+        );
+        assert_eq!(
+            result.skipped,
+            [
+                "event 'Monthly review': only daily and weekly recurring events are supported, \
+              export the occurrences instead"
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_recurrence_rules() {
+        let recurrence = parse_recurrence("FREQ=weekly;INTERVAL=2;BYDAY=MO,TH;COUNT=3").unwrap();
+
+        assert_eq!(
+            recurrence,
+            Recurrence {
+                frequency: "WEEKLY".to_string(),
+                interval: 2,
+                count: Some(3),
+                until: None,
+                weekdays: vec![Weekday::Mon, Weekday::Thu],
+                unsupported: false,
+            }
+        );
+        for input in ["INTERVAL=2", "FREQ=DAILY;INTERVAL=0", "FREQ=DAILY;COUNT"] {
+            assert!(parse_recurrence(input).is_err(), "Accepted {input}");
+        }
+    }
+
+    #[test]
+    fn reports_invalid_events() {
+        let calendar = "\
+BEGIN:VEVENT
+SUMMARY:Standup
+DTSTART:2024-10-21 09:00
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Standup
+DTSTART;VALUE=DATE:20241021
+DTEND:20241021T090000
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Standup
+DTSTART:20241021T100000
+DTEND;VALUE=DATE:20241022
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Standup
+END:VEVENT
+";
+
+        let errors = read_activities(calendar, &week_43()).unwrap_err();
+
+        assert_eq!(
+            errors,
+            [ImportError::new(
+                "line 3",
+                "Invalid date and time '2024-10-21 09:00'"
+            )]
+        );
+
+        let calendar = calendar.replace("2024-10-21 09:00", "20241021T090000");
+        let errors = read_activities(&calendar, &week_43()).unwrap_err();
+
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "event 'Standup' on 2024-10-21: DTEND is a date, but DTSTART has a time",
+                "event 'Standup' (line 15): Missing DTSTART",
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_durations() {
+        let inputs = [
+            ("PT1H30M", 90 * 60),
+            ("PT15M", 15 * 60),
+            ("P1D", 24 * 3600),
+            ("P1W", 7 * 24 * 3600),
+            ("P1DT2H", 26 * 3600),
+            ("-PT5M", -5 * 60),
+            ("PT45S", 45),
+        ];
+
+        for (input, seconds) in inputs {
+            assert_eq!(
+                parse_duration(input),
+                Ok(TimeDelta::seconds(seconds)),
+                "Failed on {input}"
+            );
+        }
+        for input in ["", "1H", "PT", "PT1", "P1H", "PT1D"] {
+            assert!(parse_duration(input).is_err(), "Accepted {input}");
+        }
+    }
+
+    #[test]
+    fn splits_escaped_lists() {
+        assert_eq!(
+            split_list(r"Acme, Planning\, internal,"),
+            ["Acme", r"Planning\, internal"]
+        );
+        assert_eq!(
+            unescape(r"Planning\, internal\; Q4\nnotes"),
+            "Planning, internal; Q4 notes"
+        );
+    }
+}
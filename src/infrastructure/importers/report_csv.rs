@@ -0,0 +1,190 @@
+//! Detailed reports of Toggl Track and Clockify, exported as CSV. Both have the columns
+//! `Project`, `Description`, `Tags`, `Start Date`, `Start Time`, `End Date`, `End Time` and
+//! `Duration`, in slightly different spellings
+
+use super::{
+    csv::read_records,
+    date_time::{parse_clock_duration, parse_date, parse_time, DateError},
+};
+use crate::domain::models::{
+    import::ImportError,
+    import_rule::{Activities, Activity, DateOrder},
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    project: Option<String>,
+    description: Option<String>,
+    /// Separated by commas
+    tags: Option<String>,
+    #[serde(rename = "start date")]
+    start_date: String,
+    #[serde(rename = "start time")]
+    start_time: String,
+    #[serde(rename = "end date")]
+    end_date: Option<String>,
+    #[serde(rename = "end time")]
+    end_time: Option<String>,
+    /// For example "01:30:00"
+    #[serde(alias = "duration (h)")]
+    duration: Option<String>,
+}
+
+/// Whether an export is a CSV report rather than JSON
+pub(super) fn is_csv(export: &str) -> bool {
+    !export.trim_start().starts_with(['[', '{'])
+}
+
+/// Reads the rows of a report. Column names are case-insensitive, and dates with slashes are read
+/// in `date_order`. Every row that can't be read is reported
+pub(crate) fn read_activities(
+    report: &str,
+    date_order: Option<DateOrder>,
+) -> Result<Activities, Vec<ImportError>> {
+    let activities = read_records(report.as_bytes(), |record, origin| {
+        parse_record(record, origin, date_order)
+    })?;
+
+    Ok(Activities {
+        activities,
+        skipped: Vec::new(),
+    })
+}
+
+fn parse_record(
+    record: Record,
+    origin: String,
+    date_order: Option<DateOrder>,
+) -> Result<Activity, ImportError> {
+    let invalid =
+        |name: &str, value: &str| ImportError::new(&origin, format!("Invalid {name} '{value}'"));
+    let date = |name: &str, value: &str| {
+        parse_date(value, date_order).map_err(|err| match err {
+            DateError::Invalid => invalid(name, value),
+            DateError::Ambiguous => {
+                let message = format!(
+                    "Ambiguous {name} '{value}', set `date_order` to \"day-first\" or \
+                     \"month-first\" in the configuration"
+                );
+                ImportError::new(&origin, message)
+            }
+        })
+    };
+    let start_date = date("start date", &record.start_date)?;
+    let start_time =
+        parse_time(&record.start_time).ok_or_else(|| invalid("start time", &record.start_time))?;
+    let start = start_date.and_time(start_time);
+
+    let duration = match (&record.end_date, &record.end_time, &record.duration) {
+        (Some(end_date), Some(end_time), _) => {
+            let end_date = date("end date", end_date)?;
+            let end_time = parse_time(end_time).ok_or_else(|| invalid("end time", end_time))?;
+            end_date.and_time(end_time) - start
+        }
+        (_, _, Some(duration)) => {
+            parse_clock_duration(duration).ok_or_else(|| invalid("duration", duration))?
+        }
+        _ => {
+            let message = "Either the end date and time or the duration is required";
+            return Err(ImportError::new(&origin, message));
+        }
+    };
+
+    let categories = record
+        .tags
+        .iter()
+        .flat_map(|tags| tags.split(','))
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    Ok(Activity {
+        date: start.date(),
+        duration,
+        summary: record.description.unwrap_or_default(),
+        project: record.project,
+        categories,
+        origin,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn reads_toggl_report() {
+        let report = "\
+User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags
+Jane,jane@example.com,Acme,Acme Website,,Fixed bugs,Yes,2024-10-21,08:00:00,2024-10-21,11:30:00,03:30:00,\"dev, urgent\"
+Jane,jane@example.com,,,,Standup,No,2024-10-22,09:00:00,2024-10-22,09:15:00,00:15:00,
+";
+
+        let result = read_activities(report, None).unwrap();
+
+        let expected = [
+            Activity {
+                date: "2024-10-21".parse().unwrap(),
+                duration: TimeDelta::minutes(3 * 60 + 30),
+                summary: "Fixed bugs".to_string(),
+                project: Some("Acme Website".to_string()),
+                categories: vec!["dev".to_string(), "urgent".to_string()],
+                origin: "row 2".to_string(),
+            },
+            Activity {
+                date: "2024-10-22".parse().unwrap(),
+                duration: TimeDelta::minutes(15),
+                summary: "Standup".to_string(),
+                project: None,
+                categories: Vec::new(),
+                origin: "row 3".to_string(),
+            },
+        ];
+        assert_eq!(result.activities, expected);
+    }
+
+    #[test]
+    fn reads_clockify_report() {
+        let report = "\
+Project,Client,Description,Task,User,Group,Email,Tags,Billable,Start Date,Start Time,Duration (h),Duration (decimal)
+Acme Website,Acme,Fixed bugs,,Jane,,jane@example.com,dev,Yes,10/21/2024,08:00:00 AM,01:30:00,1.50
+";
+
+        let result = read_activities(report, None).unwrap();
+
+        let activity = &result.activities[0];
+        assert_eq!(activity.date, "2024-10-21".parse().unwrap());
+        assert_eq!(activity.duration, TimeDelta::minutes(90));
+        assert_eq!(activity.project.as_deref(), Some("Acme Website"));
+        assert_eq!(activity.categories, ["dev"]);
+    }
+
+    #[test]
+    fn reports_every_invalid_row() {
+        let report = "\
+Description,Start date,Start time,End date,End time,Duration
+Fixed bugs,2024-10-21,08:00,2024-10-21,09:00,
+Fixed bugs,2024-21-10,08:00,2024-10-21,09:00,
+Fixed bugs,2024-10-21,08:00,,,
+Fixed bugs,2024-10-21,08:00,,,lots
+Fixed bugs,03/04/2024,08:00,,,01:00:00
+";
+
+        let errors = read_activities(report, None).unwrap_err();
+
+        let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            [
+                "row 3: Invalid start date '2024-21-10'",
+                "row 4: Either the end date and time or the duration is required",
+                "row 5: Invalid duration 'lots'",
+                "row 6: Ambiguous start date '03/04/2024', set `date_order` to \"day-first\" or \
+                 \"month-first\" in the configuration",
+            ]
+        );
+    }
+}
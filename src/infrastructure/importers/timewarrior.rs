@@ -0,0 +1,126 @@
+//! Intervals exported with `timew export`, as JSON
+
+use super::date_time::{utc_to_local, BASIC_FORMAT};
+use crate::domain::models::{
+    import::ImportError,
+    import_rule::{Activities, Activity},
+};
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Interval {
+    /// Only exported by newer versions of timewarrior
+    id: Option<u32>,
+    start: String,
+    /// Missing while the interval is being tracked
+    end: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    annotation: Option<String>,
+}
+
+/// Reads the intervals of an export. Tags are categories and annotations are summaries. Intervals
+/// that are still being tracked are skipped
+pub(crate) fn read_activities(export: &str) -> Result<Activities, Vec<ImportError>> {
+    let intervals: Vec<Interval> = serde_json::from_str(export).map_err(|err| {
+        let message = format!("Not a timewarrior export: {err}");
+        vec![ImportError::new("file", message)]
+    })?;
+
+    let mut result = Activities::default();
+    let mut errors = Vec::new();
+    for (i, interval) in intervals.into_iter().enumerate() {
+        let id = interval.id.unwrap_or(i as u32 + 1);
+        let Some(start) = parse_time(&interval.start) else {
+            let message = format!("Invalid start '{}'", interval.start);
+            errors.push(ImportError::new(format!("interval @{id}"), message));
+            continue;
+        };
+        let origin = format!("interval @{id} on {}", start.date());
+        let Some(end) = &interval.end else {
+            result
+                .skipped
+                .push(format!("{origin}: still being tracked"));
+            continue;
+        };
+        let Some(end) = parse_time(end) else {
+            errors.push(ImportError::new(origin, format!("Invalid end '{end}'")));
+            continue;
+        };
+
+        result.activities.push(Activity {
+            date: start.date(),
+            duration: end - start,
+            summary: interval.annotation.unwrap_or_default(),
+            project: None,
+            categories: interval.tags,
+            origin,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Timewarrior exports times in UTC, like "20241021T080000Z"
+fn parse_time(value: &str) -> Option<NaiveDateTime> {
+    let utc = value.strip_suffix('Z')?;
+    NaiveDateTime::parse_from_str(utc, BASIC_FORMAT)
+        .ok()
+        .map(utc_to_local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn reads_intervals() {
+        let export = r#"[
+            {"id": 3, "start": "20241021T080000Z", "end": "20241021T113000Z", "tags": ["acme", "dev"], "annotation": "Fixed bugs"},
+            {"start": "20241022T080000Z", "end": "20241022T090000Z"},
+            {"id": 1, "start": "20241023T080000Z", "tags": ["acme"]}
+        ]"#;
+
+        let result = read_activities(export).unwrap();
+
+        assert_eq!(result.activities.len(), 2);
+        let first = &result.activities[0];
+        assert_eq!(first.duration, TimeDelta::minutes(3 * 60 + 30));
+        assert_eq!(first.summary, "Fixed bugs");
+        assert_eq!(first.categories, ["acme", "dev"]);
+        assert!(first.origin.starts_with("interval @3 on "));
+        assert!(result.activities[1].origin.starts_with("interval @2 on "));
+        assert_eq!(result.skipped.len(), 1);
+        assert!(result.skipped[0].ends_with(": still being tracked"));
+    }
+
+    #[test]
+    fn reports_invalid_intervals() {
+        let export = r#"[
+            {"id": 1, "start": "2024-10-21 08:00", "end": "20241021T113000Z"},
+            {"id": 2, "start": "20241021T080000Z", "end": "soon"}
+        ]"#;
+
+        let errors = read_activities(export).unwrap_err();
+
+        assert_eq!(
+            errors[0],
+            ImportError::new("interval @1", "Invalid start '2024-10-21 08:00'")
+        );
+        assert!(errors[1].origin.starts_with("interval @2 on "));
+        assert_eq!(errors[1].message, "Invalid end 'soon'");
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let errors = read_activities("date,job,task,hours").unwrap_err();
+
+        assert_eq!(errors[0].origin, "file");
+    }
+}
@@ -0,0 +1,138 @@
+//! Time entries of Toggl Track, exported as a detailed report in CSV or from the API as JSON
+
+use super::{date_time::parse_rfc3339, report_csv};
+use crate::domain::models::{
+    import::ImportError,
+    import_rule::{Activities, Activity, DateOrder},
+};
+use chrono::TimeDelta;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct TimeEntry {
+    #[serde(default)]
+    description: Option<String>,
+    start: String,
+    /// Missing while the entry is being tracked
+    #[serde(default, alias = "end")]
+    stop: Option<String>,
+    /// In seconds. Negative while the entry is being tracked
+    #[serde(default)]
+    duration: Option<i64>,
+    /// The API only has the ID of the project, so names are only there if added by a script
+    #[serde(default, alias = "project_name")]
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// Reads a CSV report, or time entries as returned by `GET /api/v9/me/time_entries`. Dates with
+/// slashes in a report are read in `date_order`
+pub(crate) fn read_activities(
+    export: &str,
+    date_order: Option<DateOrder>,
+) -> Result<Activities, Vec<ImportError>> {
+    if report_csv::is_csv(export) {
+        return report_csv::read_activities(export, date_order);
+    }
+
+    let entries: Vec<TimeEntry> = serde_json::from_str(export).map_err(|err| {
+        let message = format!("Not a list of Toggl time entries: {err}");
+        vec![ImportError::new("file", message)]
+    })?;
+
+    let mut result = Activities::default();
+    let mut errors = Vec::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        let origin = format!("time entry {}", i + 1);
+        let Some(start) = parse_rfc3339(&entry.start) else {
+            errors.push(ImportError::new(
+                origin,
+                format!("Invalid start '{}'", entry.start),
+            ));
+            continue;
+        };
+        let duration = match (&entry.stop, entry.duration) {
+            (Some(stop), _) => match parse_rfc3339(stop) {
+                Some(stop) => stop - start,
+                None => {
+                    errors.push(ImportError::new(origin, format!("Invalid stop '{stop}'")));
+                    continue;
+                }
+            },
+            (None, Some(seconds)) if seconds >= 0 => TimeDelta::seconds(seconds),
+            (None, _) => {
+                result
+                    .skipped
+                    .push(format!("{origin}: still being tracked"));
+                continue;
+            }
+        };
+
+        result.activities.push(Activity {
+            date: start.date(),
+            duration,
+            summary: entry.description.unwrap_or_default(),
+            project: entry.project,
+            categories: entry.tags,
+            origin,
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(result)
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_time_entries() {
+        let export = r#"[
+            {"description": "Fixed bugs", "start": "2024-10-21T08:00:00+00:00", "stop": "2024-10-21T09:30:00+00:00", "duration": 5400, "project_id": 42, "tags": ["dev"]},
+            {"description": "Standup", "start": "2024-10-22T09:00:00Z", "duration": 900, "project": "Acme"},
+            {"description": "Running", "start": "2024-10-23T09:00:00Z", "duration": -1729500000}
+        ]"#;
+
+        let result = read_activities(export, None).unwrap();
+
+        assert_eq!(result.activities.len(), 2);
+        let first = &result.activities[0];
+        assert_eq!(first.summary, "Fixed bugs");
+        assert_eq!(first.duration, TimeDelta::minutes(90));
+        assert_eq!(first.project, None);
+        assert_eq!(first.categories, ["dev"]);
+        assert_eq!(first.origin, "time entry 1");
+        assert_eq!(result.activities[1].duration, TimeDelta::minutes(15));
+        assert_eq!(result.activities[1].project.as_deref(), Some("Acme"));
+        assert_eq!(result.skipped, ["time entry 3: still being tracked"]);
+    }
+
+    #[test]
+    fn reads_csv_reports() {
+        let report = "Description,Start date,Start time,Duration\nStandup,2024-10-21,09:00,0:15\n";
+
+        let result = read_activities(report, None).unwrap();
+
+        assert_eq!(result.activities[0].summary, "Standup");
+    }
+
+    #[test]
+    fn reports_invalid_entries() {
+        let export = r#"[{"start": "yesterday", "duration": 60}]"#;
+
+        let errors = read_activities(export, None).unwrap_err();
+
+        assert_eq!(
+            errors,
+            [ImportError::new(
+                "time entry 1",
+                "Invalid start 'yesterday'"
+            )]
+        );
+    }
+}
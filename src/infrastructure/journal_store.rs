@@ -0,0 +1,169 @@
+//! Changes saved to Maconomy, kept in a file next to the cookie with one JSON entry per line, so
+//! that they can be undone later
+
+use super::file_store;
+use crate::domain::models::journal::{by_command, JournalEntry};
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "journal.jsonl";
+
+#[derive(Debug, Clone)]
+pub(crate) struct JournalStore {
+    path: PathBuf,
+    /// Changes of older commands are removed, so that the journal doesn't keep growing
+    max_commands: usize,
+}
+
+impl JournalStore {
+    pub(crate) const DEFAULT_MAX_COMMANDS: usize = 100;
+
+    pub(crate) fn new(path: &str) -> Result<Self> {
+        let path = shellexpand::full(path).context("Failed to expand journal path")?;
+
+        Ok(Self {
+            path: PathBuf::from(path.as_ref()),
+            max_commands: Self::DEFAULT_MAX_COMMANDS,
+        })
+    }
+
+    /// A store in the same directory as the cookie
+    pub(crate) fn next_to(cookie_path: &str) -> Result<Self> {
+        Ok(Self {
+            path: file_store::next_to(cookie_path, FILE_NAME)?,
+            max_commands: Self::DEFAULT_MAX_COMMANDS,
+        })
+    }
+
+    pub(crate) fn with_max_commands(mut self, max_commands: usize) -> Self {
+        self.max_commands = max_commands;
+        self
+    }
+
+    /// Every entry, from the oldest to the newest
+    pub(crate) fn load(&self) -> Result<Vec<JournalEntry>> {
+        let Some(journal) = file_store::read(&self.path, "journal")? else {
+            return Ok(Vec::new());
+        };
+
+        journal
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!("Failed to parse line {} of {}", i + 1, self.path.display())
+                })
+            })
+            .collect()
+    }
+
+    /// Adds the entry at the end, and removes the changes of the commands before the latest
+    /// `max_commands`
+    pub(crate) fn append(&self, entry: &JournalEntry) -> Result<()> {
+        file_store::create_directory(&self.path)?;
+
+        let json = serde_json::to_string(entry).context("Failed to serialize journal entry")?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open journal {}", self.path.display()))?;
+        writeln!(file, "{json}")
+            .with_context(|| format!("Failed to write to journal {}", self.path.display()))?;
+
+        self.prune()
+    }
+
+    /// Keeps only the `len` oldest entries
+    pub(crate) fn truncate(&self, len: usize) -> Result<()> {
+        let mut entries = self.load()?;
+        entries.truncate(len);
+        self.write(&entries)
+    }
+
+    /// Keeps only the entries of the latest `max_commands` commands
+    fn prune(&self) -> Result<()> {
+        let entries = self.load()?;
+        let commands = by_command(&entries);
+        if commands.len() <= self.max_commands {
+            return Ok(());
+        }
+
+        let excess = commands.len() - self.max_commands;
+        let removed: usize = commands[..excess].iter().map(|command| command.len()).sum();
+        self.write(&entries[removed..])
+    }
+
+    fn write(&self, entries: &[JournalEntry]) -> Result<()> {
+        let mut journal = String::new();
+        for entry in entries {
+            let json = serde_json::to_string(entry).context("Failed to serialize journal entry")?;
+            journal.push_str(&json);
+            journal.push('\n');
+        }
+        std::fs::write(&self.path, journal)
+            .with_context(|| format!("Failed to write journal {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{journal::Change, week::WeekNumber};
+
+    #[test]
+    fn appends_loads_and_truncates_entries() {
+        let directory = file_store::test_directory("journal");
+        let cookie_path = directory.join("maconomy_cookie");
+        let store = JournalStore::next_to(&cookie_path.to_string_lossy()).unwrap();
+        let entry = |week| {
+            JournalEntry::new(
+                "2024-10-21T08:00:00Z".parse().unwrap(),
+                WeekNumber::new(week, 2024).unwrap(),
+                Change::Submit,
+            )
+        };
+        let (first, second) = (entry(42), entry(43));
+
+        assert_eq!(store.load().unwrap(), []);
+        store.append(&first).unwrap();
+        store.append(&second).unwrap();
+        assert!(directory.join("journal.jsonl").exists());
+        assert_eq!(store.load().unwrap(), [first.clone(), second]);
+        store.truncate(1).unwrap();
+        assert_eq!(store.load().unwrap(), [first]);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn keeps_only_the_latest_commands() {
+        let directory = file_store::test_directory("journal_limit");
+        let cookie_path = directory.join("maconomy_cookie");
+        let store = JournalStore::next_to(&cookie_path.to_string_lossy())
+            .unwrap()
+            .with_max_commands(2);
+        let entry = |command_started: &str, week| {
+            JournalEntry::new(
+                command_started.parse().unwrap(),
+                WeekNumber::new(week, 2024).unwrap(),
+                Change::Submit,
+            )
+        };
+        let entries = [
+            entry("2024-10-21T08:00:00Z", 42),
+            entry("2024-10-21T09:00:00Z", 42),
+            entry("2024-10-21T09:00:00Z", 43),
+            entry("2024-10-21T10:00:00Z", 43),
+        ];
+
+        for entry in &entries {
+            store.append(entry).unwrap();
+        }
+        assert_eq!(store.load().unwrap(), entries[1..]);
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -1,9 +1,22 @@
 pub(crate) mod auth_service;
+pub(crate) mod completion_cache;
+mod file_store;
 pub(crate) mod http_service;
+pub(crate) mod importers {
+    pub(crate) mod clockify;
+    pub(crate) mod csv;
+    mod date_time;
+    pub(crate) mod ics;
+    mod report_csv;
+    pub(crate) mod timewarrior;
+    pub(crate) mod toggl;
+}
+pub(crate) mod journal_store;
 pub(crate) mod repositories {
     pub(crate) mod maconomy_http_client;
     pub(crate) mod time_sheet_repository;
 }
+pub(crate) mod timer_store;
 pub(crate) mod models {
     pub(super) mod search_response;
     pub(super) mod taskname;
@@ -45,4 +45,9 @@ pub(crate) struct Tasks {
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Jobs {
     pub(crate) jobnumber: String,
+    #[serde(default)]
+    pub(crate) jobname: String,
+    /// Customer name
+    #[serde(default)]
+    pub(crate) name1: String,
 }
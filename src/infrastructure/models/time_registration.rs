@@ -78,6 +78,11 @@ pub struct CardData {
     pub regulartimeday5var: f32,
     pub regulartimeday6var: f32,
     pub regulartimeday7var: f32,
+    #[serde(default)]
+    pub submitted: bool,
+    /// For example "approved" or "rejected", or empty if the time sheet hasn't been handled
+    #[serde(default)]
+    pub approvalstatus: String,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{collections::HashMap, fmt::Display};
 
 use anyhow::{anyhow, bail, Context, Result};
 use chrono::NaiveDate;
@@ -10,6 +10,7 @@ use reqwest::{
 use serde::Deserialize;
 use serde_json::json;
 
+use crate::domain::models::hours::Hours;
 use crate::infrastructure::{
     http_service::HttpService,
     models::{
@@ -50,6 +51,56 @@ pub(crate) struct MaconomyHttpClient<'a> {
     company_name: String,
 }
 
+/// A restriction expression for Maconomy searches. Literals are always quoted and escaped, so user
+/// input can't change the meaning of the expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Restriction(String);
+
+impl Restriction {
+    /// `field` contains `value`, where any wildcards in `value` are matched literally
+    pub(crate) fn contains(field: &'static str, value: &str) -> Self {
+        let pattern = format!("*{}*", escape_wildcards(value));
+        Self(format!("{} like {}", field_name(field), quote(&pattern)))
+    }
+
+    pub(crate) fn or(self, other: Restriction) -> Self {
+        Self(format!("({} or {})", self.0, other.0))
+    }
+}
+
+impl Display for Restriction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Field names are never user input, but we make sure that they can't break the expression either
+fn field_name(field: &'static str) -> &'static str {
+    assert!(
+        !field.is_empty() && field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        "Invalid field name '{field}'"
+    );
+    field
+}
+
+/// Quotes a string literal, escaping single quotes by doubling them
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Escapes the wildcards of `like` patterns (and the escape character itself)
+fn escape_wildcards(value: &str) -> String {
+    value
+        .chars()
+        .fold(String::with_capacity(value.len()), |mut escaped, c| {
+            if matches!(c, '\\' | '*' | '?') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+            escaped
+        })
+}
+
 #[derive(Deserialize, Debug)]
 struct GetInstancesResponseBody {
     meta: Meta,
@@ -189,10 +240,34 @@ impl MaconomyHttpClient<'_> {
         Ok((time_registration, concurrency_control.into()))
     }
 
+    /// Sets the hours of each given day (1-indexed) on row `row`
     pub async fn set_time(
         &self,
-        hours: f32,
-        days: &HashSet<u8>,
+        days: &HashMap<u8, Hours>,
+        row: u8,
+        container_instance: &ContainerInstance,
+    ) -> Result<(TimeRegistration, ConcurrencyControl)> {
+        let body = set_days_body_from_days(days);
+        debug!("setting set_time body to {body}");
+
+        self.update_row(&body, row, container_instance).await
+    }
+
+    /// Sets the comment (`entrytext`) of a row
+    pub async fn set_comment(
+        &self,
+        comment: &str,
+        row: u8,
+        container_instance: &ContainerInstance,
+    ) -> Result<(TimeRegistration, ConcurrencyControl)> {
+        let body = json!({ "data": { "entrytext": comment } });
+
+        self.update_row(&body, row, container_instance).await
+    }
+
+    async fn update_row(
+        &self,
+        body: &serde_json::Value,
         row: u8,
         container_instance: &ContainerInstance,
     ) -> Result<(TimeRegistration, ConcurrencyControl)> {
@@ -200,9 +275,6 @@ impl MaconomyHttpClient<'_> {
         let instance_url = self.get_container_instance_url(&container_instance.id.0);
         let url = format!("{instance_url}/data/panes/table/{row}");
 
-        let body = set_days_body_from_days(hours, days);
-        debug!("setting set_time body to {body}");
-
         let request = self
             .client
             .post(url)
@@ -226,22 +298,28 @@ impl MaconomyHttpClient<'_> {
         Ok((time_registration, concurrency_control.into()))
     }
 
-    pub async fn get_job_number_from_name(&self, job_name: &str) -> Result<Option<String>> {
+    /// Searches for jobs where the job number, job name or customer contains `query`, or all jobs
+    /// if `query` is `None`
+    pub async fn search_jobs(
+        &self,
+        query: Option<&str>,
+    ) -> Result<search_response::SearchResponse<search_response::Jobs>> {
         let (url, company) = (&self.url, &self.company_name);
         let url = format!(
         "{url}/containers/{company}/timeregistration/search/table;foreignkey=notblockedjobnumber_jobheader"
     );
 
-        let restriction = format!(
-            "(customernumber = '{job_name}' \
-                or jobnumber = '{job_name}' \
-                or jobname = '{job_name}' \
-                or name1 = '{job_name}')"
-        );
-        let body = json!({
-            "restriction": restriction,
-            "fields": ["jobnumber"]
+        let mut body = json!({
+            "fields": ["jobnumber", "jobname", "name1"]
         });
+        if let Some(query) = query {
+            let restriction = ["customernumber", "jobnumber", "jobname", "name1"]
+                .into_iter()
+                .map(|field| Restriction::contains(field, query))
+                .reduce(Restriction::or)
+                .expect("There is at least one field");
+            body["restriction"] = restriction.to_string().into();
+        }
 
         let request = self
             .client
@@ -255,18 +333,10 @@ impl MaconomyHttpClient<'_> {
             bail!("Server responded with {status}");
         }
 
-        let response_body: search_response::SearchResponse<search_response::Jobs> = response
+        response
             .json()
             .await
-            .context("Failed to parse response body into SearchResponse with jobs")?;
-        let job_number = response_body
-            .panes
-            .filter
-            .records
-            .first()
-            .map(|record| record.data.jobnumber.clone());
-
-        Ok(job_number)
+            .context("Failed to parse response body into SearchResponse with jobs")
     }
 
     pub async fn get_tasks_for_job(
@@ -438,6 +508,11 @@ impl MaconomyHttpClient<'_> {
 
         let response = self.send_request(request).await?;
 
+        let status = &response.status();
+        if !status.is_success() {
+            bail!("Server responded with {status}");
+        }
+
         let concurrency_control = concurrency_control_from_headers(response.headers())?;
         Ok(concurrency_control.into())
     }
@@ -455,15 +530,82 @@ async fn is_uninitialized_week_error(response_body: &bytes::Bytes) -> Result<boo
     }))
 }
 
-fn set_days_body_from_days(hours: f32, days: &HashSet<u8>) -> serde_json::Value {
+fn set_days_body_from_days(days: &HashMap<u8, Hours>) -> serde_json::Value {
     let days: serde_json::Map<_, _> = days
         .iter()
-        .map(|&day| {
+        .map(|(&day, hours)| {
             let key = format!("numberday{day}");
-            let value = serde_json::json!(hours);
+            let value = serde_json::json!(hours.as_decimal());
             (key, value)
         })
         .collect();
 
     serde_json::json!({ "data": days })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_restriction() {
+        let restriction = Restriction::contains("jobnumber", "1234")
+            .or(Restriction::contains("jobname", "acme"))
+            .or(Restriction::contains("name1", "Acme Corp"));
+
+        assert_eq!(
+            restriction.to_string(),
+            "((jobnumber like '*1234*' or jobname like '*acme*') or name1 like '*Acme Corp*')"
+        );
+    }
+
+    #[test]
+    fn escapes_quotes() {
+        let restriction = Restriction::contains("jobname", "O'Reilly");
+
+        assert_eq!(restriction.to_string(), "jobname like '*O''Reilly*'");
+    }
+
+    #[test]
+    fn escapes_hostile_input() {
+        let inputs = [
+            (
+                "' or 1=1 or jobname like '",
+                "jobname like '*'' or 1=1 or jobname like ''*'",
+            ),
+            ("x') or ('1' = '1", "jobname like '*x'') or (''1'' = ''1*'"),
+            ("''", "jobname like '*''''*'"),
+            (r"\'", r"jobname like '*\\''*'"),
+        ];
+
+        for (input, expected) in inputs {
+            assert_eq!(
+                Restriction::contains("jobname", input).to_string(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn escapes_wildcards() {
+        let inputs = [
+            ("*", r"jobname like '*\**'"),
+            ("a?b", r"jobname like '*a\?b*'"),
+            (r"\*", r"jobname like '*\\\**'"),
+            ("O'*", r"jobname like '*O''\**'"),
+        ];
+
+        for (input, expected) in inputs {
+            assert_eq!(
+                Restriction::contains("jobname", input).to_string(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid field name")]
+    fn rejects_invalid_field_names() {
+        Restriction::contains("jobname = '' or jobnumber", "foo");
+    }
+}
@@ -2,21 +2,31 @@ use super::maconomy_http_client::{
     self, ConcurrencyControl, ContainerInstance, MaconomyHttpClient,
 };
 use crate::{
+    domain::matching::{find_best_match, AmbiguousMatch, MatchKind},
     domain::models::{
-        day::Days,
+        day::{Day, Days},
+        hours::{Hours, HoursChange},
+        job::{Job, Task},
+        journal::{Change, JournalEntry},
         line_number::LineNumber,
-        time_sheet::{Line, TimeSheet, Week},
+        period::Period,
+        time_sheet::{Line, Status, TimeSheet, Totals, Week},
         week::WeekNumber,
     },
-    infrastructure::models::{
-        search_response,
-        taskname::{self},
-        time_registration::{TableRecord, TimeRegistration},
+    infrastructure::{
+        journal_store::JournalStore,
+        models::{
+            search_response,
+            taskname::{self},
+            time_registration::{CardData, TableRecord, TimeRegistration},
+        },
     },
+    utils::errors::error_stack_fmt,
 };
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use log::{debug, info};
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum AddLineError {
@@ -26,6 +36,10 @@ pub(crate) enum AddLineError {
     JobNotFound(String),
     #[error("Task '{0}' not found")]
     TaskNotFound(String),
+    #[error("{0}")]
+    InvalidHours(String),
+    #[error(transparent)]
+    Ambiguous(#[from] AmbiguousMatch),
     #[error("Something went wrong when adding a new line to the time sheet: {0}")]
     Unknown(#[from] anyhow::Error),
 }
@@ -34,6 +48,12 @@ pub(crate) struct TimeSheetRepository<'a> {
     client: MaconomyHttpClient<'a>,
     container_instance: Option<ContainerInstance>,
     time_registration: Option<TimeRegistration>,
+    /// The week that was set last, which changes are made to
+    week: Option<WeekNumber>,
+    /// Where changes are recorded, if anywhere
+    journal: Option<JournalStore>,
+    /// When the repository was created, which groups the changes of a command in the journal
+    created: DateTime<Utc>,
 }
 
 impl TimeSheetRepository<'_> {
@@ -42,9 +62,46 @@ impl TimeSheetRepository<'_> {
             client: repository,
             container_instance: None,
             time_registration: None,
+            week: None,
+            journal: None,
+            created: Utc::now(),
+        }
+    }
+
+    /// Records every change that is saved in `journal`
+    pub(crate) fn with_journal(mut self, journal: JournalStore) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Stops recording changes, for example while undoing changes from the journal
+    pub(crate) fn stop_journaling(&mut self) {
+        self.journal = None;
+    }
+
+    /// Records a change to the week that was set last. A change that can't be recorded has been
+    /// saved anyway, so that isn't an error
+    fn record(&self, change: Option<Change>) {
+        let (Some(journal), Some(week), Some(change)) = (&self.journal, &self.week, change) else {
+            return;
+        };
+
+        let entry = JournalEntry::new(self.created, week.clone(), change);
+        if let Err(err) = journal.append(&entry) {
+            eprintln!(
+                "Failed to record the change, so it can't be undone: {}",
+                error_stack_fmt(&err)
+            );
         }
     }
 
+    /// The time sheet as of the last response from Maconomy
+    fn current_time_sheet(&self) -> TimeSheet {
+        self.time_registration
+            .clone()
+            .map_or_else(|| TimeSheet::new(Vec::new(), 0), TimeSheet::from)
+    }
+
     /// Gets and caches container instance
     async fn get_container_instance(&mut self) -> Result<ContainerInstance> {
         if self.container_instance.is_none() {
@@ -119,6 +176,8 @@ impl TimeSheetRepository<'_> {
             .context("Failed to set week")?;
 
         self.update_concurrency_control(concurrency_control);
+        self.week = Some(week.clone());
+        self.time_registration = Some(time_registration.clone());
 
         Ok(time_registration.into())
     }
@@ -129,18 +188,20 @@ impl TimeSheetRepository<'_> {
         task: &str,
         time_sheet: &TimeSheet,
     ) -> Result<u8, AddLineError> {
-        let line_number = match time_sheet.find_line_nr(job, task) {
+        let line_number = match time_sheet.find_line_nr(job, task)? {
             Some(line_number) => line_number,
             None => {
                 info!("Found no line for job '{job}', task '{task}'. Creating new line for it");
                 let time_sheet = self.add_line(job, task).await?;
 
-                time_sheet.find_line_nr(job, task).with_context(|| {
+                // New lines are added at the end of the time sheet
+                let line_number = time_sheet.lines.len().checked_sub(1).with_context(|| {
                     format!(
                         "did not find job '{job}' and task '{task}', even after creating a new \
                         line for it"
                     )
-                })?
+                })?;
+                line_number as u8
             }
         };
 
@@ -149,7 +210,7 @@ impl TimeSheetRepository<'_> {
 
     pub(crate) async fn set_time(
         &mut self,
-        hours: f32,
+        change: HoursChange,
         days: &Days,
         week: &WeekNumber,
         job: &str,
@@ -161,25 +222,140 @@ impl TimeSheetRepository<'_> {
             .await
             .context("Failed to get time sheet")?;
 
+        // Validate the new hours before any line gets created
+        let current_week = time_sheet
+            .find_line_nr(job, task)?
+            .and_then(|line_number| time_sheet.lines.get(line_number as usize))
+            .map(|line| &line.week);
+        let hours = days
+            .iter()
+            .map(|&day| {
+                let current = current_week.map_or(Hours::ZERO, |week| week.get(day));
+                let hours = change
+                    .apply(current)
+                    .map_err(|err| AddLineError::InvalidHours(format!("{err} on {day}")))?;
+                Ok((day as u8, hours))
+            })
+            .collect::<Result<HashMap<_, _>, AddLineError>>()?;
+
         let line_number = self
             .get_or_create_line_number(job, task, &time_sheet)
             .await?;
+
+        self.send_hours(&hours, line_number).await?;
+        Ok(())
+    }
+
+    /// Sets the hours of the given days on an existing (0-indexed) line
+    pub(crate) async fn set_line_hours(
+        &mut self,
+        week: &WeekNumber,
+        line_number: u8,
+        hours: &HashMap<Day, Hours>,
+    ) -> Result<()> {
+        self.set_lines_hours(week, &[(line_number, hours)]).await
+    }
+
+    /// Sets the hours of the given days on several existing (0-indexed) lines, getting the time
+    /// sheet only once
+    pub(crate) async fn set_lines_hours(
+        &mut self,
+        week: &WeekNumber,
+        lines: &[(u8, &HashMap<Day, Hours>)],
+    ) -> Result<()> {
+        // We need to get the time sheet before we can modify it
+        let _ = self
+            .get_time_sheet(week)
+            .await
+            .context("Failed to get time sheet")?;
+
+        for &(line_number, hours) in lines {
+            let hours = hours
+                .iter()
+                .map(|(&day, &hours)| (day as u8, hours))
+                .collect();
+            self.send_hours(&hours, line_number).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn send_hours(&mut self, hours: &HashMap<u8, Hours>, line_number: u8) -> Result<()> {
         let container_instance = self
             .get_container_instance()
             .await
             .context("Failed to get container instance")?;
 
-        let days: HashSet<_> = days.iter().map(|&d| d as u8).collect();
-
         info!("Setting time");
+        let before = self.current_time_sheet();
         let (time_registration, concurrency_control) = self
             .client
-            .set_time(hours, &days, line_number, &container_instance)
+            .set_time(hours, line_number, &container_instance)
             .await
-            .with_context(|| format!("Failed to set {hours} hours on row {line_number}"))?;
+            .with_context(|| format!("Failed to set hours on row {line_number}"))?;
 
-        self.time_registration = Some(time_registration);
+        self.time_registration = Some(time_registration.clone());
+        self.update_concurrency_control(concurrency_control);
+        let after = TimeSheet::from(time_registration);
+        self.record(Change::hours(&before, &after, line_number));
+        Ok(())
+    }
+
+    /// Sets the comment of the line that matches `job` and `task`
+    pub(crate) async fn set_comment(
+        &mut self,
+        week: &WeekNumber,
+        job: &str,
+        task: &str,
+        comment: &str,
+    ) -> Result<(), AddLineError> {
+        let time_sheet = self
+            .get_time_sheet(week)
+            .await
+            .context("Failed to get time sheet")?;
+
+        let line_number = time_sheet
+            .find_line_nr(job, task)?
+            .with_context(|| format!("Found no line for job '{job}' and task '{task}'"))?;
+
+        self.send_comment(comment, line_number).await?;
+        Ok(())
+    }
+
+    /// Sets the comment of a line by its line number
+    pub(crate) async fn set_line_comment(
+        &mut self,
+        week: &WeekNumber,
+        line_number: &LineNumber,
+        comment: &str,
+    ) -> Result<()> {
+        let time_sheet = self
+            .get_time_sheet(week)
+            .await
+            .context("Failed to get time sheet")?;
+
+        let line_number = resolve_line_number(line_number, &time_sheet)?;
+        self.send_comment(comment, line_number).await
+    }
+
+    async fn send_comment(&mut self, comment: &str, line_number: u8) -> Result<()> {
+        let container_instance = self
+            .get_container_instance()
+            .await
+            .context("Failed to get container instance")?;
+
+        info!("Setting comment");
+        let before = self.current_time_sheet();
+        let (time_registration, concurrency_control) = self
+            .client
+            .set_comment(comment, line_number, &container_instance)
+            .await
+            .with_context(|| format!("Failed to set comment on row {line_number}"))?;
+
+        self.time_registration = Some(time_registration.clone());
         self.update_concurrency_control(concurrency_control);
+        let after = TimeSheet::from(time_registration);
+        self.record(Change::comment(&before, &after, line_number));
         Ok(())
     }
 
@@ -191,56 +367,123 @@ impl TimeSheetRepository<'_> {
         container_instance.concurrency_control = concurrency_control;
     }
 
-    async fn get_short_task_name_from_full_task_name(
+    /// Finds the job that best matches `job` by its number, name or customer
+    async fn find_job(&self, job: &str) -> Result<Option<Job>, AddLineError> {
+        let jobs = self
+            .search_jobs(Some(job))
+            .await
+            .with_context(|| format!("Could not get job number for job '{job}'"))?;
+
+        let job = find_best_match(jobs, |candidate| {
+            [&candidate.number, &candidate.name, &candidate.customer]
+                .into_iter()
+                .filter_map(|name| MatchKind::of(job, name))
+                .min()
+        })
+        .into_result("Job", job, |job| format!("{} ({})", job.name, job.number))?;
+
+        Ok(job)
+    }
+
+    async fn find_task(&self, task: &str, job_number: &str) -> Result<Option<Task>, AddLineError> {
+        let tasks = self
+            .client
+            .get_tasks_for_job(job_number)
+            .await
+            .context("Failed to get tasks")?;
+
+        // `description` is the long name in this case (i.e. `tasktextvar`)
+        let task = find_best_match(tasks.panes.filter.records, |record| {
+            let description = MatchKind::of(task, &record.data.description);
+            let short_name = MatchKind::of(task, &record.data.taskname);
+            description.into_iter().chain(short_name).min()
+        })
+        .into_result("Task", task, |record| record.data.description.clone())?
+        .map(|record| Task {
+            short_name: record.data.taskname,
+            description: record.data.description,
+        });
+
+        Ok(task)
+    }
+
+    /// Finds the job and task that best match `job` and `task`, without adding a line for them
+    pub(crate) async fn find_job_and_task(
         &self,
-        task_name: &str,
         job: &str,
-    ) -> Result<Option<taskname::ShortTaskName>> {
-        let tasks = self.get_tasks(job).await.context("Failed to get tasks")?;
-        let records = tasks.panes.filter.records;
-        let task_name = records
-            .clone()
-            .into_iter()
-            // `description` is the long name in this case (i.e. `tasktextvar`)
-            .find(|row| row.data.description.to_lowercase() == task_name.to_lowercase())
-            .map(|row| taskname::ShortTaskName(row.data.taskname));
+        task: &str,
+    ) -> Result<(Job, Task), AddLineError> {
+        let Some(job) = self.find_job(job).await? else {
+            return Err(AddLineError::JobNotFound(job.to_string()));
+        };
+        let Some(task) = self.find_task(task, &job.number).await? else {
+            return Err(AddLineError::TaskNotFound(task.to_string()));
+        };
 
-        Ok(task_name)
+        Ok((job, task))
     }
 
     async fn add_line(&mut self, job: &str, task: &str) -> Result<TimeSheet, AddLineError> {
         debug!("Getting job number for job '{job}'");
-        let job_number = self
-            .client
-            .get_job_number_from_name(job)
-            .await
-            .context(format!("Could not get job number for job '{job}'"))?;
-
-        let Some(job_number) = job_number else {
+        let Some(job) = self.find_job(job).await? else {
             info!("Did not find a job number for {job}");
             return Err(AddLineError::JobNotFound(job.to_string()));
         };
-        debug!("Got job number '{job_number}' for job '{job}'");
+        let job_number = job.number;
+        debug!("Got job number '{job_number}' for job '{}'", job.name);
 
-        let task_name = self
-            .get_short_task_name_from_full_task_name(task, job)
-            .await?
-            .ok_or_else(|| {
-                info!("Did not find a long task name for task '{task}'");
-                AddLineError::TaskNotFound(task.to_string())
-            })?;
+        let task = self.find_task(task, &job_number).await?.ok_or_else(|| {
+            info!("Did not find a long task name for task '{task}'");
+            AddLineError::TaskNotFound(task.to_string())
+        })?;
+
+        let task_name = taskname::ShortTaskName(task.short_name);
+        self.add_row(&job_number, &task_name).await
+    }
 
+    async fn add_row(
+        &mut self,
+        job_number: &str,
+        task_name: &taskname::ShortTaskName,
+    ) -> Result<TimeSheet, AddLineError> {
         debug!("Adding new line");
         let container_instance = self.get_container_instance().await?;
         let (time_registration, concurrecy_control) = self
             .client
-            .add_new_row(&job_number, &task_name, &container_instance)
+            .add_new_row(job_number, task_name, &container_instance)
             .await?;
 
         self.update_concurrency_control(concurrecy_control);
         self.time_registration = Some(time_registration.clone());
 
-        Ok(time_registration.into())
+        let time_sheet = TimeSheet::from(time_registration);
+        self.record(Change::add_line(&time_sheet));
+        Ok(time_sheet)
+    }
+
+    /// Adds a new line for exactly the given job and task, and returns its (0-indexed) line number
+    pub(crate) async fn add_new_line(
+        &mut self,
+        week: &WeekNumber,
+        job: &Job,
+        task: &Task,
+    ) -> Result<u8, AddLineError> {
+        // We need to get the time sheet before we can modify it
+        let _ = self
+            .get_time_sheet(week)
+            .await
+            .context("Failed to get time sheet")?;
+
+        let task_name = taskname::ShortTaskName(task.short_name.clone());
+        let time_sheet = self.add_row(&job.number, &task_name).await?;
+
+        // New lines are added at the end of the time sheet
+        let line_number = time_sheet
+            .lines
+            .len()
+            .checked_sub(1)
+            .context("Time sheet has no lines, even after adding a new line")?;
+        Ok(line_number as u8)
     }
 
     pub(crate) async fn delete_line(
@@ -254,46 +497,68 @@ impl TimeSheetRepository<'_> {
             .await
             .context("Failed to get time sheet")?;
 
-        let line_number = match line_number {
-            LineNumber::Number(line_number) => *line_number,
-            LineNumber::Last => {
-                let last_line_number = time_sheet.lines.len() as u8;
-                info!("Using line number {last_line_number} as last line number");
-                last_line_number
-            }
-        };
+        let line_number = resolve_line_number(line_number, &time_sheet)?;
 
         let container_instance = self.get_container_instance().await?;
 
         let (time_registration, concurrecy_control) = self
             .client
-            .delete_row(line_number - 1, &container_instance)
+            .delete_row(line_number, &container_instance)
             .await
-            .with_context(|| format!("Failed to delete line number {line_number}"))?;
+            .with_context(|| format!("Failed to delete line number {}", line_number + 1))?;
 
         self.update_concurrency_control(concurrecy_control);
-        self.time_registration = Some(time_registration.clone());
+        self.time_registration = Some(time_registration);
+        self.record(Change::delete_line(&time_sheet, line_number));
 
         Ok(())
     }
 
-    async fn get_tasks(
-        &self,
-        job: &str,
-    ) -> Result<search_response::SearchResponse<search_response::Tasks>> {
-        let job_number = self
+    pub(crate) async fn search_jobs(&self, query: Option<&str>) -> Result<Vec<Job>> {
+        let jobs = self
             .client
-            .get_job_number_from_name(job)
+            .search_jobs(query)
             .await
-            .context(format!("Failed to get job number for job '{job}'"))?
-            .ok_or(anyhow!("No job number found for job '{job}'"))?;
+            .context("Failed to search for jobs")?
+            .panes
+            .filter
+            .records
+            .into_iter()
+            .map(|record| Job::from(record.data))
+            .collect();
 
-        debug!("Got job number {job_number} for job {job}");
+        Ok(jobs)
+    }
+
+    /// The job that best matches `job`, and its tasks
+    pub(crate) async fn get_tasks_for_job(&self, job: &str) -> Result<(Job, Vec<Task>)> {
+        let job = self
+            .find_job(job)
+            .await?
+            .ok_or_else(|| anyhow!("Job '{job}' not found"))?;
+        debug!("Got job number {} for job {}", job.number, job.name);
 
-        self.client
-            .get_tasks_for_job(&job_number)
+        let tasks = self
+            .get_tasks_for_job_number(&job.number)
             .await
-            .with_context(|| format!("Failed to get tasks for job '{job}'"))
+            .with_context(|| format!("Failed to get tasks for job '{}'", job.name))?;
+
+        Ok((job, tasks))
+    }
+
+    pub(crate) async fn get_tasks_for_job_number(&self, job_number: &str) -> Result<Vec<Task>> {
+        let tasks = self
+            .client
+            .get_tasks_for_job(job_number)
+            .await?
+            .panes
+            .filter
+            .records
+            .into_iter()
+            .map(|record| Task::from(record.data))
+            .collect();
+
+        Ok(tasks)
     }
 
     pub(crate) async fn submit(&mut self, week: &WeekNumber) -> Result<()> {
@@ -315,6 +580,7 @@ impl TimeSheetRepository<'_> {
             .context("Failed to submit")?;
 
         self.update_concurrency_control(concurrency_control);
+        self.record(Some(Change::Submit));
 
         Ok(())
     }
@@ -334,21 +600,121 @@ impl From<TableRecord> for Line {
         };
 
         Line::new(data.jobnamevar, data.tasktextvar, week)
+            .with_comment(data.entrytext)
+            .with_job_number(data.jobnumber)
+            .with_task_short_name(data.taskname)
     }
 }
 
+/// Gets the 0-indexed row of a (1-indexed) line number
+pub(crate) fn resolve_line_number(line_number: &LineNumber, time_sheet: &TimeSheet) -> Result<u8> {
+    let line_number = match line_number {
+        LineNumber::Number(line_number) => *line_number,
+        LineNumber::Last => {
+            let last_line_number = time_sheet.lines.len() as u8;
+            info!("Using line number {last_line_number} as last line number");
+            last_line_number
+        }
+    };
+
+    line_number
+        .checked_sub(1)
+        .context("Line numbers start at 1")
+}
+
 impl From<TimeRegistration> for TimeSheet {
     fn from(time_registration: TimeRegistration) -> Self {
         let table_records = time_registration.panes.table.records;
         let card_records = time_registration.panes.card.records;
 
         let lines: Vec<_> = table_records.into_iter().map(Line::from).collect();
-        let week = card_records
+        let card = &card_records
             .first()
             .expect("time registration contains no records")
-            .data
-            .weeknumbervar;
+            .data;
+
+        let time_sheet = Self::new(lines, card.weeknumbervar)
+            .with_totals(Totals::from(card))
+            .with_status(Status::from(card))
+            .with_employee(card.employeenamevar.clone());
+
+        // The period is empty until the week has been created
+        let start = card.periodstartvar.parse().ok();
+        let end = card.periodendvar.parse().ok();
+        let period = start
+            .zip(end)
+            .and_then(|(start, end)| Period::new(start, end).ok());
+        match period {
+            Some(period) => time_sheet.with_period(period),
+            None => time_sheet,
+        }
+    }
+}
+
+impl From<&CardData> for Status {
+    fn from(card: &CardData) -> Self {
+        match card.approvalstatus.to_lowercase().as_str() {
+            "approved" => Status::Approved,
+            "rejected" => Status::Rejected,
+            _ if card.submitted => Status::Submitted,
+            _ => Status::Open,
+        }
+    }
+}
+
+impl From<&CardData> for Totals {
+    fn from(card: &CardData) -> Self {
+        let registered = Week {
+            monday: card.totalnumberday1var.into(),
+            tuesday: card.totalnumberday2var.into(),
+            wednesday: card.totalnumberday3var.into(),
+            thursday: card.totalnumberday4var.into(),
+            friday: card.totalnumberday5var.into(),
+            saturday: card.totalnumberday6var.into(),
+            sunday: card.totalnumberday7var.into(),
+        };
+        let norm = Week {
+            monday: card.fixednumberday1var.into(),
+            tuesday: card.fixednumberday2var.into(),
+            wednesday: card.fixednumberday3var.into(),
+            thursday: card.fixednumberday4var.into(),
+            friday: card.fixednumberday5var.into(),
+            saturday: card.fixednumberday6var.into(),
+            sunday: card.fixednumberday7var.into(),
+        };
+        let regular = Week {
+            monday: card.regulartimeday1var.into(),
+            tuesday: card.regulartimeday2var.into(),
+            wednesday: card.regulartimeday3var.into(),
+            thursday: card.regulartimeday4var.into(),
+            friday: card.regulartimeday5var.into(),
+            saturday: card.regulartimeday6var.into(),
+            sunday: card.regulartimeday7var.into(),
+        };
 
-        Self::new(lines, week)
+        Totals {
+            registered,
+            norm,
+            regular,
+        }
+    }
+}
+
+impl From<search_response::Jobs> for Job {
+    fn from(job: search_response::Jobs) -> Self {
+        Job {
+            number: job.jobnumber,
+            name: job.jobname,
+            customer: job.name1,
+        }
+    }
+}
+
+impl From<search_response::Tasks> for Task {
+    fn from(task: search_response::Tasks) -> Self {
+        Task {
+            short_name: task.taskname,
+            description: task.description,
+        }
     }
 }
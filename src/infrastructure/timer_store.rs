@@ -0,0 +1,77 @@
+//! The running timer, kept in a file next to the cookie so that it survives reboots
+
+use super::file_store;
+use crate::domain::models::timer::Timer;
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "timer.json";
+
+pub(crate) struct TimerStore {
+    path: PathBuf,
+}
+
+impl TimerStore {
+    /// A store in the same directory as the cookie
+    pub(crate) fn next_to(cookie_path: &str) -> Result<Self> {
+        Ok(Self {
+            path: file_store::next_to(cookie_path, FILE_NAME)?,
+        })
+    }
+
+    /// The running timer, if there is one
+    pub(crate) fn load(&self) -> Result<Option<Timer>> {
+        let Some(json) = file_store::read(&self.path, "timer")? else {
+            return Ok(None);
+        };
+
+        serde_json::from_str(&json)
+            .map(Some)
+            .with_context(|| format!("Failed to parse timer {}", self.path.display()))
+    }
+
+    pub(crate) fn save(&self, timer: &Timer) -> Result<()> {
+        file_store::create_directory(&self.path)?;
+
+        let json = serde_json::to_string(timer).context("Failed to serialize timer")?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Failed to write timer {}", self.path.display()))
+    }
+
+    /// Removes the timer, if there is one
+    pub(crate) fn clear(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("Failed to remove timer {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_loads_and_clears_timer() {
+        let directory = file_store::test_directory("timer");
+        let cookie_path = directory.join("maconomy_cookie");
+        let store = TimerStore::next_to(&cookie_path.to_string_lossy()).unwrap();
+        let timer = Timer::new(
+            "Acme".to_string(),
+            "Development".to_string(),
+            "2024-10-21T08:00:00Z".parse().unwrap(),
+        );
+
+        assert_eq!(store.load().unwrap(), None);
+        store.save(&timer).unwrap();
+        assert!(directory.join("timer.json").exists());
+        assert_eq!(store.load().unwrap(), Some(timer));
+        store.clear().unwrap();
+        assert_eq!(store.load().unwrap(), None);
+        store.clear().unwrap();
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
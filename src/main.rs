@@ -3,9 +3,15 @@ use clap::Parser;
 use cli::arguments::{Command, Line};
 use cli::commands::CommandClient;
 use config::Configuration;
+use domain::models::check::Checks;
+use domain::models::hours::{Hours, HoursChange};
+use domain::models::timer::TimerSettings;
 use domain::time_sheet_service::TimeSheetService;
+use infrastructure::completion_cache::CompletionCache;
+use infrastructure::journal_store::JournalStore;
 use infrastructure::repositories::maconomy_http_client::MaconomyHttpClient;
 use infrastructure::repositories::time_sheet_repository::TimeSheetRepository;
+use infrastructure::timer_store::TimerStore;
 use infrastructure::{auth_service::AuthService, http_service::HttpService};
 use std::rc::Rc;
 use tokio::sync::Mutex;
@@ -22,8 +28,29 @@ mod utils;
 async fn main() -> anyhow::Result<()> {
     env_logger::init(); // Enable logging
 
+    cli::completions::complete_if_requested();
     let cli_arguments = Args::parse();
 
+    // Completions and the schema shouldn't require a configuration
+    match cli_arguments.command {
+        Command::Completions { shell } => {
+            cli::completions::write_completions(shell, &mut std::io::stdout())
+                .context("Failed to write completions")?;
+            return Ok(());
+        }
+        Command::Schema => {
+            let schema = serde_json::to_string_pretty(&cli::json::schema())
+                .context("Failed to serialize JSON Schema")?;
+            println!("{schema}");
+            return Ok(());
+        }
+        _ => (),
+    }
+
+    if cli_arguments.dry_run && !cli_arguments.command.supports_dry_run() {
+        anyhow::bail!("This command can't be run with `--dry-run`");
+    }
+
     let config = Configuration::new(cli_arguments.config);
     let url = config.get_value("maconomy_url")?;
     let company_name = config.get_value("company_id")?;
@@ -33,6 +60,27 @@ async fn main() -> anyhow::Result<()> {
         .get_optional_value("authentication.sso.cookie_path")?
         .unwrap_or("~/.local/share/maconomy-cli/maconomy_cookie".to_string());
 
+    let completion_cache_path = CompletionCache::path(&config)?;
+    let timer_store = TimerStore::next_to(&cookie_path)?;
+    let journal = match config.get_optional_value::<String>("journal_path")? {
+        Some(path) => JournalStore::new(&path)?,
+        None => JournalStore::next_to(&cookie_path)?,
+    }
+    .with_max_commands(
+        config
+            .get_optional_value("journal_max_commands")?
+            .unwrap_or(JournalStore::DEFAULT_MAX_COMMANDS),
+    );
+    let timer_granularity = || -> anyhow::Result<Hours> {
+        let settings: Option<TimerSettings> = config.get_optional_value("timer")?;
+        Ok(settings.unwrap_or_default().granularity())
+    };
+
+    let checks = || -> anyhow::Result<Checks> {
+        let checks = config.get_optional_value("checks")?;
+        Ok(checks.unwrap_or_default())
+    };
+
     let auth_service = AuthService::new(login_url, cookie_path);
     let http_service = HttpService::new(&auth_service);
     let client = reqwest::Client::builder()
@@ -41,22 +89,179 @@ async fn main() -> anyhow::Result<()> {
         .context("Failed to create HTTP client")?;
 
     let client = MaconomyHttpClient::new(url, company_name, client, http_service);
-    let repository = Rc::new(Mutex::new(TimeSheetRepository::new(client)));
+    let repository = TimeSheetRepository::new(client).with_journal(journal.clone());
+    let repository = Rc::new(Mutex::new(repository));
     let time_sheet_service = Rc::new(Mutex::new(TimeSheetService::new(repository.clone())));
     let mut command_client = CommandClient::new(
         repository.clone(),
         time_sheet_service.clone(),
         &auth_service,
-    );
+        completion_cache_path,
+    )
+    .with_dry_run(cli_arguments.dry_run);
 
     match cli_arguments.command {
         Command::Get { week, format } => command_client.get(week, format).await,
-        Command::Set { hours, task, days } => command_client.set(hours, &days, &task).await,
-        Command::Clear { task, days } => command_client.clear(&task, &days).await,
-        Command::Submit { week } => command_client.submit(week).await,
+        Command::Overview {
+            period,
+            year,
+            format,
+        } => command_client.overview(&period, year, format).await,
+        Command::Export {
+            period,
+            year,
+            format,
+        } => command_client.export(&period, year, format).await,
+        Command::Set {
+            hours,
+            task,
+            days,
+            comment,
+            force,
+            format,
+        } => {
+            command_client
+                .set(hours, &days, &task, comment, force, format)
+                .await
+        }
+        Command::Add {
+            hours,
+            task,
+            days,
+            comment,
+            force,
+            format,
+        } => {
+            let change = HoursChange::Add(hours);
+            command_client
+                .set(change, &days, &task, comment, force, format)
+                .await
+        }
+        Command::Start {
+            task,
+            force,
+            format,
+        } => {
+            command_client
+                .start(&task, &timer_store, timer_granularity()?, force, format)
+                .await
+        }
+        Command::Stop {
+            force,
+            discard,
+            format,
+        } => {
+            command_client
+                .stop(&timer_store, timer_granularity()?, force, discard, format)
+                .await
+        }
+        Command::Status => command_client.status(&timer_store, timer_granularity()?),
+        Command::Edit { week, force } => command_client.edit(week, force).await,
+        Command::Copy {
+            source,
+            with_hours,
+            week,
+            force,
+            format,
+        } => {
+            command_client
+                .copy(&source, with_hours, week, force, format)
+                .await
+        }
+        Command::Apply {
+            template: name,
+            week,
+            force,
+            format,
+        } => {
+            let template = config
+                .get_optional_value(&format!("templates.{name}"))?
+                .with_context(|| format!("Template '{name}' is missing from the configuration"))?;
+            command_client
+                .apply(&name, &template, week, force, format)
+                .await
+        }
+        Command::Import {
+            file,
+            from,
+            force,
+            format,
+        } => {
+            let settings = match from.settings_section() {
+                Some(section) => Some(config.get_optional_value(section)?.with_context(|| {
+                    format!("Rules for importing are missing from the configuration ([{section}])")
+                })?),
+                None => None,
+            };
+            command_client
+                .import(&file, from, settings.as_ref(), force, format)
+                .await
+        }
+        Command::ImportIcs {
+            file,
+            week,
+            force,
+            format,
+        } => {
+            let settings = config
+                .get_optional_value("ics")?
+                .context("Rules for importing events are missing from the configuration ([ics])")?;
+            command_client
+                .import_ics(&file, &settings, week, force, format)
+                .await
+        }
+        Command::Clear {
+            task,
+            days,
+            force,
+            format,
+        } => command_client.clear(&task, &days, force, format).await,
+        Command::Jobs { query, format } => command_client.jobs(query, format).await,
+        Command::Tasks { job, format } => command_client.tasks(&job, format).await,
+        Command::Check { week } => command_client.check(week, &checks()?).await,
+        Command::Submit {
+            week,
+            yes,
+            force,
+            format,
+        } => {
+            command_client
+                .submit(week, yes, force, &checks()?, format)
+                .await
+        }
+        Command::Undo {
+            steps,
+            force,
+            format,
+        } => command_client.undo(&journal, steps, force, format).await,
+        Command::History { limit } => cli::commands::history(&journal, limit),
         Command::Logout => command_client.logout().await,
+        Command::Completions { .. } | Command::Schema => {
+            unreachable!("Completions and the schema are written before this")
+        }
         Command::Line(line) => match line {
-            Line::Delete { line_number, week } => command_client.delete(&line_number, week).await,
+            Line::Delete {
+                line_number,
+                week,
+                force,
+                yes,
+                format,
+            } => {
+                command_client
+                    .delete(&line_number, week, force, yes, format)
+                    .await
+            }
+            Line::Comment {
+                line_number,
+                comment,
+                week,
+                force,
+                format,
+            } => {
+                command_client
+                    .comment(&line_number, &comment, week, force, format)
+                    .await
+            }
         },
     };
 
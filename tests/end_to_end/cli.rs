@@ -2,8 +2,10 @@ use crate::helpers::{
     config::create_test_config,
     maconomy_mock::{
         mock_add_row, mock_get_instance, mock_get_table_rows, mock_job_number_search,
-        mock_set_hours, mock_set_week, mock_tasks_search, MACONOMY_CONCURRENCY_CONTROL,
+        mock_job_number_search_without_results, mock_set_hours, mock_set_hours_with_body,
+        mock_set_week, mock_tasks_search, MACONOMY_CONCURRENCY_CONTROL,
     },
+    mock_data::get_mock_submitted_table_rows_response,
 };
 use assert_cmd::Command;
 use std::{env, ffi};
@@ -24,10 +26,31 @@ fn run(
 ) -> assert_cmd::Command {
     env::set_var("MACONOMY__MACONOMY_URL", server_url);
     let mut cmd = Command::cargo_bin("maconomy").unwrap();
+    // Keep tests from touching the real completion cache
+    cmd.env(
+        "MACONOMY__COMPLETION_CACHE_PATH",
+        temp_completion_cache_path(),
+    );
+    // ...and from the real journal of changes
+    cmd.env("MACONOMY__JOURNAL_PATH", temp_journal_path());
     cmd.args(args);
     cmd
 }
 
+fn temp_journal_path() -> String {
+    env::temp_dir()
+        .join(format!("maconomy_journal_{}.jsonl", Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn temp_completion_cache_path() -> String {
+    env::temp_dir()
+        .join(format!("maconomy_completion_cache_{}.json", Uuid::new_v4()))
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[tokio::main]
 #[test]
 async fn get_timesheet() {
@@ -39,15 +62,78 @@ async fn get_timesheet() {
     let config = create_test_config(&mock_server.uri());
 
     // When
-    let output = run_json(
-        ["--config", &config, "get", "--format", "json"],
-        &mock_server.uri(),
-    );
+    let command = [
+        "--config", &config, "get", "--format", "json", "--week", "43", "--year", "2024",
+    ];
+    let output = run_json(command, &mock_server.uri());
+
+    // Then
+    insta::assert_json_snapshot!(output);
+}
+
+#[tokio::main]
+#[test]
+async fn get_timesheet_as_csv() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "get", "--format", "csv", "--week", "43", "--year", "2024",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    insta::assert_snapshot!(String::from_utf8_lossy(&output.stdout));
+}
+
+#[tokio::main]
+#[test]
+async fn get_overview_of_weeks() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    // Both weeks get the same time sheet
+    mock_set_week(None).expect(2).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "overview", "--weeks", "42-43", "--year", "2024", "--format", "json",
+    ];
+    let output = run_json(command, &mock_server.uri());
 
     // Then
     insta::assert_json_snapshot!(output);
 }
 
+#[tokio::main]
+#[test]
+async fn export_html_report() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "export", "--weeks", "43", "--year", "2024", "--format", "html",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    let html = String::from_utf8_lossy(&output.stdout);
+    assert!(html.contains("<dt>Employee</dt><dd>John Smith</dd>"));
+    insta::assert_snapshot!(html);
+}
+
 #[tokio::main]
 #[test]
 async fn set_hours() {
@@ -85,6 +171,45 @@ async fn set_hours() {
     output.assert().success();
 }
 
+#[tokio::main]
+#[test]
+async fn set_hours_and_print_json() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "8",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--day",
+        "monday",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--format",
+        "json",
+    ];
+    let output = run_json(command, &mock_server.uri());
+
+    // Then
+    assert_eq!(output["schema_version"], 1);
+    assert_eq!(output["week"], 43);
+    assert_eq!(output["lines"][0]["line_number"], 1);
+    assert_eq!(output["lines"][0]["days"][0]["date"], "2024-10-21");
+}
+
 #[tokio::main]
 #[test]
 async fn set_hours_on_nonexistent_job() {
@@ -178,3 +303,1407 @@ async fn set_hours_on_nonexistent_task() {
         }))
         .failure();
 }
+
+#[tokio::main]
+#[test]
+async fn add_hours() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    // Monday already has 8 hours for this job and task
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday1": 9.5 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "add",
+        "1:30",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--day",
+        "monday",
+        "--week",
+        "42",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn set_hours_on_dates_across_weeks() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    // The weekend between the dates is skipped
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday5": 8.0 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday1": 8.0 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "8",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--date",
+        "2024-10-18..2024-10-21",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn set_hours_on_dates_reports_changed_weeks_on_failure() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday5": 8.0 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_set_hours(Some(wiremock::ResponseTemplate::new(500)))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "8",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--date",
+        "2024-10-18..2024-10-21",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::str::contains(
+            "Week 42, year 2024 was changed before the failure, and keeps its changes",
+        ))
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn subtract_hours_below_zero() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "-8:30",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--day",
+        "monday",
+        "--week",
+        "42",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn list_jobs() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let output = run_json(
+        ["--config", &config, "jobs", "job", "--format", "json"],
+        &mock_server.uri(),
+    );
+
+    // Then
+    insta::assert_json_snapshot!(output);
+}
+
+#[tokio::main]
+#[test]
+async fn list_tasks() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let output = run_json(
+        [
+            "--config", &config, "tasks", "--job", "job one", "--format", "json",
+        ],
+        &mock_server.uri(),
+    );
+
+    // Then
+    insta::assert_json_snapshot!(output);
+}
+
+#[tokio::main]
+#[test]
+async fn set_hours_on_ambiguous_line() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "set", "8", "--job", "job", "--task", "task", "--week", "42",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn completes_job_names_from_listed_jobs() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+    let cache_path = temp_completion_cache_path();
+
+    run(["--config", &config, "jobs"], &mock_server.uri())
+        .env("MACONOMY__COMPLETION_CACHE_PATH", &cache_path)
+        .assert()
+        .success();
+
+    // When
+    let command = ["--", "maconomy", "set", "8", "--job", "jo"];
+    let mut output = run(command, &mock_server.uri());
+    output
+        .env("MACONOMY__COMPLETION_CACHE_PATH", &cache_path)
+        .env("COMPLETE", "fish");
+
+    // Then
+    output.assert().success().stdout("Job One\n");
+}
+
+#[test]
+fn writes_completion_script() {
+    let mut output = run(["completions", "bash"], "");
+
+    output
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("COMPLETE=\"bash\""));
+}
+
+#[tokio::main]
+#[test]
+async fn copy_week_skips_existing_lines() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    // Both weeks get the same lines
+    mock_set_week(None).mount(&mock_server).await;
+    mock_add_row(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "copy",
+        "--from-previous-week",
+        "--with-hours",
+        "--week",
+        "42",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .success();
+}
+
+fn create_config_with_template(uri: &str) -> String {
+    let template = r#"
+        [templates.standard]
+        entries = [
+            { job = "job one", task = "some task one", days = "mon-tue", hours = 8 },
+            { job = "job one", task = "some task two", days = "fri", hours = "4:30" },
+        ]
+    "#;
+    format!("{}{template}", create_test_config(uri))
+}
+
+#[tokio::main]
+#[test]
+async fn apply_template() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let body = serde_json::json!({ "data": { "numberday1": 8.0, "numberday2": 8.0 } });
+    mock_set_hours_with_body(body)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday5": 4.5 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_config_with_template(&mock_server.uri());
+
+    // When
+    let command = ["--config", &config, "apply", "standard", "--week", "42"];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn apply_template_dry_run() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_config_with_template(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "apply",
+        "standard",
+        "--week",
+        "42",
+        "--dry-run",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .success();
+}
+
+#[tokio::main]
+#[test]
+async fn set_hours_dry_run_on_new_line() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    mock_add_row(None).expect(0).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "--dry-run",
+        "set",
+        "4:30",
+        "--job",
+        "job one",
+        "--task",
+        "some task three",
+        "--day",
+        "tue-wed",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .success();
+}
+
+#[tokio::main]
+#[test]
+async fn delete_line_dry_run() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "line",
+        "delete",
+        "1",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--dry-run",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    assert!(output.status.success());
+    // Deleting the line would have been an unmocked request
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1 line(s) deleted"), "{stdout}");
+}
+
+#[tokio::main]
+#[test]
+async fn comment_line_dry_run() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "line",
+        "comment",
+        "1",
+        "Reviewed pull requests",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--dry-run",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    assert!(output.status.success());
+    // Setting the comment would have been an unmocked request
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Reviewed pull requests"), "{stdout}");
+}
+
+#[tokio::main]
+#[test]
+async fn copy_week_dry_run() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_add_row(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "copy",
+        "--from-previous-week",
+        "--with-hours",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--dry-run",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    assert!(output.status.success());
+    // Both weeks have the same lines, so nothing would be copied
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("0 day(s) changed"), "{stdout}");
+}
+
+#[tokio::main]
+#[test]
+async fn submit_warns_about_underfilled_and_overfilled_days() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_config_with_checks(&mock_server.uri(), "max_hours_per_day = \"7:30\"");
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "submit",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--force",
+        "--dry-run",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let warnings: Vec<_> = stderr
+        .lines()
+        .filter(|line| line.starts_with("Warning: "))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            "Warning: Monday has 8:00, more than the maximum of 7:30 (max_hours_per_day)",
+            "Warning: Tuesday has 8:00, more than the maximum of 7:30 (max_hours_per_day)",
+            "Warning: Wednesday has 8:00, more than the maximum of 7:30 (max_hours_per_day)",
+            "Warning: Thursday has 0:00 of regular time, less than the expected 8:00",
+            "Warning: Friday has 0:00 of regular time, less than the expected 8:00",
+        ]
+    );
+}
+
+fn create_config_with_checks(uri: &str, checks: &str) -> String {
+    format!("{}\n[checks]\n{checks}\n", create_test_config(uri))
+}
+
+#[tokio::main]
+#[test]
+async fn check_week_with_violations() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let checks = "reach_norm = true\nno_empty_lines = true";
+    let config = create_config_with_checks(&mock_server.uri(), checks);
+
+    // When
+    let command = [
+        "--config", &config, "check", "--week", "43", "--year", "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout(
+            "Week 43, year 2024 fails 3 check(s):\n  \
+            - Thursday has 0:00, less than the expected 8:00 (reach_norm)\n  \
+            - Friday has 0:00, less than the expected 8:00 (reach_norm)\n  \
+            - Line 2 'Job One', 'Some task two' has no hours (no_empty_lines)\n",
+        )
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn check_week_without_violations() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let checks = "comment_required = [\"^Job One$\"]\nno_weekends = true";
+    let config = create_config_with_checks(&mock_server.uri(), checks);
+
+    // When
+    let command = [
+        "--config", &config, "check", "--week", "43", "--year", "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout("Week 43, year 2024 passes every check\n")
+        .success();
+}
+
+#[tokio::main]
+#[test]
+async fn check_without_checks() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let mut output = run(["--config", &config, "check"], &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr("No checks are configured. Add them to `[checks]` in the configuration\n")
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn submit_refuses_week_that_fails_checks() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_config_with_checks(&mock_server.uri(), "no_empty_lines = true");
+
+    // When
+    let command = [
+        "--config", &config, "submit", "--week", "43", "--year", "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    // Submitting would have been an unmocked request
+    output
+        .assert()
+        .stderr(
+            "  - Line 2 'Job One', 'Some task two' has no hours (no_empty_lines)\n\
+            Week 43, year 2024 fails 1 check(s), so it wasn't submitted. Use `--force` to submit \
+            it anyway\n",
+        )
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn submit_with_force_despite_failing_checks() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    let config = create_config_with_checks(&mock_server.uri(), "no_empty_lines = true");
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "submit",
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--force",
+        "--dry-run",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr
+        .starts_with("Warning: Line 2 'Job One', 'Some task two' has no hours (no_empty_lines)\n"));
+}
+
+#[tokio::main]
+#[test]
+async fn dry_run_of_unsupported_command() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = ["--config", &config, "stop", "--dry-run"];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::str::contains("can't be run with `--dry-run`"))
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn set_hours_with_comment() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday1": 8.0 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "entrytext": "Code review" } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "8",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--day",
+        "monday",
+        "--week",
+        "42",
+        "--comment",
+        "Code review",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn comment_line() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "entrytext": "Planning" } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "line", "comment", "last", "Planning", "--week", "42",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+fn submitted_week_response() -> wiremock::ResponseTemplate {
+    wiremock::ResponseTemplate::new(200)
+        .append_header(MACONOMY_CONCURRENCY_CONTROL, Uuid::new_v4().to_string())
+        .set_body_json(get_mock_submitted_table_rows_response())
+}
+
+#[tokio::main]
+#[test]
+async fn set_hours_on_submitted_week() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(Some(submitted_week_response()))
+        .mount(&mock_server)
+        .await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "8",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--week",
+        "42",
+        "--year",
+        "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .failure();
+}
+
+#[tokio::main]
+#[test]
+async fn force_set_hours_on_submitted_week() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(Some(submitted_week_response()))
+        .mount(&mock_server)
+        .await;
+    mock_set_hours(None).expect(1).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "set",
+        "8",
+        "--job",
+        "job one",
+        "--task",
+        "some task one",
+        "--week",
+        "42",
+        "--force",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn comment_line_on_submitted_week() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(Some(submitted_week_response()))
+        .mount(&mock_server)
+        .await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "line", "comment", "1", "Planning", "--week", "42", "--year", "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::str::contains(
+            "Week 42, year 2024 has been submitted. Use `--force` to change it anyway",
+        ))
+        .failure();
+}
+
+fn create_temp_file(contents: &str) -> String {
+    let path = env::temp_dir().join(format!("maconomy_import_{}.csv", Uuid::new_v4()));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[tokio::main]
+#[test]
+async fn import_entries_dry_run() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    mock_add_row(None).expect(0).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+    let file = create_temp_file(
+        "date,job,task,hours,comment\n\
+         2024-10-22,job one,some task one,2,\n\
+         2024-10-23,job one,some task three,1:30,Planning\n",
+    );
+
+    // When
+    let command = ["--config", &config, "import", &file, "--dry-run"];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    let output = String::from_utf8_lossy(&output.stdout);
+    insta::assert_snapshot!(anstream::adapter::strip_str(&output).to_string());
+}
+
+#[tokio::main]
+#[test]
+async fn import_entries() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    // Both entries go to the same, existing line
+    mock_set_hours_with_body(
+        serde_json::json!({ "data": { "numberday2": 2.0, "numberday3": 4.5 } }),
+    )
+    .expect(1)
+    .mount(&mock_server)
+    .await;
+    let config = create_test_config(&mock_server.uri());
+    let file = create_temp_file(
+        "date,job,task,hours\n\
+         2024-10-22,job one,some task one,2\n\
+         2024-10-23,job one,some task one,4.5\n",
+    );
+
+    // When
+    let command = ["--config", &config, "import", &file];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn import_entries_as_json() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(1).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+    let file = create_temp_file("date,job,task,hours\n2024-10-22,job one,some task one,2\n");
+
+    // When
+    let command = ["--config", &config, "import", &file, "--format", "json"];
+    let output = run_json(command, &mock_server.uri());
+
+    // Then
+    assert_eq!(output["year"], 2024);
+    assert_eq!(output["week"], 43);
+}
+
+#[tokio::main]
+#[test]
+async fn import_entries_with_unknown_job() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    mock_job_number_search_without_results("initech")
+        .mount(&mock_server)
+        .await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    let config = create_test_config(&mock_server.uri());
+    let file = create_temp_file(
+        "date,job,task,hours\n\
+         2024-10-22,job one,some task one,2\n\
+         2024-10-23,initech,development,8\n\
+         2024-10-24,initech,development,8\n",
+    );
+
+    // When
+    let command = ["--config", &config, "import", &file];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stderr(predicates::function::function(move |output: &str| {
+            insta::assert_snapshot!(output);
+            true
+        }))
+        .failure();
+}
+
+const CALENDAR: &str = "\
+BEGIN:VCALENDAR
+VERSION:2.0
+BEGIN:VEVENT
+SUMMARY:Daily standup
+DTSTART:20241022T090000
+DTEND:20241022T091000
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Standup follow-up
+DTSTART:20241022T140000
+DTEND:20241022T142000
+END:VEVENT
+BEGIN:VEVENT
+SUMMARY:Lunch
+DTSTART:20241022T113000
+DTEND:20241022T120000
+END:VEVENT
+END:VCALENDAR
+";
+
+fn create_ics_config(uri: &str) -> String {
+    let rules = r#"
+        [ics]
+        granularity = "0:15"
+        rules = [{ summary = "(?i)standup", job = "job one", task = "some task one" }]
+    "#;
+    format!("{}{rules}", create_test_config(uri))
+}
+
+#[tokio::main]
+#[test]
+async fn import_ics_dry_run() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let config = create_ics_config(&mock_server.uri());
+    let file = create_temp_file(CALENDAR);
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "import-ics",
+        &file,
+        "--week",
+        "43",
+        "--year",
+        "2024",
+        "--dry-run",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+
+    // Then
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    insta::assert_snapshot!(anstream::adapter::strip_str(&stdout).to_string());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Skipped event 'Lunch' on 2024-10-22: no rule applies"));
+}
+
+#[tokio::main]
+#[test]
+async fn import_ics() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    // Both standups are rounded to 15 minutes and summed
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday2": 0.5 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let config = create_ics_config(&mock_server.uri());
+    let file = create_temp_file(CALENDAR);
+
+    // When
+    let command = [
+        "--config",
+        &config,
+        "import-ics",
+        &file,
+        "--week",
+        "43",
+        "--year",
+        "2024",
+    ];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output.assert().success();
+}
+
+#[tokio::main]
+#[test]
+async fn import_timewarrior_export_from_stdin() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_job_number_search(None).mount(&mock_server).await;
+    mock_tasks_search(None).mount(&mock_server).await;
+    // Both intervals are tagged with acme, and go to the same day
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday2": 2.5 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let rules = r#"
+        [timewarrior]
+        rules = [{ tag = "^acme$", job = "job one", task = "some task one" }]
+    "#;
+    let config = format!("{}{rules}", create_test_config(&mock_server.uri()));
+    let export = r#"[
+        {"id": 2, "start": "20241022T080000Z", "end": "20241022T095500Z", "tags": ["acme", "dev"]},
+        {"id": 1, "start": "20241022T130000Z", "end": "20241022T133500Z", "tags": ["acme"]}
+    ]"#;
+
+    // When
+    let command = ["--config", &config, "import", "-", "--from", "timewarrior"];
+    let mut output = run(command, &mock_server.uri());
+    output.env("TZ", "UTC").write_stdin(export);
+
+    // Then
+    output.assert().success();
+}
+
+/// A configuration with the cookie in a directory of its own, where the timer is kept
+fn create_timer_config(uri: &str) -> (String, std::path::PathBuf) {
+    let directory = env::temp_dir().join(format!("maconomy_timer_{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let cookie_path = directory.join("maconomy_cookie");
+    std::fs::copy(
+        "tests/end_to_end/helpers/integration_test_maconomy_cookie",
+        &cookie_path,
+    )
+    .unwrap();
+    let config = create_test_config(uri).replace(
+        "tests/end_to_end/helpers/integration_test_maconomy_cookie",
+        &cookie_path.to_string_lossy(),
+    );
+
+    (config, directory.join("timer.json"))
+}
+
+#[tokio::main]
+#[test]
+async fn start_timer_and_show_status() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let (config, timer_path) = create_timer_config(&mock_server.uri());
+
+    // When
+    let command = [
+        "--config", &config, "start", "--job", "Acme", "--task", "Dev",
+    ];
+    let output = run(command, &mock_server.uri()).unwrap();
+    let status = run(["--config", &config, "status"], &mock_server.uri()).unwrap();
+
+    // Then
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Started timer for 'Acme', 'Dev' at "));
+    let timer: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&timer_path).unwrap()).unwrap();
+    assert_eq!(timer["job"], "Acme");
+    assert_eq!(timer["task"], "Dev");
+    let status = String::from_utf8_lossy(&status.stdout);
+    assert!(status.starts_with("Timer for 'Acme', 'Dev' running since "));
+    assert!(status.ends_with(": 0:00 (books 0:00)\n"));
+}
+
+#[tokio::main]
+#[test]
+async fn stop_timer() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours(None).expect(1).mount(&mock_server).await;
+    let (config, timer_path) = create_timer_config(&mock_server.uri());
+    let started = chrono::Utc::now() - chrono::Duration::minutes(50);
+    let timer = serde_json::json!({
+        "job": "job one",
+        "task": "some task one",
+        "started": started,
+    });
+    std::fs::write(&timer_path, timer.to_string()).unwrap();
+
+    // When
+    let output = run(["--config", &config, "stop"], &mock_server.uri()).unwrap();
+
+    // Then
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "Stopped timer for 'job one', 'some task one' after 0:50, and added 0:45 to today\n"
+    );
+    assert!(!timer_path.exists());
+}
+
+#[tokio::main]
+#[test]
+async fn stop_timer_that_ran_for_several_days() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    // Once per day, where the first day may round to nothing
+    mock_set_hours(None).expect(2..=3).mount(&mock_server).await;
+    let (config, timer_path) = create_timer_config(&mock_server.uri());
+    let started = chrono::Utc::now() - chrono::Duration::hours(30);
+    let timer = serde_json::json!({
+        "job": "job one",
+        "task": "some task two",
+        "started": started,
+    });
+    std::fs::write(&timer_path, timer.to_string()).unwrap();
+
+    // When
+    let output = run(["--config", &config, "stop"], &mock_server.uri()).unwrap();
+
+    // Then
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("Stopped timer for 'job one', 'some task two' after 30:00"));
+    // For example "and added 18:00 to Mon 2024-10-21, 12:00 to today"
+    assert!(stdout.matches(" to ").count() >= 2);
+    assert!(!timer_path.exists());
+}
+
+#[tokio::main]
+#[test]
+async fn discard_timer() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_set_hours(None).expect(0).mount(&mock_server).await;
+    let (config, timer_path) = create_timer_config(&mock_server.uri());
+    let started = chrono::Utc::now() - chrono::Duration::minutes(50);
+    let timer = serde_json::json!({
+        "job": "job one",
+        "task": "some task one",
+        "started": started,
+    });
+    std::fs::write(&timer_path, timer.to_string()).unwrap();
+
+    // When
+    let command = ["--config", &config, "stop", "--discard"];
+    let mut output = run(command, &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout(
+            "Discarded timer for 'job one', 'some task one' after 0:50, without booking anything\n",
+        )
+        .success();
+    assert!(!timer_path.exists());
+}
+
+#[tokio::main]
+#[test]
+async fn stop_without_timer() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let (config, _) = create_timer_config(&mock_server.uri());
+
+    // When
+    let mut output = run(["--config", &config, "stop"], &mock_server.uri());
+
+    // Then
+    output.assert().stderr("No timer is running\n").failure();
+}
+
+/// A config with its own journal of changes, which has been filled with `entries`
+fn create_config_with_journal(uri: &str, entries: &[serde_json::Value]) -> (String, String) {
+    let journal_path = temp_journal_path();
+    let journal: String = entries.iter().map(|entry| format!("{entry}\n")).collect();
+    std::fs::write(&journal_path, journal).unwrap();
+    let config = format!(
+        "journal_path = \"{journal_path}\"\n{}",
+        create_test_config(uri)
+    );
+
+    (config, journal_path)
+}
+
+fn hours_journal_entry(command_started: &str) -> serde_json::Value {
+    let week = |monday: f64| {
+        serde_json::json!({
+            "monday": monday, "tuesday": 0.0, "wednesday": 0.0, "thursday": 0.0,
+            "friday": 0.0, "saturday": 0.0, "sunday": 0.0, "total": monday,
+        })
+    };
+    serde_json::json!({
+        "command_started": command_started,
+        "time": command_started,
+        "week": { "number": 43, "year": 2024 },
+        "change": {
+            "kind": "hours",
+            "line": {
+                "job": "Job One",
+                "task": "Some task one",
+                "job_number": "1234567",
+                "task_short_name": "task one",
+            },
+            "line_number": 0,
+            "before": week(6.0),
+            "after": week(8.0),
+        },
+    })
+}
+
+#[tokio::main]
+#[test]
+async fn show_history() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let entries = [hours_journal_entry("2024-10-21T08:00:00Z")];
+    let (config, _) = create_config_with_journal(&mock_server.uri(), &entries);
+
+    // When
+    let output = run(["--config", &config, "history"], &mock_server.uri()).unwrap();
+
+    // Then
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("1. Mon 2024-10-21 "));
+    assert!(stdout.ends_with(": Set hours of 'Job One', 'Some task one': Monday 6:00 → 8:00\n"));
+}
+
+#[tokio::main]
+#[test]
+async fn show_empty_history() {
+    // Given
+    let mock_server = MockServer::start().await;
+    let (config, _) = create_config_with_journal(&mock_server.uri(), &[]);
+
+    // When
+    let mut output = run(["--config", &config, "history"], &mock_server.uri());
+
+    // Then
+    output
+        .assert()
+        .stdout("No changes have been made with maconomy yet\n")
+        .success();
+}
+
+#[tokio::main]
+#[test]
+async fn undo_set_hours() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday1": 6.0 } }))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    let entries = [
+        hours_journal_entry("2024-10-21T08:00:00Z"),
+        hours_journal_entry("2024-10-21T09:00:00Z"),
+    ];
+    let (config, journal_path) = create_config_with_journal(&mock_server.uri(), &entries);
+
+    // When
+    let output = run(["--config", &config, "undo"], &mock_server.uri()).unwrap();
+
+    // Then
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.ends_with(": Set hours of 'Job One', 'Some task one': Monday 6:00 → 8:00\n"));
+    let journal = std::fs::read_to_string(journal_path).unwrap();
+    assert_eq!(journal.lines().count(), 1);
+    assert!(journal.contains("2024-10-21T08:00:00Z"));
+}
+
+#[tokio::main]
+#[test]
+async fn undo_refuses_hours_changed_since() {
+    // Given
+    let mock_server = MockServer::start().await;
+    mock_get_instance(None).mount(&mock_server).await;
+    mock_get_table_rows(None).mount(&mock_server).await;
+    mock_set_week(None).mount(&mock_server).await;
+    mock_set_hours_with_body(serde_json::json!({ "data": { "numberday1": 6.0 } }))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+    let mut entry = hours_journal_entry("2024-10-21T08:00:00Z");
+    entry["change"]["after"]["monday"] = serde_json::json!(7.0);
+    let (config, journal_path) = create_config_with_journal(&mock_server.uri(), &[entry]);
+
+    // When
+    let mut output = run(["--config", &config, "undo"], &mock_server.uri());
+
+    // Then
+    output.assert().failure().stderr(predicates::str::contains(
+        "The hours of 'Job One', 'Some task one' on Monday have been changed since",
+    ));
+    let journal = std::fs::read_to_string(journal_path).unwrap();
+    assert_eq!(journal.lines().count(), 1);
+}
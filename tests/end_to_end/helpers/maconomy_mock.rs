@@ -77,6 +77,8 @@ pub(crate) fn mock_job_number_search(
             {
               "data": {
                 "jobnumber": "1234567",
+                "jobname": "Job One",
+                "name1": "Customer One"
               }
             }
           ]
@@ -87,6 +89,28 @@ pub(crate) fn mock_job_number_search(
     create_mock(&path_regex, default_body, response)
 }
 
+/// Like `mock_job_number_search`, but only matches searches for `query`, and finds no jobs
+pub(crate) fn mock_job_number_search_without_results(query: &str) -> wiremock::Mock {
+    let path_regex = format!(
+        "/containers/{COMPANY_REGEX}/timeregistration/search/table;foreignkey=notblockedjobnumber_jobheader"
+    );
+    let response = wiremock::ResponseTemplate::new(200)
+        .append_header(MACONOMY_CONCURRENCY_CONTROL, Uuid::new_v4().to_string())
+        .set_body_json(json!({
+          "panes": {
+            "filter": {
+              "meta": { "paneName": "filter", "rowCount": 0, "rowOffset": 0 },
+              "records": []
+            }
+          }
+        }));
+
+    wiremock::Mock::given(method("POST"))
+        .and(wiremock::matchers::path_regex(path_regex))
+        .and(wiremock::matchers::body_string_contains(query))
+        .respond_with(response)
+}
+
 pub(crate) fn mock_tasks_search(response: Option<wiremock::ResponseTemplate>) -> wiremock::Mock {
     let path_regex = format!(
         "/containers/{COMPANY_REGEX}/timeregistration/search/table;foreignkey=taskname_tasklistline"
@@ -152,3 +176,18 @@ pub(crate) fn mock_set_hours(response: Option<wiremock::ResponseTemplate>) -> wi
 
     create_mock(&path_regex, default_body, response)
 }
+
+/// Like `mock_set_hours`, but only matches requests with body `expected_body`
+pub(crate) fn mock_set_hours_with_body(expected_body: serde_json::Value) -> wiremock::Mock {
+    let path_regex = format!(
+        "/containers/{COMPANY_REGEX}/timeregistration/instances/{UUID_REGEX}/data/panes/table/{ROW_NUMBER_REGEX}$"
+    );
+    let response = wiremock::ResponseTemplate::new(200)
+        .append_header(MACONOMY_CONCURRENCY_CONTROL, Uuid::new_v4().to_string())
+        .set_body_json(get_mock_table_rows_response());
+
+    wiremock::Mock::given(method("POST"))
+        .and(wiremock::matchers::path_regex(path_regex))
+        .and(wiremock::matchers::body_json(expected_body))
+        .respond_with(response)
+}
@@ -40,7 +40,9 @@ pub(crate) fn get_mock_table_rows_response() -> serde_json::Value {
                 "regulartimeday4var": 0,
                 "regulartimeday5var": 0,
                 "regulartimeday6var": 0,
-                "regulartimeday7var": 0
+                "regulartimeday7var": 0,
+                "submitted": false,
+                "approvalstatus": ""
               }
             }
           ]
@@ -54,7 +56,7 @@ pub(crate) fn get_mock_table_rows_response() -> serde_json::Value {
           "records": [
             {
               "data": {
-                "jobnumber": "ABC123",
+                "jobnumber": "1234567",
                 "numberday1": 8,
                 "numberday2": 0,
                 "numberday3": 0,
@@ -62,8 +64,8 @@ pub(crate) fn get_mock_table_rows_response() -> serde_json::Value {
                 "numberday5": 0,
                 "numberday6": 0,
                 "numberday7": 0,
-                "entrytext": "Some task one",
-                "taskname": "300",
+                "entrytext": "Fixed bugs",
+                "taskname": "task one",
                 "instancekey": "1579ecb8-7773-4b69-b3ff-116da9dee8d8",
                 "timeregistrationunit": "hours",
                 "jobnamevar": "Job One",
@@ -72,7 +74,7 @@ pub(crate) fn get_mock_table_rows_response() -> serde_json::Value {
             },
             {
               "data": {
-                "jobnumber": "DEF456",
+                "jobnumber": "1234567",
                 "numberday1": 0,
                 "numberday2": 0,
                 "numberday3": 0,
@@ -80,8 +82,8 @@ pub(crate) fn get_mock_table_rows_response() -> serde_json::Value {
                 "numberday5": 0,
                 "numberday6": 0,
                 "numberday7": 0,
-                "entrytext": "Job Two",
-                "taskname": "Some task two",
+                "entrytext": "",
+                "taskname": "task two",
                 "instancekey": "265123e0-a069-44d2-bd60-8706f1a7d9b9",
                 "timeregistrationunit": "hours",
                 "jobnamevar": "Job One",
@@ -93,3 +95,10 @@ pub(crate) fn get_mock_table_rows_response() -> serde_json::Value {
       }
     })
 }
+
+/// Like `get_mock_table_rows_response`, but for a week that has been submitted
+pub(crate) fn get_mock_submitted_table_rows_response() -> serde_json::Value {
+    let mut response = get_mock_table_rows_response();
+    response["panes"]["card"]["records"][0]["data"]["submitted"] = true.into();
+    response
+}